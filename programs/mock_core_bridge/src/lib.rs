@@ -0,0 +1,189 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+declare_id!("FhPvfahh85mVY5iBqEG3YnbcVkMX31USepxrQtTC857s");
+
+/// Chain id this bridge reports for Solana.
+pub const CHAIN_ID_SOLANA: u16 = 1;
+pub const CONFIG_SEED: &str = "Bridge";
+pub const SEQUENCE_SEED: &str = "Sequence";
+pub const MESSAGE_SEED: &str = "Message";
+pub const POSTED_VAA_SEED: &str = "PostedVAA";
+
+/// Records outbound messages and lets a single guardian post inbound VAAs.
+/// The guardian's signature stands in for the guardian set quorum of a real
+/// core bridge. Implements receipt_money's own core bridge interface, not
+/// Wormhole's account and instruction formats. Only meant for local tests.
+#[program]
+pub mod mock_core_bridge {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>, guardian: Pubkey) -> Result<()> {
+        ctx.accounts.config.guardian = guardian;
+        ctx.accounts.config.bump = ctx.bumps.config;
+        Ok(())
+    }
+
+    pub fn post_message(
+        ctx: Context<PostMessage>,
+        nonce: u32,
+        payload: Vec<u8>,
+        consistency_level: u8,
+    ) -> Result<()> {
+        let sequence = &mut ctx.accounts.sequence;
+        let message = &mut ctx.accounts.message;
+        message.emitter = ctx.accounts.emitter.key();
+        message.sequence = sequence.next;
+        message.nonce = nonce;
+        message.consistency_level = consistency_level;
+        message.posted_at = Clock::get()?.unix_timestamp;
+        message.payload = payload;
+        sequence.next += 1;
+        Ok(())
+    }
+
+    pub fn post_vaa(
+        ctx: Context<PostVaa>,
+        emitter_chain: u16,
+        emitter_address: [u8; 32],
+        sequence: u64,
+        payload: Vec<u8>,
+    ) -> Result<()> {
+        let posted_vaa = &mut ctx.accounts.posted_vaa;
+        posted_vaa.emitter_chain = emitter_chain;
+        posted_vaa.emitter_address = emitter_address;
+        posted_vaa.sequence = sequence;
+        posted_vaa.posted_at = Clock::get()?.unix_timestamp;
+        posted_vaa.payload = payload;
+        Ok(())
+    }
+}
+
+/// Hash identifying a VAA body, the seed of its `PostedVaa` account.
+pub fn vaa_hash(emitter_chain: u16, emitter_address: &[u8; 32], sequence: u64, payload: &[u8]) -> [u8; 32] {
+    hashv(&[
+        &emitter_chain.to_le_bytes(),
+        emitter_address,
+        &sequence.to_le_bytes(),
+        payload,
+    ])
+    .to_bytes()
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump,
+        payer = payer,
+        space = 8 + BridgeConfig::INIT_SPACE,
+    )]
+    pub config: Account<'info, BridgeConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u32, payload: Vec<u8>)]
+pub struct PostMessage<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub emitter: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        seeds = [SEQUENCE_SEED.as_bytes(), emitter.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + Sequence::INIT_SPACE,
+    )]
+    pub sequence: Account<'info, Sequence>,
+
+    #[account(
+        init,
+        seeds = [MESSAGE_SEED.as_bytes(), emitter.key().as_ref(), &sequence.next.to_le_bytes()],
+        bump,
+        payer = payer,
+        space = PostedMessage::space(payload.len()),
+    )]
+    pub message: Account<'info, PostedMessage>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(emitter_chain: u16, emitter_address: [u8; 32], sequence: u64, payload: Vec<u8>)]
+pub struct PostVaa<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub guardian: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED.as_bytes()], bump = config.bump, has_one = guardian)]
+    pub config: Account<'info, BridgeConfig>,
+
+    #[account(
+        init,
+        seeds = [
+            POSTED_VAA_SEED.as_bytes(),
+            &vaa_hash(emitter_chain, &emitter_address, sequence, &payload),
+        ],
+        bump,
+        payer = payer,
+        space = PostedVaa::space(payload.len()),
+    )]
+    pub posted_vaa: Account<'info, PostedVaa>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct BridgeConfig {
+    pub guardian: Pubkey,
+    pub bump: u8,
+}
+
+/// Next sequence number of an emitter.
+#[account]
+#[derive(InitSpace)]
+pub struct Sequence {
+    pub next: u64,
+}
+
+/// An outbound message, picked up by the guardians off-chain.
+#[account]
+pub struct PostedMessage {
+    pub emitter: Pubkey,
+    pub sequence: u64,
+    pub nonce: u32,
+    pub consistency_level: u8,
+    pub posted_at: i64,
+    pub payload: Vec<u8>,
+}
+
+impl PostedMessage {
+    pub fn space(payload_len: usize) -> usize {
+        8 + 32 + 8 + 4 + 1 + 8 + 4 + payload_len
+    }
+}
+
+/// An inbound message the guardians attested to.
+#[account]
+pub struct PostedVaa {
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub sequence: u64,
+    pub posted_at: i64,
+    pub payload: Vec<u8>,
+}
+
+impl PostedVaa {
+    pub fn space(payload_len: usize) -> usize {
+        8 + 2 + 32 + 8 + 8 + 4 + payload_len
+    }
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("98ExDuHzFz6zcH1NvxsGNpfB9rH46KL75gZpGhEUKanJ");
+
+pub const POOL_AUTHORITY_SEED: &str = "pool_authority";
+
+/// Swaps at whatever amounts the caller asks for, paying out of token
+/// accounts owned by the pool authority PDA. Only meant for local tests.
+#[program]
+pub mod mock_swap {
+    use super::*;
+
+    pub fn swap(ctx: Context<Swap>, amount_in: u64, amount_out: u64) -> Result<()> {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_source.to_account_info(),
+                    to: ctx.accounts.pool_source.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            amount_in,
+        )?;
+        let signer_seeds: &[&[&[u8]]] = &[&[POOL_AUTHORITY_SEED.as_bytes(), &[ctx.bumps.pool_authority]]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.pool_destination.to_account_info(),
+                    to: ctx.accounts.user_destination.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_out,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    pub user: Signer<'info>,
+
+    #[account(mut, token::mint = source_mint, token::authority = user)]
+    pub user_source: Account<'info, TokenAccount>,
+
+    #[account(mut, token::mint = destination_mint)]
+    pub user_destination: Account<'info, TokenAccount>,
+
+    #[account(mut, token::mint = source_mint, token::authority = pool_authority)]
+    pub pool_source: Account<'info, TokenAccount>,
+
+    #[account(mut, token::mint = destination_mint, token::authority = pool_authority)]
+    pub pool_destination: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA that owns the pool token accounts
+    #[account(seeds = [POOL_AUTHORITY_SEED.as_bytes()], bump)]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub source_mint: Account<'info, Mint>,
+    pub destination_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum HookErrorCode {
+    /// The signer is not the mint authority of the receipt mint.
+    #[msg("InvalidMintAuthority")]
+    InvalidMintAuthority,
+    /// The hook was invoked outside of a Token-2022 transfer.
+    #[msg("NotTransferring")]
+    NotTransferring,
+    /// The source or destination owner is not on the allowlist.
+    #[msg("NotAllowlisted")]
+    NotAllowlisted,
+}
@@ -0,0 +1,460 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{hash::hash, instruction::Instruction, program::invoke_signed},
+    system_program::{create_account, CreateAccount},
+};
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        extension::{
+            transfer_hook::TransferHookAccount, BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::Account as SplAccount,
+    },
+    token_interface::{Mint, TokenAccount},
+};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+pub mod errors;
+pub mod state;
+
+use errors::HookErrorCode;
+use state::{AllowlistEntry, HookConfig};
+
+declare_id!("AWNcxfuBcvrdsXRRALHoPihuHTXrYsVGKrvw3uA9Wrra");
+
+pub const RECEIPT_MONEY_PROGRAM_ID: Pubkey = pubkey!("RMcr2nvyrwCh89SvH47916S9TCvPkoGBPNR8E1d1LWa");
+pub const EXTRA_ACCOUNT_METAS_SEED: &str = "extra-account-metas";
+/// Signs the position updates this program forwards to receipt_money.
+pub const HOOK_AUTHORITY_SEED: &str = "hook_authority";
+/// Must match `UserPosition::USER_POSITION_SEED` in receipt_money.
+pub const USER_POSITION_SEED: &str = "user_position";
+/// Must match `ReceiptState::VAULT_AUTHORITY_SEED` in receipt_money.
+pub const VAULT_AUTHORITY_SEED: &str = "receipt_vault_authority";
+
+/// Transfer hook for receipt mints. Enforces an optional allowlist and keeps
+/// receipt_money `UserPosition` accounts in sync with receipt transfers.
+#[program]
+pub mod receipt_hook {
+    use super::*;
+
+    /// Called by receipt_money while it still holds the mint authority.
+    pub fn initialize_extra_account_meta_list(
+        ctx: Context<InitializeExtraAccountMetaList>,
+        authority: Pubkey,
+    ) -> Result<()> {
+        let extra_account_metas = extra_account_metas(
+            &ctx.accounts.receipt_state.key(),
+            &ctx.accounts.token_mint_vault.key(),
+        )?;
+        let mint_key = ctx.accounts.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            EXTRA_ACCOUNT_METAS_SEED.as_bytes(),
+            mint_key.as_ref(),
+            &[ctx.bumps.extra_account_meta_list],
+        ]];
+        let space = ExtraAccountMetaList::size_of(extra_account_metas.len())?;
+        create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                CreateAccount {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: ctx.accounts.extra_account_meta_list.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &crate::ID,
+        )?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(
+            &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
+            &extra_account_metas,
+        )?;
+
+        let hook_config = &mut ctx.accounts.hook_config;
+        hook_config.authority = authority;
+        hook_config.mint = mint_key;
+        hook_config.receipt_state = ctx.accounts.receipt_state.key();
+        hook_config.token_mint_vault = ctx.accounts.token_mint_vault.key();
+        hook_config.allowlist_enabled = false;
+        hook_config.bump = ctx.bumps.hook_config;
+        Ok(())
+    }
+
+    pub fn set_allowlist_enabled(ctx: Context<SetAllowlistEnabled>, enabled: bool) -> Result<()> {
+        ctx.accounts.hook_config.allowlist_enabled = enabled;
+        Ok(())
+    }
+
+    /// Hands the allowlist to `new_authority`, e.g. after the market authority moved.
+    pub fn set_hook_authority(ctx: Context<SetHookAuthority>, new_authority: Pubkey) -> Result<()> {
+        ctx.accounts.hook_config.authority = new_authority;
+        Ok(())
+    }
+
+    pub fn add_to_allowlist(ctx: Context<AddToAllowlist>) -> Result<()> {
+        let allowlist_entry = &mut ctx.accounts.allowlist_entry;
+        allowlist_entry.mint = ctx.accounts.hook_config.mint;
+        allowlist_entry.wallet = ctx.accounts.wallet.key();
+        allowlist_entry.bump = ctx.bumps.allowlist_entry;
+        Ok(())
+    }
+
+    pub fn remove_from_allowlist(_ctx: Context<RemoveFromAllowlist>) -> Result<()> {
+        Ok(())
+    }
+
+    #[interface(spl_transfer_hook_interface::execute)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        // Only Token-2022 may drive position updates, never a direct call
+        assert_is_transferring(&ctx.accounts.source_token.to_account_info())?;
+
+        // Receipts staked, posted as collateral or escrowed by the market stay
+        // with their owner's position, and the market itself needs no allowlisting
+        let (market_authority, _) = Pubkey::find_program_address(
+            &[VAULT_AUTHORITY_SEED.as_bytes(), ctx.accounts.receipt_state.key.as_ref()],
+            &RECEIPT_MONEY_PROGRAM_ID,
+        );
+        let source_is_market = ctx.accounts.source_token.owner == market_authority;
+        let destination_is_market = ctx.accounts.destination_token.owner == market_authority;
+
+        if ctx.accounts.hook_config.allowlist_enabled {
+            require!(
+                (source_is_market || is_initialized(&ctx.accounts.source_allowlist, &crate::ID))
+                    && (destination_is_market
+                        || is_initialized(&ctx.accounts.destination_allowlist, &crate::ID)),
+                HookErrorCode::NotAllowlisted
+            );
+        }
+
+        let source_tracked = is_initialized(&ctx.accounts.source_position, &RECEIPT_MONEY_PROGRAM_ID);
+        let destination_tracked =
+            is_initialized(&ctx.accounts.destination_position, &RECEIPT_MONEY_PROGRAM_ID);
+        let same_owner = ctx.accounts.source_token.owner == ctx.accounts.destination_token.owner;
+        if same_owner
+            || source_is_market
+            || destination_is_market
+            || !(source_tracked || destination_tracked)
+        {
+            return Ok(());
+        }
+        ctx.accounts.transfer_position(amount, source_tracked, destination_tracked, ctx.bumps.hook_authority)
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub mint_authority: Signer<'info>,
+
+    #[account(
+        constraint = mint.mint_authority == Some(mint_authority.key()).into()
+            @ HookErrorCode::InvalidMintAuthority,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: receipt_money market of `mint`, only its key is recorded
+    #[account(owner = RECEIPT_MONEY_PROGRAM_ID)]
+    pub receipt_state: UncheckedAccount<'info>,
+
+    /// CHECK: Underlying vault of the market, only its key is recorded
+    pub token_mint_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Created in the handler, its size depends on the meta list
+    #[account(
+        mut,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [HookConfig::HOOK_CONFIG_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = HookConfig::LEN,
+    )]
+    pub hook_config: Box<Account<'info, HookConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAllowlistEnabled<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority)]
+    pub hook_config: Box<Account<'info, HookConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct SetHookAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority)]
+    pub hook_config: Box<Account<'info, HookConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct AddToAllowlist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority)]
+    pub hook_config: Box<Account<'info, HookConfig>>,
+
+    /// CHECK: Wallet allowed to send and receive receipts
+    pub wallet: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [
+            AllowlistEntry::ALLOWLIST_SEED.as_bytes(),
+            hook_config.mint.as_ref(),
+            wallet.key().as_ref(),
+        ],
+        bump,
+        payer = authority,
+        space = AllowlistEntry::LEN,
+    )]
+    pub allowlist_entry: Box<Account<'info, AllowlistEntry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFromAllowlist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority)]
+    pub hook_config: Box<Account<'info, HookConfig>>,
+
+    #[account(
+        mut,
+        close = authority,
+        constraint = allowlist_entry.mint == hook_config.mint,
+    )]
+    pub allowlist_entry: Box<Account<'info, AllowlistEntry>>,
+}
+
+/// Account order follows the transfer hook interface, then `extra_account_metas`.
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(token::mint = mint)]
+    pub destination_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Owner or delegate of the source, checked by Token-2022
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by Token-2022 when resolving the extra accounts
+    #[account(seeds = [EXTRA_ACCOUNT_METAS_SEED.as_bytes(), mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [HookConfig::HOOK_CONFIG_SEED.as_bytes(), mint.key().as_ref()],
+        bump = hook_config.bump,
+    )]
+    pub hook_config: Box<Account<'info, HookConfig>>,
+
+    /// CHECK: Allowlist entry of the source owner, may not exist
+    #[account(
+        seeds = [
+            AllowlistEntry::ALLOWLIST_SEED.as_bytes(),
+            mint.key().as_ref(),
+            source_token.owner.as_ref(),
+        ],
+        bump,
+    )]
+    pub source_allowlist: UncheckedAccount<'info>,
+
+    /// CHECK: Allowlist entry of the destination owner, may not exist
+    #[account(
+        seeds = [
+            AllowlistEntry::ALLOWLIST_SEED.as_bytes(),
+            mint.key().as_ref(),
+            destination_token.owner.as_ref(),
+        ],
+        bump,
+    )]
+    pub destination_allowlist: UncheckedAccount<'info>,
+
+    /// CHECK: receipt_money program
+    #[account(address = RECEIPT_MONEY_PROGRAM_ID)]
+    pub receipt_money_program: UncheckedAccount<'info>,
+
+    /// CHECK: Checked against the hook config
+    #[account(address = hook_config.receipt_state)]
+    pub receipt_state: UncheckedAccount<'info>,
+
+    /// CHECK: Checked against the hook config
+    #[account(address = hook_config.token_mint_vault)]
+    pub token_mint_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Position of the source owner, may not exist
+    #[account(
+        mut,
+        seeds = [
+            USER_POSITION_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            source_token.owner.as_ref(),
+        ],
+        bump,
+        seeds::program = RECEIPT_MONEY_PROGRAM_ID,
+    )]
+    pub source_position: UncheckedAccount<'info>,
+
+    /// CHECK: Position of the destination owner, may not exist
+    #[account(
+        mut,
+        seeds = [
+            USER_POSITION_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            destination_token.owner.as_ref(),
+        ],
+        bump,
+        seeds::program = RECEIPT_MONEY_PROGRAM_ID,
+    )]
+    pub destination_position: UncheckedAccount<'info>,
+
+    /// CHECK: PDA signer for receipt_money
+    #[account(seeds = [HOOK_AUTHORITY_SEED.as_bytes()], bump)]
+    pub hook_authority: UncheckedAccount<'info>,
+}
+
+impl<'info> TransferHook<'info> {
+    /// Moves the cost basis of `amount` receipts between positions through
+    /// receipt_money `transfer_position`. A missing position is passed as the
+    /// receipt_money program id, Anchor's encoding of an absent optional account.
+    fn transfer_position(
+        &self,
+        amount: u64,
+        source_tracked: bool,
+        destination_tracked: bool,
+        hook_authority_bump: u8,
+    ) -> Result<()> {
+        let position_meta = |position: &UncheckedAccount<'info>, tracked: bool| {
+            if tracked {
+                AccountMeta::new(position.key(), false)
+            } else {
+                AccountMeta::new_readonly(RECEIPT_MONEY_PROGRAM_ID, false)
+            }
+        };
+        let mut data = hash(b"global:transfer_position").to_bytes()[..8].to_vec();
+        data.extend_from_slice(&amount.to_le_bytes());
+        let ix = Instruction {
+            program_id: RECEIPT_MONEY_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(self.hook_authority.key(), true),
+                AccountMeta::new_readonly(self.receipt_state.key(), false),
+                AccountMeta::new_readonly(self.mint.key(), false),
+                AccountMeta::new_readonly(self.token_mint_vault.key(), false),
+                position_meta(&self.source_position, source_tracked),
+                position_meta(&self.destination_position, destination_tracked),
+            ],
+            data,
+        };
+        invoke_signed(
+            &ix,
+            &[
+                self.hook_authority.to_account_info(),
+                self.receipt_state.to_account_info(),
+                self.mint.to_account_info(),
+                self.token_mint_vault.to_account_info(),
+                self.source_position.to_account_info(),
+                self.destination_position.to_account_info(),
+                self.receipt_money_program.to_account_info(),
+            ],
+            &[&[HOOK_AUTHORITY_SEED.as_bytes(), &[hook_authority_bump]]],
+        )
+        .map_err(Into::into)
+    }
+}
+
+/// Extra accounts of `TransferHook` after the interface accounts. Indices
+/// 0-4 are source, mint, destination, owner and the meta list itself.
+pub fn extra_account_metas(
+    receipt_state: &Pubkey,
+    token_mint_vault: &Pubkey,
+) -> Result<Vec<ExtraAccountMeta>> {
+    let owner_of = |account_index: u8| Seed::AccountData {
+        account_index,
+        data_index: 32,
+        length: 32,
+    };
+    let position = |owner_index: u8| {
+        ExtraAccountMeta::new_external_pda_with_seeds(
+            8, // receipt_money_program
+            &[
+                Seed::Literal { bytes: USER_POSITION_SEED.as_bytes().to_vec() },
+                Seed::AccountKey { index: 9 }, // receipt_state
+                owner_of(owner_index),
+            ],
+            false,
+            true,
+        )
+    };
+    let allowlist = |owner_index: u8| {
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: AllowlistEntry::ALLOWLIST_SEED.as_bytes().to_vec() },
+                Seed::AccountKey { index: 1 },
+                owner_of(owner_index),
+            ],
+            false,
+            false,
+        )
+    };
+    Ok(vec![
+        // 5: hook_config
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: HookConfig::HOOK_CONFIG_SEED.as_bytes().to_vec() },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            false,
+        )?,
+        // 6: source_allowlist
+        allowlist(0)?,
+        // 7: destination_allowlist
+        allowlist(2)?,
+        // 8: receipt_money_program
+        ExtraAccountMeta::new_with_pubkey(&RECEIPT_MONEY_PROGRAM_ID, false, false)?,
+        // 9: receipt_state
+        ExtraAccountMeta::new_with_pubkey(receipt_state, false, false)?,
+        // 10: token_mint_vault
+        ExtraAccountMeta::new_with_pubkey(token_mint_vault, false, false)?,
+        // 11: source_position
+        position(0)?,
+        // 12: destination_position
+        position(2)?,
+        // 13: hook_authority
+        ExtraAccountMeta::new_with_seeds(
+            &[Seed::Literal { bytes: HOOK_AUTHORITY_SEED.as_bytes().to_vec() }],
+            false,
+            false,
+        )?,
+    ])
+}
+
+fn assert_is_transferring(source_token: &AccountInfo) -> Result<()> {
+    let data = source_token.try_borrow_data()?;
+    let account = StateWithExtensions::<SplAccount>::unpack(&data)?;
+    let extension = account.get_extension::<TransferHookAccount>()?;
+    require!(bool::from(extension.transferring), HookErrorCode::NotTransferring);
+    Ok(())
+}
+
+fn is_initialized(account: &AccountInfo, owner: &Pubkey) -> bool {
+    account.owner == owner && !account.data_is_empty()
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+/// Hook settings of one receipt mint.
+#[account]
+pub struct HookConfig {
+    /// Manages the allowlist, the receipt market authority at creation.
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub receipt_state: Pubkey,
+    pub token_mint_vault: Pubkey,
+    /// When set, both sides of a transfer need an `AllowlistEntry`.
+    pub allowlist_enabled: bool,
+    pub bump: u8,
+}
+
+impl HookConfig {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // authority
+        32 + // mint
+        32 + // receipt_state
+        32 + // token_mint_vault
+        1 + // allowlist_enabled
+        1; // bump
+
+    pub const HOOK_CONFIG_SEED: &'static str = "hook_config";
+}
+
+/// A wallet allowed to hold and move receipts of `mint`.
+#[account]
+pub struct AllowlistEntry {
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+    pub bump: u8,
+}
+
+impl AllowlistEntry {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        32 + // wallet
+        1; // bump
+
+    pub const ALLOWLIST_SEED: &'static str = "allowlist";
+}
@@ -6,4 +6,219 @@ pub enum ReceiptErrorCode {
     /// The input token is invalid for swap.
     #[msg("InvalidInput")]
     InvalidInput,
-}
\ No newline at end of file
+    /// Arithmetic overflowed or divided by zero.
+    #[msg("MathOverflow")]
+    MathOverflow,
+    /// The market has no oracle configured.
+    #[msg("OracleNotConfigured")]
+    OracleNotConfigured,
+    /// The oracle account is not the configured one or is not a valid price account.
+    #[msg("InvalidOracle")]
+    InvalidOracle,
+    /// The oracle price is older than the configured staleness.
+    #[msg("StaleOracle")]
+    StaleOracle,
+    /// The oracle confidence interval is wider than allowed.
+    #[msg("OracleConfidenceTooWide")]
+    OracleConfidenceTooWide,
+    /// Flash loans are disabled for this market.
+    #[msg("FlashLoanDisabled")]
+    FlashLoanDisabled,
+    /// A flash loan is already in flight for this market.
+    #[msg("FlashLoanInProgress")]
+    FlashLoanInProgress,
+    /// There is no flash loan to repay.
+    #[msg("NoFlashLoanOutstanding")]
+    NoFlashLoanOutstanding,
+    /// The flash borrow is not followed by a matching flash repay.
+    #[msg("MissingFlashRepay")]
+    MissingFlashRepay,
+    /// Flash borrows must be top-level instructions.
+    #[msg("FlashLoanCpiNotAllowed")]
+    FlashLoanCpiNotAllowed,
+    /// A basis points value is above 100%.
+    #[msg("InvalidBasisPoints")]
+    InvalidBasisPoints,
+    /// `swap_and_deposit` requires the `jupiter-cpi` feature.
+    #[msg("SwapNotEnabled")]
+    SwapNotEnabled,
+    /// The swap program is not the one configured for this market.
+    #[msg("InvalidSwapProgram")]
+    InvalidSwapProgram,
+    /// The swap spent more or returned less than the caller allowed.
+    #[msg("SlippageExceeded")]
+    SlippageExceeded,
+    /// The market holds less underlying than receipts outstanding.
+    #[msg("Undercollateralized")]
+    Undercollateralized,
+    /// The vault or lending reserve does not hold enough liquidity.
+    #[msg("InsufficientLiquidity")]
+    InsufficientLiquidity,
+    /// The receipt state already uses the current layout.
+    #[msg("AlreadyMigrated")]
+    AlreadyMigrated,
+    /// The market still has receipts or vault balances outstanding.
+    #[msg("MarketNotEmpty")]
+    MarketNotEmpty,
+    /// Classic spl token receipt mints need the Metaplex metadata accounts.
+    #[msg("MissingMetadataAccounts")]
+    MissingMetadataAccounts,
+    /// The fee receiver is missing or is not the configured one.
+    #[msg("InvalidFeeReceiver")]
+    InvalidFeeReceiver,
+    /// The referrer token account is missing or not owned by the referrer.
+    #[msg("InvalidReferrer")]
+    InvalidReferrer,
+    /// Transfer hooks need a Token-2022 receipt mint and all hook accounts.
+    #[msg("InvalidTransferHook")]
+    InvalidTransferHook,
+    /// Interest-bearing display needs a Token-2022 receipt mint.
+    #[msg("InterestBearingNotSupported")]
+    InterestBearingNotSupported,
+    /// The receipt mint rate was updated too recently.
+    #[msg("InterestRateUpdateTooSoon")]
+    InterestRateUpdateTooSoon,
+    /// Confidential transfers need a Token-2022 receipt mint.
+    #[msg("ConfidentialTransferNotSupported")]
+    ConfidentialTransferNotSupported,
+    /// Redemptions burn from the public balance, confidential receipts have
+    /// to be withdrawn to it first.
+    #[msg("InsufficientPublicBalance")]
+    InsufficientPublicBalance,
+    /// The market is timelocked, admin changes go through proposals.
+    #[msg("TimelockActive")]
+    TimelockActive,
+    /// Proposals need a timelocked market.
+    #[msg("TimelockNotEnabled")]
+    TimelockNotEnabled,
+    /// The timelock delay is negative or above `Proposal::MAX_DELAY`.
+    #[msg("InvalidTimelockDelay")]
+    InvalidTimelockDelay,
+    /// The proposal's eta has not been reached.
+    #[msg("ProposalNotReady")]
+    ProposalNotReady,
+    /// The proposal was not executed within its grace period.
+    #[msg("ProposalExpired")]
+    ProposalExpired,
+    /// The signer is neither the guardian nor the authority.
+    #[msg("Unauthorized")]
+    Unauthorized,
+    /// Duplicate signers, too many signers, or a threshold out of range.
+    #[msg("InvalidMultisigConfig")]
+    InvalidMultisigConfig,
+    /// The signer is not part of the multisig.
+    #[msg("NotMultisigSigner")]
+    NotMultisigSigner,
+    /// The multisig signer set changed after the transaction was created.
+    #[msg("StaleMultisigTransaction")]
+    StaleMultisigTransaction,
+    /// The multisig transaction already ran.
+    #[msg("AlreadyExecuted")]
+    AlreadyExecuted,
+    /// The multisig transaction is below its threshold.
+    #[msg("NotEnoughApprovals")]
+    NotEnoughApprovals,
+    /// A flow limit tripped, deposits and redemptions wait for the guardian.
+    #[msg("MarketPaused")]
+    MarketPaused,
+    /// The market already has the maximum number of reward pools.
+    #[msg("TooManyRewardPools")]
+    TooManyRewardPools,
+    /// Reward pools are missing, out of order, or belong to another market.
+    #[msg("InvalidRewardPool")]
+    InvalidRewardPool,
+    /// The emission end is not in the future or emits nothing per second.
+    #[msg("InvalidEmissionSchedule")]
+    InvalidEmissionSchedule,
+    /// Unstaking more receipts than the position holds.
+    #[msg("InsufficientStake")]
+    InsufficientStake,
+    /// Zero or too many leaves, an expiry in the past, or claims beyond the funded total.
+    #[msg("InvalidDistribution")]
+    InvalidDistribution,
+    /// The proof does not lead from the claimed leaf to the merkle root.
+    #[msg("InvalidMerkleProof")]
+    InvalidMerkleProof,
+    /// The leaf was already claimed.
+    #[msg("AlreadyClaimed")]
+    AlreadyClaimed,
+    /// Claims closed at the distribution's expiry.
+    #[msg("DistributionExpired")]
+    DistributionExpired,
+    /// Clawback waits for the distribution's expiry.
+    #[msg("DistributionNotExpired")]
+    DistributionNotExpired,
+    /// The bridge mode cannot change while receipts are bridged out.
+    #[msg("InvalidBridgeConfig")]
+    InvalidBridgeConfig,
+    /// The market has no core bridge program.
+    #[msg("BridgeNotConfigured")]
+    BridgeNotConfigured,
+    /// The VAA is not a posted core bridge VAA carrying a transfer for this market and chain.
+    #[msg("InvalidBridgeMessage")]
+    InvalidBridgeMessage,
+    /// The VAA comes from an emitter not registered for its chain.
+    #[msg("UnknownEmitter")]
+    UnknownEmitter,
+    /// LTV above the liquidation threshold, or a threshold and bonus that let liquidations seize more than they repay.
+    #[msg("InvalidLendingConfig")]
+    InvalidLendingConfig,
+    /// The borrow or withdrawal would take the debt above the reserve's LTV.
+    #[msg("BorrowLimitExceeded")]
+    BorrowLimitExceeded,
+    /// The obligation is above its liquidation threshold.
+    #[msg("ObligationHealthy")]
+    ObligationHealthy,
+    /// The liquidation repays more than the close factor allows.
+    #[msg("LiquidationTooLarge")]
+    LiquidationTooLarge,
+    /// Withdrawing more collateral than the obligation holds.
+    #[msg("InsufficientCollateral")]
+    InsufficientCollateral,
+    /// Leverage needs a reserve that lends the market's underlying.
+    #[msg("LeverageUnavailable")]
+    LeverageUnavailable,
+    /// The target leverage is below 1x or above what the reserve's LTV allows.
+    #[msg("InvalidLeverage")]
+    InvalidLeverage,
+    /// Not an SPL stake pool account, or not the market's configured pool.
+    #[msg("InvalidStakePool")]
+    InvalidStakePool,
+    /// The stake pool, or the market's value of its tokens, has not been
+    /// updated for the current epoch.
+    #[msg("StaleStakePool")]
+    StaleStakePool,
+    /// The insurance vault is missing, not created yet, or not the market's.
+    #[msg("InvalidInsuranceVault")]
+    InvalidInsuranceVault,
+    /// The market is shut down, only emergency redemptions remain open.
+    #[msg("MarketShutdown")]
+    MarketShutdown,
+    /// Emergency redemptions open only once the market is shut down.
+    #[msg("MarketNotShutdown")]
+    MarketNotShutdown,
+    /// No executed proposal has queued a new auditor for the receipt mint.
+    #[msg("NoPendingAuditorUpdate")]
+    NoPendingAuditorUpdate,
+    /// The chain already has an emitter, replacing it takes a timelocked proposal.
+    #[msg("ForeignEmitterRegistered")]
+    ForeignEmitterRegistered,
+    /// A `ForeignEmitter` proposal executes with the emitter account and a payer.
+    #[msg("MissingForeignEmitter")]
+    MissingForeignEmitter,
+    /// The lending reserve still has debt, collateral or liquidity.
+    #[msg("ReserveNotEmpty")]
+    ReserveNotEmpty,
+    /// The oracle publish time is further ahead of the cluster clock than skew allows.
+    #[msg("OracleTimestampInFuture")]
+    OracleTimestampInFuture,
+    /// Receipts bridged out through the emitter have not all come back.
+    #[msg("BridgedSupplyOutstanding")]
+    BridgedSupplyOutstanding,
+    /// The market is timelocked and no executed proposal approved this action.
+    #[msg("AdminActionNotApproved")]
+    AdminActionNotApproved,
+    /// The token account holds less than the amount it should pay in.
+    #[msg("InsufficientBalance")]
+    InsufficientBalance,
+}
@@ -0,0 +1,217 @@
+use anchor_lang::prelude::*;
+use crate::state::MarketParamChange;
+
+/// Emitted by `verify_backing` with the market's collateralization.
+#[event]
+#[derive(Clone)]
+pub struct BackingReport {
+    pub receipt_state: Pubkey,
+    /// Underlying owned by the market, including any flash loan in flight.
+    pub total_underlying: u64,
+    pub receipt_supply: u64,
+    /// `total_underlying / receipt_supply` in basis points, or `u64::MAX` when
+    /// no receipts are outstanding.
+    pub collateralization_bps: u64,
+    pub slot: u64,
+}
+
+/// Emitted for every deposit made with a referrer.
+#[event]
+pub struct ReferredDeposit {
+    pub receipt_state: Pubkey,
+    pub referrer: Pubkey,
+    pub depositor: Pubkey,
+    pub recipient: Pubkey,
+    /// Underlying deposited, before fees.
+    pub amount: u64,
+    pub receipts: u64,
+    pub referral_fee: u64,
+    /// Referrer totals after this deposit.
+    pub total_volume: u64,
+    pub deposit_count: u64,
+}
+
+#[event]
+pub struct ProposalQueued {
+    pub receipt_state: Pubkey,
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub change: MarketParamChange,
+    pub eta: i64,
+}
+
+#[event]
+pub struct ProposalCancelled {
+    pub receipt_state: Pubkey,
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub canceller: Pubkey,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub receipt_state: Pubkey,
+    pub proposal: Pubkey,
+    pub id: u64,
+}
+
+/// Emitted when a deposit or redemption breaks a flow limit and pauses the market.
+#[event]
+pub struct FlowLimitTripped {
+    pub receipt_state: Pubkey,
+    /// The rejected flow.
+    pub inflow: u64,
+    pub outflow: u64,
+    pub window_start_tvl: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct FlowLimitsReset {
+    pub receipt_state: Pubkey,
+    pub guardian: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct RewardsFunded {
+    pub receipt_state: Pubkey,
+    pub reward_pool: Pubkey,
+    /// Reward tokens received by the vault.
+    pub amount: u64,
+    pub emission_per_second: u64,
+    pub emission_end_ts: i64,
+}
+
+#[event]
+pub struct UnallocatedRewardsSwept {
+    pub receipt_state: Pubkey,
+    pub reward_pool: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RewardsClaimed {
+    pub receipt_state: Pubkey,
+    pub reward_pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct DistributionCreated {
+    pub receipt_state: Pubkey,
+    pub distribution: Pubkey,
+    pub id: u64,
+    pub mint: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub num_nodes: u64,
+    pub expiry_ts: i64,
+}
+
+#[event]
+pub struct DistributionClaimed {
+    pub distribution: Pubkey,
+    pub index: u64,
+    pub claimant: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct DistributionClawedBack {
+    pub distribution: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BridgedOut {
+    pub receipt_state: Pubkey,
+    pub sender: Pubkey,
+    pub amount: u64,
+    pub target_chain: u16,
+    pub recipient: [u8; 32],
+    pub message: Pubkey,
+}
+
+#[event]
+pub struct BridgedIn {
+    pub receipt_state: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub emitter_chain: u16,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct ObligationLiquidated {
+    pub reserve: Pubkey,
+    pub obligation: Pubkey,
+    pub liquidator: Pubkey,
+    pub repaid: u64,
+    /// Receipts moved to the liquidator.
+    pub seized: u64,
+}
+
+#[event]
+pub struct LeverageDeposited {
+    pub reserve: Pubkey,
+    pub owner: Pubkey,
+    /// Underlying the owner put in, fees included.
+    pub amount: u64,
+    /// Underlying borrowed from the reserve into the vault.
+    pub borrowed: u64,
+    /// Receipts added to the obligation's collateral.
+    pub receipts: u64,
+}
+
+#[event]
+pub struct Deleveraged {
+    pub reserve: Pubkey,
+    pub owner: Pubkey,
+    /// Collateral receipts burned.
+    pub receipts: u64,
+    /// Debt repaid out of their redemption.
+    pub repaid: u64,
+    /// Underlying paid out to the owner.
+    pub withdrawn: u64,
+}
+
+/// Value of a market's stake pool tokens, emitted whenever it is recomputed.
+#[event]
+pub struct StakePoolValueRefreshed {
+    pub receipt_state: Pubkey,
+    pub pool_tokens: u64,
+    /// Underlying the pool tokens are worth.
+    pub value: u64,
+    /// Stake pool epoch the value was taken at.
+    pub epoch: u64,
+}
+
+#[event]
+pub struct InsuranceFunded {
+    pub receipt_state: Pubkey,
+    pub amount: u64,
+    /// Insurance vault balance after funding.
+    pub balance: u64,
+}
+
+#[event]
+pub struct LossCovered {
+    pub receipt_state: Pubkey,
+    pub amount: u64,
+    /// Insurance vault balance left.
+    pub balance: u64,
+    /// Underlying covered by the insurance vault over the market's lifetime.
+    pub total_covered: u64,
+}
+
+#[event]
+pub struct EmergencyShutdown {
+    pub receipt_state: Pubkey,
+    pub triggered_by: Pubkey,
+    pub underlying: u64,
+    pub stake_pool_tokens: u64,
+    pub receipt_supply: u64,
+    pub slot: u64,
+}
@@ -0,0 +1,134 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{ReceiptState, RewardPool},
+    utils::{create_token_account, is_supported_mint},
+};
+
+#[derive(Accounts)]
+pub struct AddRewardPool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        seeds = [
+            RewardPool::REWARD_POOL_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            reward_mint.key().as_ref(),
+        ],
+        bump,
+        payer = authority,
+        space = RewardPool::LEN,
+    )]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+
+    /// CHECK: Created below, holds the reward tokens
+    #[account(
+        mut,
+        seeds = [
+            RewardPool::REWARD_VAULT_SEED.as_bytes(),
+            reward_pool.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub reward_vault: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: Owns the reward and stake vaults
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Created with the market's first reward pool, holds staked receipts
+    #[account(
+        mut,
+        seeds = [
+            RewardPool::STAKE_VAULT_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub stake_vault: UncheckedAccount<'info>,
+
+    /// Spl token program or token program 2022
+    pub reward_mint_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub crypto_receipt_mint_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_add_reward_pool(ctx: Context<AddRewardPool>) -> Result<()> {
+    let receipt_state_key = ctx.accounts.receipt_state.key();
+    let index = ctx.accounts.receipt_state.reward_pool_count;
+    require!(
+        (index as usize) < RewardPool::MAX_REWARD_POOLS,
+        ReceiptErrorCode::TooManyRewardPools
+    );
+    require!(is_supported_mint(&ctx.accounts.reward_mint)?, ReceiptErrorCode::InvalidInput);
+
+    let reward_pool_key = ctx.accounts.reward_pool.key();
+    create_token_account(
+        &ctx.accounts.vault_authority.to_account_info(),
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.reward_vault.to_account_info(),
+        &ctx.accounts.reward_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.reward_mint_program.to_account_info(),
+        &[
+            RewardPool::REWARD_VAULT_SEED.as_bytes(),
+            reward_pool_key.as_ref(),
+            &[ctx.bumps.reward_vault][..],
+        ][..],
+    )?;
+    if ctx.accounts.stake_vault.data_is_empty() {
+        create_token_account(
+            &ctx.accounts.vault_authority.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.stake_vault.to_account_info(),
+            &ctx.accounts.crypto_receipt_mint.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+            &[
+                RewardPool::STAKE_VAULT_SEED.as_bytes(),
+                receipt_state_key.as_ref(),
+                &[ctx.bumps.stake_vault][..],
+            ][..],
+        )?;
+    }
+
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    reward_pool.receipt_state = receipt_state_key;
+    reward_pool.reward_mint = ctx.accounts.reward_mint.key();
+    reward_pool.reward_vault = ctx.accounts.reward_vault.key();
+    reward_pool.index = index;
+    reward_pool.last_update_ts = Clock::get()?.unix_timestamp;
+    reward_pool.emission_end_ts = reward_pool.last_update_ts;
+    reward_pool.bump = ctx.bumps.reward_pool;
+    ctx.accounts.receipt_state.reward_pool_count = index + 1;
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::Token2022;
+use crate::{state::ReceiptState, utils::token::confidential_transfer_update_mint};
+
+#[derive(Accounts)]
+pub struct ApplyConfidentialTransferAuditor<'info> {
+    #[account(
+        mut,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    /// CHECK: Confidential transfer authority of the receipt mint
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Receipt mint, validated by `receipt_state` and the token program
+    #[account(mut)]
+    pub crypto_receipt_mint: UncheckedAccount<'info>,
+
+    pub crypto_receipt_mint_program: Program<'info, Token2022>,
+}
+
+/// Permissionless, moves the auditor of an executed proposal onto the receipt mint.
+pub fn handle_apply_confidential_transfer_auditor(
+    ctx: Context<ApplyConfidentialTransferAuditor>,
+) -> Result<()> {
+    update_mint_auditor(
+        &mut ctx.accounts.receipt_state,
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.crypto_receipt_mint,
+        &ctx.accounts.crypto_receipt_mint_program,
+    )
+}
+
+/// Replace the receipt mint's auditor with the one queued in `receipt_state`.
+pub fn update_mint_auditor<'info>(
+    receipt_state: &mut Account<'info, ReceiptState>,
+    vault_authority: &UncheckedAccount<'info>,
+    crypto_receipt_mint: &UncheckedAccount<'info>,
+    crypto_receipt_mint_program: &Program<'info, Token2022>,
+) -> Result<()> {
+    let auditor_elgamal_pubkey = receipt_state.take_pending_auditor()?;
+    let receipt_state_key = receipt_state.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+        receipt_state_key.as_ref(),
+        &[receipt_state.vault_authority_bump],
+    ]];
+    confidential_transfer_update_mint(
+        &crypto_receipt_mint_program.to_account_info(),
+        &crypto_receipt_mint.to_account_info(),
+        &vault_authority.to_account_info(),
+        auditor_elgamal_pubkey,
+        signer_seeds,
+    )
+}
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{Multisig, MultisigTransaction},
+};
+
+#[derive(Accounts)]
+pub struct ApproveMultisigTransaction<'info> {
+    pub signer: Signer<'info>,
+
+    pub multisig: Box<Account<'info, Multisig>>,
+
+    #[account(
+        mut,
+        has_one = multisig,
+        constraint = !transaction.executed @ ReceiptErrorCode::AlreadyExecuted,
+        constraint = transaction.owner_set_seqno == multisig.owner_set_seqno
+            @ ReceiptErrorCode::StaleMultisigTransaction,
+    )]
+    pub transaction: Box<Account<'info, MultisigTransaction>>,
+}
+
+pub fn handle_approve_multisig_transaction(ctx: Context<ApproveMultisigTransaction>) -> Result<()> {
+    let signer_index = ctx.accounts.multisig.signer_index(&ctx.accounts.signer.key())?;
+    ctx.accounts.transaction.approvals[signer_index] = true;
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::TokenAccount;
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{Obligation, ObligationHealth},
+};
+use super::lending_market::*;
+
+#[derive(Accounts)]
+pub struct Borrow<'info> {
+    pub market: LendingMarket<'info>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        constraint = obligation.reserve == market.reserve.key() @ ReceiptErrorCode::InvalidInput,
+    )]
+    pub obligation: Box<Account<'info, Obligation>>,
+
+    #[account(mut, token::mint = market.borrow_mint)]
+    pub owner_borrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+impl<'info> Borrow<'info> {
+    /// Add `amount` to the obligation's debt and pay it out to `to`, within
+    /// the reserve's LTV.
+    pub fn borrow_to(&mut self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        require!(amount > 0, ReceiptErrorCode::InvalidInput);
+        let pricing = self.market.refresh()?;
+        self.market.add_debt(&mut self.obligation, amount)?;
+        let reserve = &self.market.reserve;
+        let health = ObligationHealth::new(reserve, &self.obligation, &pricing)?;
+        require!(health.within_ltv(reserve.ltv_bps)?, ReceiptErrorCode::BorrowLimitExceeded);
+        self.market.pay_out_liquidity(to, amount)
+    }
+}
+
+pub fn handle_borrow(ctx: Context<Borrow>, amount: u64) -> Result<()> {
+    let to = ctx.accounts.owner_borrow_token_account.to_account_info();
+    ctx.accounts.borrow_to(to, amount)
+}
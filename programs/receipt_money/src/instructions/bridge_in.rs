@@ -0,0 +1,165 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    events::BridgedIn,
+    state::{BridgeClaim, BridgeTransfer, ForeignEmitter, PostedVaa, ReceiptState},
+    utils::{token_mint_to, transfer_checked_with_hook},
+};
+
+#[derive(Accounts)]
+#[instruction(sequence: u64)]
+pub struct BridgeIn<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    /// CHECK: Verified VAA, owner and layout are checked by `PostedVaa::load`
+    pub posted_vaa: UncheckedAccount<'info>,
+
+    /// Emitter of the VAA's source chain, matched against the VAA in the handler
+    #[account(has_one = receipt_state)]
+    pub foreign_emitter: Box<Account<'info, ForeignEmitter>>,
+
+    #[account(
+        init,
+        seeds = [
+            BridgeClaim::BRIDGE_CLAIM_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            &foreign_emitter.chain.to_le_bytes(),
+            foreign_emitter.address.as_ref(),
+            &sequence.to_le_bytes(),
+        ],
+        bump,
+        payer = payer,
+        space = BridgeClaim::LEN,
+    )]
+    pub bridge_claim: Box<Account<'info, BridgeClaim>>,
+
+    #[account(mut)]
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, token::mint = crypto_receipt_mint)]
+    pub recipient_crypto_receipt_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Holds escrowed receipts, the token program checks it in escrow mode
+    #[account(
+        mut,
+        seeds = [
+            BridgeClaim::BRIDGE_ESCROW_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub bridge_escrow: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: Mint authority of the receipts and owner of the bridge escrow
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Spl token program or token program 2022
+    pub crypto_receipt_mint_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Credit receipts bridged back from another chain, once per message.
+/// `sequence` is the VAA's sequence, it keys the claim. Receipts come out of
+/// the escrow in escrow mode and are minted otherwise, the remaining accounts
+/// carry the receipt mint's transfer hook accounts for the escrow transfer.
+pub fn handle_bridge_in<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BridgeIn<'info>>,
+    sequence: u64,
+) -> Result<()> {
+    let receipt_state = &ctx.accounts.receipt_state;
+    require_keys_neq!(
+        receipt_state.core_bridge_program,
+        Pubkey::default(),
+        ReceiptErrorCode::BridgeNotConfigured
+    );
+    let posted_vaa = PostedVaa::load(&ctx.accounts.posted_vaa, &receipt_state.core_bridge_program)?;
+    let foreign_emitter = &ctx.accounts.foreign_emitter;
+    require!(
+        foreign_emitter.chain == posted_vaa.emitter_chain
+            && foreign_emitter.address == posted_vaa.emitter_address,
+        ReceiptErrorCode::UnknownEmitter
+    );
+    require!(posted_vaa.sequence == sequence, ReceiptErrorCode::InvalidBridgeMessage);
+    let transfer = BridgeTransfer::deserialize(&mut posted_vaa.payload.as_slice())
+        .map_err(|_| error!(ReceiptErrorCode::InvalidBridgeMessage))?;
+    require!(
+        transfer.token_mint == receipt_state.token_mint
+            && transfer.target_chain == BridgeClaim::CHAIN_ID
+            && transfer.recipient == ctx.accounts.recipient_crypto_receipt_token_account.owner.to_bytes(),
+        ReceiptErrorCode::InvalidBridgeMessage
+    );
+
+    let receipt_state_key = receipt_state.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+        receipt_state_key.as_ref(),
+        &[receipt_state.vault_authority_bump],
+    ]];
+    if receipt_state.bridge_escrow {
+        transfer_checked_with_hook(
+            ctx.accounts.vault_authority.to_account_info(),
+            ctx.accounts.bridge_escrow.to_account_info(),
+            ctx.accounts.recipient_crypto_receipt_token_account.to_account_info(),
+            ctx.accounts.crypto_receipt_mint.to_account_info(),
+            ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+            transfer.amount,
+            ctx.accounts.crypto_receipt_mint.decimals,
+            ctx.remaining_accounts,
+            signer_seeds,
+        )?;
+    } else {
+        token_mint_to(
+            ctx.accounts.vault_authority.to_account_info(),
+            ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+            ctx.accounts.crypto_receipt_mint.to_account_info(),
+            ctx.accounts.recipient_crypto_receipt_token_account.to_account_info(),
+            transfer.amount,
+            signer_seeds,
+        )?;
+    }
+
+    // Only receipts that left this chain can come back
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    receipt_state.bridged_out_supply = receipt_state
+        .bridged_out_supply
+        .checked_sub(transfer.amount)
+        .ok_or(ReceiptErrorCode::InvalidBridgeMessage)?;
+    let bridge_claim = &mut ctx.accounts.bridge_claim;
+    bridge_claim.receipt_state = receipt_state_key;
+    bridge_claim.emitter_chain = posted_vaa.emitter_chain;
+    bridge_claim.emitter_address = posted_vaa.emitter_address;
+    bridge_claim.sequence = sequence;
+    bridge_claim.bump = ctx.bumps.bridge_claim;
+
+    emit!(BridgedIn {
+        receipt_state: receipt_state_key,
+        recipient: ctx.accounts.recipient_crypto_receipt_token_account.owner,
+        amount: transfer.amount,
+        emitter_chain: posted_vaa.emitter_chain,
+        sequence: posted_vaa.sequence,
+    });
+    Ok(())
+}
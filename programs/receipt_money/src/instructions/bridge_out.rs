@@ -0,0 +1,197 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+    solana_program::program::invoke_signed,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    events::BridgedOut,
+    state::{post_message_instruction, BridgeClaim, BridgeTransfer, ForeignEmitter, ReceiptState},
+    utils::{create_token_account, token_burn, transfer_checked_with_hook},
+};
+
+#[derive(Accounts)]
+#[instruction(amount: u64, target_chain: u16)]
+pub struct BridgeOut<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    /// The market's emitter on the target chain, bridging is limited to registered chains
+    #[account(
+        has_one = receipt_state,
+        seeds = [
+            ForeignEmitter::FOREIGN_EMITTER_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            &target_chain.to_le_bytes(),
+        ],
+        bump = foreign_emitter.bump,
+    )]
+    pub foreign_emitter: Box<Account<'info, ForeignEmitter>>,
+
+    #[account(mut)]
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, token::mint = crypto_receipt_mint, token::authority = user)]
+    pub user_crypto_receipt_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Holds escrowed receipts, created on the first escrowed bridge out
+    #[account(
+        mut,
+        seeds = [
+            BridgeClaim::BRIDGE_ESCROW_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub bridge_escrow: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: Owns the bridge escrow
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Signs the market's bridge messages
+    #[account(
+        seeds = [
+            BridgeClaim::BRIDGE_EMITTER_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub emitter: UncheckedAccount<'info>,
+
+    /// CHECK: Core bridge program configured by the market authority
+    #[account(
+        executable,
+        address = receipt_state.core_bridge_program @ ReceiptErrorCode::BridgeNotConfigured,
+    )]
+    pub core_bridge_program: UncheckedAccount<'info>,
+
+    /// CHECK: The emitter's sequence account, checked by the core bridge
+    #[account(mut)]
+    pub sequence: UncheckedAccount<'info>,
+
+    /// CHECK: The message account, checked by the core bridge
+    #[account(mut)]
+    pub message: UncheckedAccount<'info>,
+
+    /// Spl token program or token program 2022
+    pub crypto_receipt_mint_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Burn or escrow `amount` receipts and post a message crediting them to
+/// `recipient` on `target_chain`. In escrow mode the remaining accounts carry
+/// the receipt mint's transfer hook accounts.
+pub fn handle_bridge_out<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BridgeOut<'info>>,
+    amount: u64,
+    target_chain: u16,
+    recipient: [u8; 32],
+    nonce: u32,
+) -> Result<()> {
+    require!(amount > 0, ReceiptErrorCode::InvalidInput);
+    let receipt_state_key = ctx.accounts.receipt_state.key();
+    if ctx.accounts.receipt_state.bridge_escrow {
+        if ctx.accounts.bridge_escrow.data_is_empty() {
+            create_token_account(
+                &ctx.accounts.vault_authority.to_account_info(),
+                &ctx.accounts.user.to_account_info(),
+                &ctx.accounts.bridge_escrow.to_account_info(),
+                &ctx.accounts.crypto_receipt_mint.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                &ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+                &[
+                    BridgeClaim::BRIDGE_ESCROW_SEED.as_bytes(),
+                    receipt_state_key.as_ref(),
+                    &[ctx.bumps.bridge_escrow][..],
+                ][..],
+            )?;
+        }
+        transfer_checked_with_hook(
+            ctx.accounts.user.to_account_info(),
+            ctx.accounts.user_crypto_receipt_token_account.to_account_info(),
+            ctx.accounts.bridge_escrow.to_account_info(),
+            ctx.accounts.crypto_receipt_mint.to_account_info(),
+            ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+            amount,
+            ctx.accounts.crypto_receipt_mint.decimals,
+            ctx.remaining_accounts,
+            &[],
+        )?;
+    } else {
+        token_burn(
+            ctx.accounts.user.to_account_info(),
+            ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+            ctx.accounts.crypto_receipt_mint.to_account_info(),
+            ctx.accounts.user_crypto_receipt_token_account.to_account_info(),
+            amount,
+            &[],
+        )?;
+    }
+
+    let payload = BridgeTransfer {
+        token_mint: ctx.accounts.receipt_state.token_mint,
+        amount,
+        target_chain,
+        recipient,
+    }
+    .try_to_vec()?;
+    let post_message_ix = post_message_instruction(
+        ctx.accounts.core_bridge_program.key(),
+        ctx.accounts.user.key(),
+        ctx.accounts.emitter.key(),
+        ctx.accounts.sequence.key(),
+        ctx.accounts.message.key(),
+        nonce,
+        payload,
+    )?;
+    invoke_signed(
+        &post_message_ix,
+        &[
+            ctx.accounts.user.to_account_info(),
+            ctx.accounts.emitter.to_account_info(),
+            ctx.accounts.sequence.to_account_info(),
+            ctx.accounts.message.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.core_bridge_program.to_account_info(),
+        ],
+        &[&[
+            BridgeClaim::BRIDGE_EMITTER_SEED.as_bytes(),
+            receipt_state_key.as_ref(),
+            &[ctx.bumps.emitter],
+        ]],
+    )?;
+
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    receipt_state.bridged_out_supply = receipt_state
+        .bridged_out_supply
+        .checked_add(amount)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    emit!(BridgedOut {
+        receipt_state: receipt_state_key,
+        sender: ctx.accounts.user.key(),
+        amount,
+        target_chain,
+        recipient,
+        message: ctx.accounts.message.key(),
+    });
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::{
+    errors::ReceiptErrorCode,
+    events::ProposalCancelled,
+    state::{Proposal, ReceiptState},
+};
+
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    #[account(
+        constraint = canceller.key() == receipt_state.guardian
+            || canceller.key() == receipt_state.authority
+            @ ReceiptErrorCode::Unauthorized,
+    )]
+    pub canceller: Signer<'info>,
+
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        mut,
+        close = proposer,
+        has_one = receipt_state,
+        has_one = proposer,
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+
+    /// CHECK: Gets the proposal rent back
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+pub fn handle_cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+    emit!(ProposalCancelled {
+        receipt_state: ctx.accounts.receipt_state.key(),
+        proposal: ctx.accounts.proposal.key(),
+        id: ctx.accounts.proposal.id,
+        canceller: ctx.accounts.canceller.key(),
+    });
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    events::DistributionClaimed,
+    state::{Distribution, ReceiptState},
+    utils::{merkle_leaf, transfer_from_pool_vault_to_user, verify_merkle_proof},
+};
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    pub claimant: Signer<'info>,
+
+    #[account(
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        mut,
+        has_one = receipt_state,
+        has_one = mint,
+        has_one = vault,
+        seeds = [
+            Distribution::DISTRIBUTION_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            &distribution.id.to_le_bytes(),
+        ],
+        bump = distribution.bump,
+    )]
+    pub distribution: Box<Account<'info, Distribution>>,
+
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Any account of the distributed mint may receive the claim
+    #[account(mut, token::mint = mint)]
+    pub claimant_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: Owns the distribution vault
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Spl token program or token program 2022
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handle_claim(ctx: Context<Claim>, index: u64, amount: u64, proof: Vec<[u8; 32]>) -> Result<()> {
+    let distribution = &mut ctx.accounts.distribution;
+    require!(
+        !distribution.clawed_back && Clock::get()?.unix_timestamp < distribution.expiry_ts,
+        ReceiptErrorCode::DistributionExpired
+    );
+    let leaf = merkle_leaf(index, &ctx.accounts.claimant.key(), amount);
+    require!(
+        verify_merkle_proof(&proof, &distribution.merkle_root, leaf),
+        ReceiptErrorCode::InvalidMerkleProof
+    );
+    distribution.record_claim(index, amount)?;
+
+    let receipt_state_key = ctx.accounts.receipt_state.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+        receipt_state_key.as_ref(),
+        &[ctx.accounts.receipt_state.vault_authority_bump],
+    ]];
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.vault_authority.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.claimant_token_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+        ctx.accounts.mint.decimals,
+        signer_seeds,
+    )?;
+
+    emit!(DistributionClaimed {
+        distribution: ctx.accounts.distribution.key(),
+        index,
+        claimant: ctx.accounts.claimant.key(),
+        amount,
+    });
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    events::RewardsClaimed,
+    state::{ReceiptState, RewardPool, StakePosition},
+    utils::transfer_from_pool_vault_to_user,
+};
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        mut,
+        has_one = receipt_state,
+        has_one = owner,
+        seeds = [
+            StakePosition::STAKE_POSITION_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump = stake_position.bump,
+    )]
+    pub stake_position: Box<Account<'info, StakePosition>>,
+
+    #[account(
+        mut,
+        has_one = receipt_state,
+        has_one = reward_mint,
+        has_one = reward_vault,
+    )]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+
+    #[account(mut)]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Any account of the reward mint may receive the rewards
+    #[account(mut, token::mint = reward_mint)]
+    pub owner_reward_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: Owns the reward vault
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Spl token program or token program 2022
+    pub reward_mint_program: Interface<'info, TokenInterface>,
+}
+
+/// Pay out everything the position earned from one reward pool.
+/// Returns the amount claimed.
+pub fn handle_claim_rewards(ctx: Context<ClaimRewards>) -> Result<u64> {
+    let now = Clock::get()?.unix_timestamp;
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    reward_pool.update(now, ctx.accounts.receipt_state.total_staked)?;
+    let stake_position = &mut ctx.accounts.stake_position;
+    stake_position.settle(reward_pool)?;
+    let index = reward_pool.index as usize;
+    let amount = stake_position.accrued[index];
+    stake_position.accrued[index] = 0;
+    reward_pool.unclaimed_rewards = reward_pool
+        .unclaimed_rewards
+        .checked_sub(amount)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+
+    let receipt_state_key = ctx.accounts.receipt_state.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+        receipt_state_key.as_ref(),
+        &[ctx.accounts.receipt_state.vault_authority_bump],
+    ]];
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.vault_authority.to_account_info(),
+        ctx.accounts.reward_vault.to_account_info(),
+        ctx.accounts.owner_reward_token_account.to_account_info(),
+        ctx.accounts.reward_mint.to_account_info(),
+        ctx.accounts.reward_mint_program.to_account_info(),
+        amount,
+        ctx.accounts.reward_mint.decimals,
+        signer_seeds,
+    )?;
+
+    emit!(RewardsClaimed {
+        receipt_state: receipt_state_key,
+        reward_pool: ctx.accounts.reward_pool.key(),
+        owner: ctx.accounts.owner.key(),
+        amount,
+    });
+    Ok(amount)
+}
@@ -0,0 +1,116 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    events::DistributionClawedBack,
+    state::{AdminAction, Distribution, ReceiptState},
+    utils::{token_close_account, transfer_from_pool_vault_to_user},
+};
+
+#[derive(Accounts)]
+pub struct Clawback<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        mut,
+        close = authority,
+        has_one = receipt_state,
+        has_one = mint,
+        has_one = vault,
+        seeds = [
+            Distribution::DISTRIBUTION_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            &distribution.id.to_le_bytes(),
+        ],
+        bump = distribution.bump,
+    )]
+    pub distribution: Box<Account<'info, Distribution>>,
+
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, token::mint = mint)]
+    pub receiver_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: Owns the distribution vault
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Spl token program or token program 2022
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Return everything left unclaimed once the distribution expired, then close
+/// the distribution and its vault, returning their rent to the authority.
+/// Needs an approved `AdminAction` on a timelocked market.
+pub fn handle_clawback(ctx: Context<Clawback>) -> Result<()> {
+    require!(
+        Clock::get()?.unix_timestamp >= ctx.accounts.distribution.expiry_ts,
+        ReceiptErrorCode::DistributionNotExpired
+    );
+    ctx.accounts
+        .receipt_state
+        .authorize_admin_action(&AdminAction::Clawback {
+            distribution_id: ctx.accounts.distribution.id,
+            receiver_token_account: ctx.accounts.receiver_token_account.key(),
+        })?;
+    let amount = ctx.accounts.vault.amount;
+    let receipt_state_key = ctx.accounts.receipt_state.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+        receipt_state_key.as_ref(),
+        &[ctx.accounts.receipt_state.vault_authority_bump],
+    ]];
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.vault_authority.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.receiver_token_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+        ctx.accounts.mint.decimals,
+        signer_seeds,
+    )?;
+    token_close_account(
+        ctx.accounts.vault_authority.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.authority.to_account_info(),
+        signer_seeds,
+    )?;
+    ctx.accounts.distribution.clawed_back = true;
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    receipt_state.open_distribution_count = receipt_state
+        .open_distribution_count
+        .checked_sub(1)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+
+    emit!(DistributionClawedBack {
+        distribution: ctx.accounts.distribution.key(),
+        amount,
+    });
+    Ok(())
+}
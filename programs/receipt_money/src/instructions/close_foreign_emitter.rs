@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{ForeignEmitter, ReceiptState},
+};
+
+#[derive(Accounts)]
+pub struct CloseForeignEmitter<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: Receives the emitter rent
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        mut,
+        close = recipient,
+        has_one = receipt_state,
+    )]
+    pub foreign_emitter: Box<Account<'info, ForeignEmitter>>,
+}
+
+/// Close a foreign emitter once no receipts are bridged out, so the market
+/// can close after it and a market later opened for the same mint does not
+/// trust it. Not behind the timelock: removing an emitter only stops
+/// bridging, and with nothing bridged out no holder is stranded.
+pub fn handle_close_foreign_emitter(ctx: Context<CloseForeignEmitter>) -> Result<()> {
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    require!(
+        receipt_state.bridged_out_supply == 0,
+        ReceiptErrorCode::BridgedSupplyOutstanding
+    );
+    receipt_state.foreign_emitter_count = receipt_state
+        .foreign_emitter_count
+        .checked_sub(1)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{LendingReserve, ReceiptState},
+    utils::token_close_account,
+};
+
+#[derive(Accounts)]
+pub struct CloseLendingReserve<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: Receives the rent of the reserve and its vaults
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        mut,
+        close = recipient,
+        has_one = receipt_state,
+        has_one = liquidity_vault,
+        has_one = collateral_vault,
+    )]
+    pub reserve: Box<Account<'info, LendingReserve>>,
+
+    #[account(mut)]
+    pub liquidity_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub collateral_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: Owns the reserve vaults
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Spl token program or token program 2022
+    pub borrow_mint_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub crypto_receipt_mint_program: Interface<'info, TokenInterface>,
+}
+
+/// Close a reserve with no debt, collateral or liquidity left, so the market
+/// can close after it. Not behind the timelock for the same reason as
+/// `close_market`.
+pub fn handle_close_lending_reserve(ctx: Context<CloseLendingReserve>) -> Result<()> {
+    let reserve = &ctx.accounts.reserve;
+    require!(
+        reserve.total_borrow_shares == 0
+            && reserve.total_collateral == 0
+            && ctx.accounts.liquidity_vault.amount == 0
+            && ctx.accounts.collateral_vault.amount == 0,
+        ReceiptErrorCode::ReserveNotEmpty
+    );
+
+    let receipt_state_key = ctx.accounts.receipt_state.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+        receipt_state_key.as_ref(),
+        &[ctx.accounts.receipt_state.vault_authority_bump],
+    ]];
+    token_close_account(
+        ctx.accounts.vault_authority.to_account_info(),
+        ctx.accounts.borrow_mint_program.to_account_info(),
+        ctx.accounts.liquidity_vault.to_account_info(),
+        ctx.accounts.recipient.to_account_info(),
+        signer_seeds,
+    )?;
+    token_close_account(
+        ctx.accounts.vault_authority.to_account_info(),
+        ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+        ctx.accounts.collateral_vault.to_account_info(),
+        ctx.accounts.recipient.to_account_info(),
+        signer_seeds,
+    )?;
+
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    receipt_state.open_reserve_count = receipt_state
+        .open_reserve_count
+        .checked_sub(1)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    Ok(())
+}
@@ -0,0 +1,307 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{BridgeClaim, ReceiptState, RewardPool},
+    utils::{has_mint_close_authority, token_close_account, transfer_from_pool_vault_to_user},
+};
+
+#[derive(Accounts)]
+pub struct CloseMarket<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: Receives the rent of every closed account
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = recipient,
+        has_one = authority,
+        has_one = token_mint_vault,
+        has_one = crypto_receipt_mint,
+        constraint = receipt_state.crypto_receipt_vault == crypto_receipt_mint_vault.key(),
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: This is both vault authority and mint authority of crToken
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub crypto_receipt_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Created with the first reward pool, closed here if it exists
+    #[account(
+        mut,
+        seeds = [
+            RewardPool::STAKE_VAULT_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub stake_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Created by the first escrowed bridge out, closed here if it exists
+    #[account(
+        mut,
+        seeds = [
+            BridgeClaim::BRIDGE_ESCROW_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub bridge_escrow: UncheckedAccount<'info>,
+
+    /// Required once the market has an insurance vault, whose balance goes to
+    /// the fee receiver the insurance cut was taken from
+    #[account(
+        mut,
+        address = receipt_state.insurance_vault @ ReceiptErrorCode::InvalidInsuranceVault,
+    )]
+    pub insurance_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Required when underlying is left in the vaults
+    #[account(
+        mut,
+        address = receipt_state.fee_receiver @ ReceiptErrorCode::InvalidFeeReceiver,
+    )]
+    pub fee_receiver: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(address = receipt_state.token_mint)]
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Required once the market has a stake pool vault
+    #[account(
+        mut,
+        address = receipt_state.stake_pool_vault @ ReceiptErrorCode::InvalidStakePool,
+    )]
+    pub stake_pool_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Receives pool tokens left in the stake pool vault
+    #[account(mut)]
+    pub stake_pool_token_receiver: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub pool_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Spl token program or token program 2022
+    pub pool_mint_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Spl token program or token program 2022
+    pub token_mint_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub crypto_receipt_mint_program: Interface<'info, TokenInterface>,
+}
+
+/// Not behind the timelock: only an empty market closes, leaving no depositor
+/// for a delay to protect. Lending reserves, foreign emitters and
+/// distributions are closed first with `close_lending_reserve`,
+/// `close_foreign_emitter` and `clawback`.
+///
+/// Every reward pool of the market follows as remaining accounts, in index
+/// order, as the pool, its reward vault and the reward mint's token program.
+/// Reward vaults must be empty, `sweep_unallocated_rewards` empties them in the
+/// same transaction. Underlying anyone sent to the market vault and what is
+/// left in the insurance vault go to the fee receiver, pool tokens left in
+/// the stake pool vault to `stake_pool_token_receiver`.
+///
+/// Bridge claims stay open: they stop a market opened later for the same mint
+/// from redeeming old messages again, and their rent belongs to the relayer
+/// that paid it.
+pub fn handle_close_market<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CloseMarket<'info>>,
+) -> Result<()> {
+    let receipt_state = &ctx.accounts.receipt_state;
+    require!(
+        ctx.accounts.crypto_receipt_mint.supply == 0
+            && receipt_state.total_staked == 0
+            && ctx.accounts.crypto_receipt_mint_vault.amount == 0
+            && receipt_state.flash_loan_outstanding == 0
+            && receipt_state.bridged_out_supply == 0
+            && receipt_state.stake_pool_value == 0
+            && receipt_state.open_reserve_count == 0
+            && receipt_state.foreign_emitter_count == 0
+            && receipt_state.open_distribution_count == 0,
+        ReceiptErrorCode::MarketNotEmpty
+    );
+
+    let receipt_state_key = receipt_state.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+        receipt_state_key.as_ref(),
+        &[receipt_state.vault_authority_bump],
+    ]];
+    close_reward_pools(ctx.remaining_accounts, ctx.accounts, signer_seeds)?;
+
+    let accounts = &ctx.accounts;
+    let token_mint_vault = &accounts.token_mint_vault;
+    sweep_and_close(
+        accounts,
+        token_mint_vault,
+        accounts.fee_receiver.as_deref(),
+        accounts.token_mint.as_deref(),
+        &accounts.token_mint_program,
+        ReceiptErrorCode::InvalidFeeReceiver,
+        signer_seeds,
+    )?;
+    if let Some(insurance_vault) = accounts.insurance_vault.as_deref() {
+        sweep_and_close(
+            accounts,
+            insurance_vault,
+            accounts.fee_receiver.as_deref(),
+            accounts.token_mint.as_deref(),
+            &accounts.token_mint_program,
+            ReceiptErrorCode::InvalidFeeReceiver,
+            signer_seeds,
+        )?;
+    } else {
+        require_keys_eq!(
+            accounts.receipt_state.insurance_vault,
+            Pubkey::default(),
+            ReceiptErrorCode::InvalidInsuranceVault
+        );
+    }
+    if let Some(stake_pool_vault) = accounts.stake_pool_vault.as_deref() {
+        let pool_mint_program = accounts
+            .pool_mint_program
+            .as_ref()
+            .ok_or(ReceiptErrorCode::InvalidStakePool)?;
+        sweep_and_close(
+            accounts,
+            stake_pool_vault,
+            accounts.stake_pool_token_receiver.as_deref(),
+            accounts.pool_mint.as_deref(),
+            pool_mint_program,
+            ReceiptErrorCode::InvalidStakePool,
+            signer_seeds,
+        )?;
+    } else {
+        require_keys_eq!(
+            accounts.receipt_state.stake_pool_vault,
+            Pubkey::default(),
+            ReceiptErrorCode::InvalidStakePool
+        );
+    }
+
+    // No receipts exist, so the receipt vaults are empty
+    for receipt_vault in [
+        accounts.crypto_receipt_mint_vault.to_account_info(),
+        accounts.stake_vault.to_account_info(),
+        accounts.bridge_escrow.to_account_info(),
+    ] {
+        if receipt_vault.data_is_empty() {
+            continue;
+        }
+        token_close_account(
+            accounts.vault_authority.to_account_info(),
+            accounts.crypto_receipt_mint_program.to_account_info(),
+            receipt_vault,
+            accounts.recipient.to_account_info(),
+            signer_seeds,
+        )?;
+    }
+
+    // Markets created before the receipt mint had a close authority keep their mint
+    let crypto_receipt_mint_info = accounts.crypto_receipt_mint.to_account_info();
+    if has_mint_close_authority(&crypto_receipt_mint_info)? {
+        token_close_account(
+            accounts.vault_authority.to_account_info(),
+            accounts.crypto_receipt_mint_program.to_account_info(),
+            crypto_receipt_mint_info,
+            accounts.recipient.to_account_info(),
+            signer_seeds,
+        )?;
+    } else {
+        msg!("Receipt mint has no close authority, leaving it open");
+    }
+    Ok(())
+}
+
+/// Move whatever `vault` holds to `receiver`, then close it.
+fn sweep_and_close<'info>(
+    accounts: &CloseMarket<'info>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    receiver: Option<&InterfaceAccount<'info, TokenAccount>>,
+    mint: Option<&InterfaceAccount<'info, Mint>>,
+    token_program: &Interface<'info, TokenInterface>,
+    missing_receiver: ReceiptErrorCode,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if vault.amount > 0 {
+        let receiver = receiver.ok_or(missing_receiver)?;
+        let mint = mint
+            .filter(|mint| mint.key() == vault.mint)
+            .ok_or(ReceiptErrorCode::InvalidInput)?;
+        require_keys_eq!(receiver.mint, vault.mint, ReceiptErrorCode::InvalidInput);
+        transfer_from_pool_vault_to_user(
+            accounts.vault_authority.to_account_info(),
+            vault.to_account_info(),
+            receiver.to_account_info(),
+            mint.to_account_info(),
+            token_program.to_account_info(),
+            vault.amount,
+            mint.decimals,
+            signer_seeds,
+        )?;
+    }
+    token_close_account(
+        accounts.vault_authority.to_account_info(),
+        token_program.to_account_info(),
+        vault.to_account_info(),
+        accounts.recipient.to_account_info(),
+        signer_seeds,
+    )
+}
+
+fn close_reward_pools<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    accounts: &CloseMarket<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let receipt_state = &accounts.receipt_state;
+    require!(
+        remaining_accounts.len() == 3 * receipt_state.reward_pool_count as usize,
+        ReceiptErrorCode::InvalidRewardPool
+    );
+    for (index, pool_accounts) in remaining_accounts.chunks(3).enumerate() {
+        let reward_pool = Account::<RewardPool>::try_from(&pool_accounts[0])?;
+        require!(
+            reward_pool.receipt_state == receipt_state.key()
+                && reward_pool.index as usize == index
+                && reward_pool.reward_vault == pool_accounts[1].key()
+                && *pool_accounts[1].owner == pool_accounts[2].key(),
+            ReceiptErrorCode::InvalidRewardPool
+        );
+        let reward_vault = InterfaceAccount::<TokenAccount>::try_from(&pool_accounts[1])?;
+        require!(reward_vault.amount == 0, ReceiptErrorCode::MarketNotEmpty);
+        token_close_account(
+            accounts.vault_authority.to_account_info(),
+            pool_accounts[2].clone(),
+            pool_accounts[1].clone(),
+            accounts.recipient.to_account_info(),
+            signer_seeds,
+        )?;
+        reward_pool.close(accounts.recipient.to_account_info())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    events::LossCovered,
+    state::{AdminAction, ReceiptState},
+    utils::transfer_from_pool_vault_to_user,
+};
+
+#[derive(Accounts)]
+pub struct CoverLoss<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = token_mint,
+        has_one = token_mint_vault,
+        has_one = insurance_vault @ ReceiptErrorCode::InvalidInsuranceVault,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub insurance_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: Owns the insurance vault and `token_mint_vault`
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Spl token program or token program 2022
+    pub token_mint_program: Interface<'info, TokenInterface>,
+}
+
+/// Move `amount` from the insurance vault into `token_mint_vault` to make
+/// receipt holders whole after a reported loss. Needs an approved
+/// `AdminAction` on a timelocked market.
+pub fn handle_cover_loss(ctx: Context<CoverLoss>, amount: u64) -> Result<()> {
+    require!(amount > 0, ReceiptErrorCode::InvalidInput);
+    ctx.accounts
+        .receipt_state
+        .authorize_admin_action(&AdminAction::CoverLoss { amount })?;
+    require!(
+        amount <= ctx.accounts.insurance_vault.amount,
+        ReceiptErrorCode::InsufficientLiquidity
+    );
+    let receipt_state_key = ctx.accounts.receipt_state.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+        receipt_state_key.as_ref(),
+        &[ctx.accounts.receipt_state.vault_authority_bump],
+    ]];
+    let before = ctx.accounts.token_mint_vault.amount;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.vault_authority.to_account_info(),
+        ctx.accounts.insurance_vault.to_account_info(),
+        ctx.accounts.token_mint_vault.to_account_info(),
+        ctx.accounts.token_mint.to_account_info(),
+        ctx.accounts.token_mint_program.to_account_info(),
+        amount,
+        ctx.accounts.token_mint.decimals,
+        signer_seeds,
+    )?;
+    ctx.accounts.token_mint_vault.reload()?;
+    ctx.accounts.insurance_vault.reload()?;
+    let covered = ctx.accounts.token_mint_vault.amount - before;
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    receipt_state.insurance_losses_covered = receipt_state
+        .insurance_losses_covered
+        .checked_add(covered)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    // After shutdown the cover goes to the emergency redemptions still to come
+    if receipt_state.shutdown {
+        receipt_state.shutdown_underlying = receipt_state
+            .shutdown_underlying
+            .checked_add(covered)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+    }
+    emit!(LossCovered {
+        receipt_state: receipt_state_key,
+        amount: covered,
+        balance: ctx.accounts.insurance_vault.amount,
+        total_covered: receipt_state.insurance_losses_covered,
+    });
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    events::DistributionCreated,
+    state::{AdminAction, Distribution, ReceiptState},
+    utils::{create_token_account, get_transfer_fee, is_supported_mint, transfer_from_user_to_token_vault},
+};
+
+#[derive(Accounts)]
+#[instruction(merkle_root: [u8; 32], total_amount: u64, num_nodes: u64)]
+pub struct CreateDistribution<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        seeds = [
+            Distribution::DISTRIBUTION_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            &receipt_state.distribution_count.to_le_bytes(),
+        ],
+        bump,
+        payer = authority,
+        space = Distribution::space(num_nodes.min(Distribution::MAX_NODES)),
+    )]
+    pub distribution: Box<Account<'info, Distribution>>,
+
+    /// CHECK: Created below, holds the tokens being distributed
+    #[account(
+        mut,
+        seeds = [
+            Distribution::DISTRIBUTION_VAULT_SEED.as_bytes(),
+            distribution.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: Owns the distribution vault
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(mut, token::mint = mint, token::authority = authority)]
+    pub authority_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Fund a distribution of `total_amount` over the `num_nodes` leaves of the
+/// tree rooted at `merkle_root`, claimable until `expiry_ts`. Needs an
+/// approved `AdminAction` on a timelocked market.
+pub fn handle_create_distribution(
+    ctx: Context<CreateDistribution>,
+    merkle_root: [u8; 32],
+    total_amount: u64,
+    num_nodes: u64,
+    expiry_ts: i64,
+) -> Result<()> {
+    require!(
+        num_nodes > 0 && num_nodes <= Distribution::MAX_NODES && total_amount > 0,
+        ReceiptErrorCode::InvalidDistribution
+    );
+    require!(expiry_ts > Clock::get()?.unix_timestamp, ReceiptErrorCode::InvalidDistribution);
+    ctx.accounts
+        .receipt_state
+        .authorize_admin_action(&AdminAction::CreateDistribution {
+            mint: ctx.accounts.mint.key(),
+            merkle_root,
+            total_amount,
+            num_nodes,
+            expiry_ts,
+        })?;
+    // Leaves promise exact amounts, so the vault must receive all of them
+    require!(is_supported_mint(&ctx.accounts.mint)?, ReceiptErrorCode::InvalidInput);
+    require!(
+        get_transfer_fee(&ctx.accounts.mint.to_account_info(), total_amount)? == 0,
+        ReceiptErrorCode::InvalidInput
+    );
+
+    let distribution_key = ctx.accounts.distribution.key();
+    create_token_account(
+        &ctx.accounts.vault_authority.to_account_info(),
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        &[
+            Distribution::DISTRIBUTION_VAULT_SEED.as_bytes(),
+            distribution_key.as_ref(),
+            &[ctx.bumps.vault][..],
+        ][..],
+    )?;
+    transfer_from_user_to_token_vault(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.authority_token_account.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        total_amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    let distribution = &mut ctx.accounts.distribution;
+    distribution.receipt_state = receipt_state.key();
+    distribution.id = receipt_state.distribution_count;
+    distribution.mint = ctx.accounts.mint.key();
+    distribution.vault = ctx.accounts.vault.key();
+    distribution.merkle_root = merkle_root;
+    distribution.total_amount = total_amount;
+    distribution.num_nodes = num_nodes;
+    distribution.expiry_ts = expiry_ts;
+    distribution.bump = ctx.bumps.distribution;
+    distribution.claimed = vec![0; Distribution::bitmap_len(num_nodes)];
+    receipt_state.distribution_count += 1;
+    receipt_state.open_distribution_count = receipt_state
+        .open_distribution_count
+        .checked_add(1)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+
+    emit!(DistributionCreated {
+        receipt_state: receipt_state.key(),
+        distribution: distribution_key,
+        id: distribution.id,
+        mint: distribution.mint,
+        merkle_root,
+        total_amount,
+        num_nodes,
+        expiry_ts,
+    });
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::state::Multisig;
+
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Any key, only used as a seed
+    pub create_key: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [Multisig::MULTISIG_SEED.as_bytes(), create_key.key().as_ref()],
+        bump,
+        payer = payer,
+        space = Multisig::LEN,
+    )]
+    pub multisig: Box<Account<'info, Multisig>>,
+
+    /// CHECK: Signs for the multisig, set it as a market authority
+    #[account(
+        seeds = [Multisig::MULTISIG_SIGNER_SEED.as_bytes(), multisig.key().as_ref()],
+        bump,
+    )]
+    pub multisig_signer: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_create_multisig(
+    ctx: Context<CreateMultisig>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    Multisig::validate_signers(&signers, threshold)?;
+    let multisig = &mut ctx.accounts.multisig;
+    multisig.create_key = ctx.accounts.create_key.key();
+    multisig.signers = signers;
+    multisig.threshold = threshold;
+    multisig.bump = ctx.bumps.multisig;
+    multisig.signer_bump = ctx.bumps.multisig_signer;
+    Ok(())
+}
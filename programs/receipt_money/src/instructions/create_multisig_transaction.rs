@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::state::{Multisig, MultisigTransaction, TransactionAccount};
+
+#[derive(Accounts)]
+#[instruction(program_id: Pubkey, accounts: Vec<TransactionAccount>, data: Vec<u8>)]
+pub struct CreateMultisigTransaction<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(mut)]
+    pub multisig: Box<Account<'info, Multisig>>,
+
+    #[account(
+        init,
+        seeds = [
+            MultisigTransaction::MULTISIG_TRANSACTION_SEED.as_bytes(),
+            multisig.key().as_ref(),
+            &multisig.transaction_count.to_le_bytes(),
+        ],
+        bump,
+        payer = proposer,
+        space = MultisigTransaction::space(accounts.len(), data.len()),
+    )]
+    pub transaction: Box<Account<'info, MultisigTransaction>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Queue an instruction for the multisig, counting as the proposer's approval.
+pub fn handle_create_multisig_transaction(
+    ctx: Context<CreateMultisigTransaction>,
+    program_id: Pubkey,
+    accounts: Vec<TransactionAccount>,
+    data: Vec<u8>,
+) -> Result<()> {
+    let multisig = &mut ctx.accounts.multisig;
+    let proposer_index = multisig.signer_index(&ctx.accounts.proposer.key())?;
+    let mut approvals = vec![false; multisig.signers.len()];
+    approvals[proposer_index] = true;
+
+    let transaction = &mut ctx.accounts.transaction;
+    transaction.multisig = multisig.key();
+    transaction.proposer = ctx.accounts.proposer.key();
+    transaction.index = multisig.transaction_count;
+    transaction.program_id = program_id;
+    transaction.accounts = accounts;
+    transaction.data = data;
+    transaction.approvals = approvals;
+    transaction.owner_set_seqno = multisig.owner_set_seqno;
+    transaction.executed = false;
+    transaction.bump = ctx.bumps.transaction;
+    multisig.transaction_count += 1;
+    Ok(())
+}
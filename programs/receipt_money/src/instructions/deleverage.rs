@@ -0,0 +1,101 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::TokenAccount;
+use crate::{
+    errors::ReceiptErrorCode,
+    events::Deleveraged,
+    state::{Obligation, ObligationHealth},
+    utils::{get_transfer_fee, quote_redeem},
+};
+use super::{lending_market::*, repay::reduce_debt};
+
+#[derive(Accounts)]
+pub struct Deleverage<'info> {
+    pub market: LendingMarket<'info>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        constraint = obligation.reserve == market.reserve.key() @ ReceiptErrorCode::InvalidInput,
+    )]
+    pub obligation: Box<Account<'info, Obligation>>,
+
+    #[account(mut, token::mint = market.borrow_mint)]
+    pub owner_mint_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Unwind `receipts` of collateral in one step. The vault fronts the
+/// underlying they redeem for to repay as much debt as it covers, then burns
+/// the collateral to settle, and pays the rest out to the owner.
+/// Returns the underlying the owner received.
+pub fn handle_deleverage(ctx: Context<Deleverage>, receipts: u64, min_underlying_out: u64) -> Result<u64> {
+    require!(receipts > 0, ReceiptErrorCode::InvalidInput);
+    let market = &mut ctx.accounts.market;
+    let obligation = &mut ctx.accounts.obligation;
+    require_keys_eq!(
+        market.reserve.borrow_mint,
+        market.receipt_state.token_mint,
+        ReceiptErrorCode::LeverageUnavailable
+    );
+    require!(receipts <= obligation.collateral, ReceiptErrorCode::InsufficientCollateral);
+
+    let pricing = market.refresh()?;
+    let token_mint = market.borrow_mint.to_account_info();
+    let quote = quote_redeem(
+        &market.receipt_state,
+        &token_mint,
+        market.token_mint_vault.amount,
+        pricing.receipt_supply,
+        receipts,
+    )?;
+    require!(quote.underlying > 0, ReceiptErrorCode::InvalidInput);
+    require!(
+        quote.underlying <= market.token_mint_vault.amount,
+        ReceiptErrorCode::InsufficientLiquidity
+    );
+    let repay = quote.underlying.min(market.reserve.debt_of(obligation.borrow_shares)?);
+    let withdrawn = quote.underlying - repay;
+    let received = withdrawn - get_transfer_fee(&token_mint, withdrawn)?;
+    require!(received >= min_underlying_out, ReceiptErrorCode::SlippageExceeded);
+    let receipt_state_key = market.receipt_state.key();
+    let vault_amount = market.token_mint_vault.amount;
+    if !market.receipt_state.record_flow(receipt_state_key, vault_amount, 0, quote.underlying)? {
+        return Ok(0);
+    }
+
+    let mut repaid = 0;
+    if repay > 0 {
+        let before = market.liquidity_vault.amount;
+        let liquidity_vault = market.liquidity_vault.to_account_info();
+        market.pay_out_underlying(liquidity_vault, repay)?;
+        market.liquidity_vault.reload()?;
+        repaid = market.liquidity_vault.amount - before;
+        reduce_debt(market, obligation, repaid)?;
+    }
+    // Burning the collateral settles what the vault fronted
+    market.burn_collateral(receipts)?;
+    obligation.collateral -= receipts;
+    if withdrawn > 0 {
+        market.pay_out_underlying(ctx.accounts.owner_mint_token_account.to_account_info(), withdrawn)?;
+    }
+
+    market.reload()?;
+    let pricing = market.refresh()?;
+    let health = ObligationHealth::new(&market.reserve, obligation, &pricing)?;
+    require!(
+        health.within_ltv(market.reserve.ltv_bps)?,
+        ReceiptErrorCode::BorrowLimitExceeded
+    );
+    emit!(Deleveraged {
+        reserve: market.reserve.key(),
+        owner: obligation.owner,
+        receipts,
+        repaid,
+        withdrawn,
+    });
+    Ok(received)
+}
@@ -2,10 +2,15 @@ use anchor_lang::{
     accounts::interface_account::InterfaceAccount,
     prelude::*,
 };
-use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{self, Mint, TokenAccount, TokenInterface},
+};
 use crate::{
-    state::ReceiptState, 
-    utils::transfer_from_user_to_token_vault,
+    errors::ReceiptErrorCode,
+    events::{InsuranceFunded, ReferredDeposit},
+    state::{ReceiptState, ReferralStats, StakePoolState, UserPosition},
+    utils::{mul_div_floor, quote_deposit, transfer_from_user_to_token_vault, BASIS_POINTS_DIVISOR},
 };
 
 #[derive(Accounts)]
@@ -20,14 +25,18 @@ pub struct Deposit<'info> {
         associated_token::token_program = token_mint_program,
     )]
     pub user_mint_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Any wallet may receive the minted receipts
+    pub recipient: UncheckedAccount<'info>,
     
     #[account(
-        mut,
+        init_if_needed,
+        payer = user,
         associated_token::mint = crypto_receipt_mint,
-        associated_token::authority = user,
+        associated_token::authority = recipient,
         associated_token::token_program = crypto_receipt_mint_program,
     )]
-    pub user_crypto_receipt_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub recipient_crypto_receipt_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(
         mut,
@@ -40,7 +49,7 @@ pub struct Deposit<'info> {
         ],
         bump = receipt_state.bump,
     )]
-    pub receipt_state: Account<'info, ReceiptState>,
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
     
     pub token_mint: Box<InterfaceAccount<'info, Mint>>,
 
@@ -89,36 +98,276 @@ pub struct Deposit<'info> {
     pub token_mint_program: Interface<'info, TokenInterface>,
     /// Spl token program or token program 2022
     pub crypto_receipt_mint_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// Protocol fee account, required while deposit fees are enabled
+    #[account(
+        mut,
+        address = receipt_state.fee_receiver @ ReceiptErrorCode::InvalidFeeReceiver,
+    )]
+    pub fee_receiver: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Stats of the referrer this deposit is attributed to, never the
+    /// depositor or recipient themselves
+    #[account(
+        mut,
+        has_one = receipt_state,
+        constraint = referral_stats.referrer != user.key()
+            && referral_stats.referrer != recipient.key() @ ReceiptErrorCode::InvalidReferrer,
+        seeds = [
+            ReferralStats::REFERRAL_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            referral_stats.referrer.as_ref(),
+        ],
+        bump = referral_stats.bump,
+    )]
+    pub referral_stats: Option<Box<Account<'info, ReferralStats>>>,
+
+    /// Referrer's underlying account, receives the referral share of the fee
+    #[account(mut, token::mint = token_mint)]
+    pub referrer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: Recipient's position, may not exist. Updated with this
+    /// deposit's cost basis when it does
+    #[account(
+        mut,
+        seeds = [
+            UserPosition::USER_POSITION_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            recipient.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub recipient_position: UncheckedAccount<'info>,
+
+    /// Market insurance vault, required while an insurance fee share is set
+    #[account(
+        mut,
+        address = receipt_state.insurance_vault @ ReceiptErrorCode::InvalidInsuranceVault,
+    )]
+    pub insurance_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: Parsed by `StakePoolState::load`. With `stake_pool_vault`,
+    /// revalues the market's pool tokens before pricing the deposit
+    #[account(address = receipt_state.stake_pool @ ReceiptErrorCode::InvalidStakePool)]
+    pub stake_pool: Option<UncheckedAccount<'info>>,
+
+    #[account(address = receipt_state.stake_pool_vault @ ReceiptErrorCode::InvalidStakePool)]
+    pub stake_pool_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 } 
 
-pub fn handle_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-    let receipt_state = &mut ctx.accounts.receipt_state;
-    let receipt_state_pubkey = receipt_state.key();
-    // Transfer tokens from user to vault
+impl<'info> Deposit<'info> {
+    /// Transfer `amount` of underlying from the user into the vault and mint the
+    /// receipts it buys to the recipient.
+    /// Returns zero, moving nothing, when the deposit trips the inflow limit.
+    pub fn deposit_underlying(&mut self, amount: u64) -> Result<u64> {
+        revalue_stake_pool_tokens(
+            &mut self.receipt_state,
+            self.stake_pool.as_ref(),
+            self.stake_pool_vault.as_deref(),
+        )?;
+        let receipt_state = &self.receipt_state;
+        let receipt_state_pubkey = receipt_state.key();
+        // Price the deposit before the vault balance changes
+        let quote = quote_deposit(
+            receipt_state,
+            &self.token_mint.to_account_info(),
+            self.token_mint_vault.amount,
+            receipt_state.receipt_supply(self.crypto_receipt_mint.supply)?,
+            amount,
+        )?;
+        require!(quote.receipts > 0, ReceiptErrorCode::InvalidInput);
+        // A deposit the user cannot pay for must not count against the inflow limit
+        require!(
+            amount <= self.user_mint_token_account.amount,
+            ReceiptErrorCode::InsufficientBalance
+        );
+        if !self.receipt_state.record_flow(
+            receipt_state_pubkey,
+            self.token_mint_vault.amount,
+            quote.to_vault,
+            0,
+        )? {
+            return Ok(0);
+        }
+        let referral_fee = self.pay_deposit_fee(quote.fee)?;
+        // Transfer tokens from user to vault
+        transfer_from_user_to_token_vault(
+            self.user.to_account_info(),
+            self.user_mint_token_account.to_account_info(),
+            self.token_mint_vault.to_account_info(),
+            self.token_mint.to_account_info(),
+            self.token_mint_program.to_account_info(),
+            quote.to_vault,
+            self.token_mint.decimals,
+        )?;
+        msg!("Transferring tokens from user to vault done");
+        // Mint receipt tokens
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(), 
+            receipt_state_pubkey.as_ref(),
+            &[self.receipt_state.vault_authority_bump],
+        ]];
+        msg!("Minting receipt tokens");
+        let cpi_accounts = token_interface::MintTo {
+            mint: self.crypto_receipt_mint.to_account_info(),
+            to: self.recipient_crypto_receipt_token_account.to_account_info(),
+            authority: self.vault_authority.to_account_info(),
+        };
+        let cpi_program = self.crypto_receipt_mint_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts).with_signer(signer_seeds);
+        token_interface::mint_to(cpi_ctx, quote.receipts)?;
+
+        self.record_referral(amount, quote.receipts, referral_fee)?;
+        UserPosition::update_if_open(&self.recipient_position, |position| {
+            position.record_deposit(amount, quote.receipts)
+        })?;
+        Ok(quote.receipts)
+    }
+
+    /// Split the deposit fee between the referrer and the protocol fee receiver.
+    /// Returns the referrer's share.
+    fn pay_deposit_fee(&mut self, fee: u64) -> Result<u64> {
+        if fee == 0 {
+            return Ok(0);
+        }
+        let referral_fee = match &self.referral_stats {
+            Some(referral_stats) => {
+                let referral_fee = mul_div_floor(
+                    fee,
+                    self.receipt_state.referral_fee_share_bps as u64,
+                    BASIS_POINTS_DIVISOR,
+                )?;
+                if referral_fee > 0 {
+                    let referrer_token_account = self
+                        .referrer_token_account
+                        .as_ref()
+                        .ok_or(ReceiptErrorCode::InvalidReferrer)?;
+                    require_keys_eq!(
+                        referrer_token_account.owner,
+                        referral_stats.referrer,
+                        ReceiptErrorCode::InvalidReferrer
+                    );
+                    transfer_from_user_to_token_vault(
+                        self.user.to_account_info(),
+                        self.user_mint_token_account.to_account_info(),
+                        referrer_token_account.to_account_info(),
+                        self.token_mint.to_account_info(),
+                        self.token_mint_program.to_account_info(),
+                        referral_fee,
+                        self.token_mint.decimals,
+                    )?;
+                }
+                referral_fee
+            }
+            None => 0,
+        };
+        pay_protocol_fee(
+            &self.receipt_state,
+            fee - referral_fee,
+            self.user.to_account_info(),
+            self.user_mint_token_account.to_account_info(),
+            self.fee_receiver.as_deref(),
+            self.insurance_vault.as_deref_mut(),
+            &self.token_mint,
+            self.token_mint_program.to_account_info(),
+        )?;
+        Ok(referral_fee)
+    }
+
+    /// Credit the referrer's stats and emit the event the leaderboard is rebuilt from.
+    fn record_referral(&mut self, amount: u64, receipts: u64, referral_fee: u64) -> Result<()> {
+        let Some(referral_stats) = self.referral_stats.as_mut() else {
+            return Ok(());
+        };
+        referral_stats.total_volume = referral_stats
+            .total_volume
+            .checked_add(amount)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        referral_stats.deposit_count = referral_stats
+            .deposit_count
+            .checked_add(1)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        referral_stats.total_fees_earned = referral_stats
+            .total_fees_earned
+            .checked_add(referral_fee)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        emit!(ReferredDeposit {
+            receipt_state: self.receipt_state.key(),
+            referrer: referral_stats.referrer,
+            depositor: self.user.key(),
+            recipient: self.recipient.key(),
+            amount,
+            receipts,
+            referral_fee,
+            total_volume: referral_stats.total_volume,
+            deposit_count: referral_stats.deposit_count,
+        });
+        Ok(())
+    }
+}
+
+/// Pay the protocol's share of a deposit fee to the fee receiver, less the
+/// insurance cut, which goes to the insurance vault.
+#[allow(clippy::too_many_arguments)]
+pub fn pay_protocol_fee<'info>(
+    receipt_state: &Account<'info, ReceiptState>,
+    protocol_fee: u64,
+    payer: AccountInfo<'info>,
+    payer_token_account: AccountInfo<'info>,
+    fee_receiver: Option<&InterfaceAccount<'info, TokenAccount>>,
+    insurance_vault: Option<&mut InterfaceAccount<'info, TokenAccount>>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_mint_program: AccountInfo<'info>,
+) -> Result<()> {
+    let insurance_cut = receipt_state.insurance_cut(protocol_fee)?;
+    if insurance_cut > 0 {
+        let insurance_vault = insurance_vault.ok_or(ReceiptErrorCode::InvalidInsuranceVault)?;
+        let before = insurance_vault.amount;
+        transfer_from_user_to_token_vault(
+            payer.clone(),
+            payer_token_account.clone(),
+            insurance_vault.to_account_info(),
+            token_mint.to_account_info(),
+            token_mint_program.clone(),
+            insurance_cut,
+            token_mint.decimals,
+        )?;
+        insurance_vault.reload()?;
+        emit!(InsuranceFunded {
+            receipt_state: receipt_state.key(),
+            amount: insurance_vault.amount - before,
+            balance: insurance_vault.amount,
+        });
+    }
+    let fee_receiver = fee_receiver.ok_or(ReceiptErrorCode::InvalidFeeReceiver)?;
     transfer_from_user_to_token_vault(
-        ctx.accounts.user.to_account_info(),
-        ctx.accounts.user_mint_token_account.to_account_info(),
-        ctx.accounts.token_mint_vault.to_account_info(),
-        ctx.accounts.token_mint.to_account_info(),
-        ctx.accounts.token_mint_program.to_account_info(),
-        amount,
-        ctx.accounts.token_mint.decimals,
-    )?;
-    msg!("Transferring tokens from user to vault done");
-    // Mint receipt tokens
-    let signer_seeds: &[&[&[u8]]] = &[&[
-        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(), 
-        receipt_state_pubkey.as_ref(),
-        &[ctx.bumps.vault_authority],
-    ]];
-    msg!("Minting receipt tokens");
-    let cpi_accounts = token_interface::MintTo {
-        mint: ctx.accounts.crypto_receipt_mint.to_account_info(),
-        to: ctx.accounts.user_crypto_receipt_token_account.to_account_info(),
-        authority: ctx.accounts.vault_authority.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.crypto_receipt_mint_program.to_account_info();
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts).with_signer(signer_seeds);
-    token_interface::mint_to(cpi_ctx, amount)?;
+        payer,
+        payer_token_account,
+        fee_receiver.to_account_info(),
+        token_mint.to_account_info(),
+        token_mint_program,
+        protocol_fee - insurance_cut,
+        token_mint.decimals,
+    )
+}
+
+/// Revalue the market's stake pool tokens when the pool and its vault are
+/// passed, then require a value from this epoch to price receipts against.
+pub fn revalue_stake_pool_tokens(
+    receipt_state: &mut ReceiptState,
+    stake_pool: Option<&UncheckedAccount>,
+    stake_pool_vault: Option<&InterfaceAccount<TokenAccount>>,
+) -> Result<()> {
+    if let (Some(stake_pool), Some(stake_pool_vault)) = (stake_pool, stake_pool_vault) {
+        let pool = StakePoolState::load_current(&stake_pool.to_account_info())?;
+        receipt_state.set_stake_pool_value(&pool, stake_pool_vault.amount)?;
+    }
+    receipt_state.require_fresh_stake_pool_value()
+}
+
+pub fn handle_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+    ctx.accounts.deposit_underlying(amount)?;
     Ok(())
 } 
\ No newline at end of file
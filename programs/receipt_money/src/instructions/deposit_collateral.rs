@@ -0,0 +1,73 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::TokenAccount;
+use crate::{
+    errors::ReceiptErrorCode,
+    state::Obligation,
+    utils::transfer_checked_with_hook,
+};
+use super::lending_market::*;
+
+#[derive(Accounts)]
+pub struct DepositCollateral<'info> {
+    pub market: LendingMarket<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            Obligation::OBLIGATION_SEED.as_bytes(),
+            market.reserve.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = Obligation::LEN,
+    )]
+    pub obligation: Box<Account<'info, Obligation>>,
+
+    #[account(mut, token::mint = market.crypto_receipt_mint, token::authority = owner)]
+    pub owner_crypto_receipt_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Lock receipts as collateral in the owner's obligation. The remaining
+/// accounts carry the receipt mint's transfer hook accounts.
+pub fn handle_deposit_collateral<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DepositCollateral<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ReceiptErrorCode::InvalidInput);
+    let market = &mut ctx.accounts.market;
+    transfer_checked_with_hook(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.owner_crypto_receipt_token_account.to_account_info(),
+        market.collateral_vault.to_account_info(),
+        market.crypto_receipt_mint.to_account_info(),
+        market.crypto_receipt_mint_program.to_account_info(),
+        amount,
+        market.crypto_receipt_mint.decimals,
+        ctx.remaining_accounts,
+        &[],
+    )?;
+    market.reserve.total_collateral = market
+        .reserve
+        .total_collateral
+        .checked_add(amount)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+
+    let obligation = &mut ctx.accounts.obligation;
+    obligation.reserve = market.reserve.key();
+    obligation.owner = ctx.accounts.owner.key();
+    obligation.bump = ctx.bumps.obligation;
+    obligation.collateral = obligation
+        .collateral
+        .checked_add(amount)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    Ok(())
+}
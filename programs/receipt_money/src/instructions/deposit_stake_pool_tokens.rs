@@ -0,0 +1,141 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    events::StakePoolValueRefreshed,
+    state::{ReceiptState, StakePoolState},
+    utils::{
+        fee_amount, get_transfer_fee, token_mint_to, transfer_from_user_to_token_vault,
+        underlying_to_receipts,
+    },
+};
+
+#[derive(Accounts)]
+pub struct DepositStakePoolTokens<'info> {
+    pub user: Signer<'info>,
+
+    #[account(mut, token::mint = pool_mint, token::authority = user)]
+    pub user_pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = crypto_receipt_mint)]
+    pub user_crypto_receipt_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = token_mint_vault,
+        has_one = crypto_receipt_mint,
+        has_one = stake_pool,
+        has_one = stake_pool_vault,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    /// CHECK: Parsed by `StakePoolState::load`
+    pub stake_pool: UncheckedAccount<'info>,
+
+    pub pool_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub stake_pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: This is both vault authority and mint authority of crToken
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Spl token program or token program 2022
+    pub pool_mint_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub crypto_receipt_mint_program: Interface<'info, TokenInterface>,
+}
+
+/// Deposit stake pool tokens and mint receipts for the lamports they are
+/// worth at the pool's current rate. Returns the receipts minted.
+///
+/// Unlike `deposit`, the deposit fee is withheld from the receipts minted
+/// rather than paid out through `pay_protocol_fee`: the fee receiver, referrer
+/// and insurance vault hold the underlying, not pool tokens, and the vault
+/// cannot pay them in SOL without selling pool tokens. The fee's value stays
+/// with the market, backing every receipt holder.
+pub fn handle_deposit_stake_pool_tokens(ctx: Context<DepositStakePoolTokens>, amount: u64) -> Result<u64> {
+    require!(amount > 0, ReceiptErrorCode::InvalidInput);
+    let pool = StakePoolState::load_current(&ctx.accounts.stake_pool.to_account_info())?;
+    require_keys_eq!(pool.pool_mint, ctx.accounts.pool_mint.key(), ReceiptErrorCode::InvalidStakePool);
+
+    // Revalue the pool tokens already held so the deposit is priced at the current rate
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    receipt_state.set_stake_pool_value(&pool, ctx.accounts.stake_pool_vault.amount)?;
+    let received = amount
+        .checked_sub(get_transfer_fee(&ctx.accounts.pool_mint.to_account_info(), amount)?)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    let value = pool.lamports_for(received)?;
+    let net_value = value
+        .checked_sub(fee_amount(value, receipt_state.deposit_fee_bps)?)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    let receipts = underlying_to_receipts(
+        net_value,
+        receipt_state.total_underlying(ctx.accounts.token_mint_vault.amount)?,
+        receipt_state.receipt_supply(ctx.accounts.crypto_receipt_mint.supply)?,
+    )?;
+    require!(receipts > 0, ReceiptErrorCode::InvalidInput);
+    // A deposit the user cannot pay for must not count against the inflow limit
+    require!(
+        amount <= ctx.accounts.user_pool_token_account.amount,
+        ReceiptErrorCode::InsufficientBalance
+    );
+    let receipt_state_key = receipt_state.key();
+    if !receipt_state.record_flow(receipt_state_key, ctx.accounts.token_mint_vault.amount, value, 0)? {
+        return Ok(0);
+    }
+
+    transfer_from_user_to_token_vault(
+        ctx.accounts.user.to_account_info(),
+        ctx.accounts.user_pool_token_account.to_account_info(),
+        ctx.accounts.stake_pool_vault.to_account_info(),
+        ctx.accounts.pool_mint.to_account_info(),
+        ctx.accounts.pool_mint_program.to_account_info(),
+        amount,
+        ctx.accounts.pool_mint.decimals,
+    )?;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+        receipt_state_key.as_ref(),
+        &[ctx.accounts.receipt_state.vault_authority_bump],
+    ]];
+    token_mint_to(
+        ctx.accounts.vault_authority.to_account_info(),
+        ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+        ctx.accounts.crypto_receipt_mint.to_account_info(),
+        ctx.accounts.user_crypto_receipt_token_account.to_account_info(),
+        receipts,
+        signer_seeds,
+    )?;
+
+    ctx.accounts.stake_pool_vault.reload()?;
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    receipt_state.set_stake_pool_value(&pool, ctx.accounts.stake_pool_vault.amount)?;
+    emit!(StakePoolValueRefreshed {
+        receipt_state: receipt_state_key,
+        pool_tokens: ctx.accounts.stake_pool_vault.amount,
+        value: receipt_state.stake_pool_value,
+        epoch: pool.last_update_epoch,
+    });
+    Ok(receipts)
+}
@@ -0,0 +1,147 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    state::ReceiptState,
+    utils::{mul_div_floor, token_burn, transfer_from_pool_vault_to_user},
+};
+
+#[derive(Accounts)]
+pub struct EmergencyRedeem<'info> {
+    pub user: Signer<'info>,
+
+    #[account(mut, token::mint = token_mint)]
+    pub user_mint_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = crypto_receipt_mint, token::authority = user)]
+    pub user_crypto_receipt_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = token_mint,
+        has_one = token_mint_vault,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: This is both vault authority and mint authority of crToken
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Spl token program or token program 2022
+    pub token_mint_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub crypto_receipt_mint_program: Interface<'info, TokenInterface>,
+
+    /// Market stake pool vault, required when stake pool tokens were held at shutdown
+    #[account(mut, address = receipt_state.stake_pool_vault @ ReceiptErrorCode::InvalidStakePool)]
+    pub stake_pool_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub user_pool_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub pool_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Spl token program or token program 2022
+    pub pool_mint_program: Option<Interface<'info, TokenInterface>>,
+}
+
+/// Burn `receipt_amount` of the user's receipts for their pro-rata share of
+/// what backed the market at shutdown, losses included.
+/// Returns the underlying paid out.
+pub fn handle_emergency_redeem(ctx: Context<EmergencyRedeem>, receipt_amount: u64) -> Result<u64> {
+    require!(receipt_amount > 0, ReceiptErrorCode::InvalidInput);
+    let receipt_state = &ctx.accounts.receipt_state;
+    require!(receipt_state.shutdown, ReceiptErrorCode::MarketNotShutdown);
+    let underlying = mul_div_floor(
+        receipt_amount,
+        receipt_state.shutdown_underlying,
+        receipt_state.shutdown_receipt_supply,
+    )?;
+    let pool_tokens = mul_div_floor(
+        receipt_amount,
+        receipt_state.shutdown_stake_pool_tokens,
+        receipt_state.shutdown_receipt_supply,
+    )?;
+
+    token_burn(
+        ctx.accounts.user.to_account_info(),
+        ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+        ctx.accounts.crypto_receipt_mint.to_account_info(),
+        ctx.accounts.user_crypto_receipt_token_account.to_account_info(),
+        receipt_amount,
+        &[],
+    )?;
+    let receipt_state_key = receipt_state.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+        receipt_state_key.as_ref(),
+        &[receipt_state.vault_authority_bump],
+    ]];
+    if underlying > 0 {
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.vault_authority.to_account_info(),
+            ctx.accounts.token_mint_vault.to_account_info(),
+            ctx.accounts.user_mint_token_account.to_account_info(),
+            ctx.accounts.token_mint.to_account_info(),
+            ctx.accounts.token_mint_program.to_account_info(),
+            underlying,
+            ctx.accounts.token_mint.decimals,
+            signer_seeds,
+        )?;
+    }
+    if pool_tokens > 0 {
+        let (Some(stake_pool_vault), Some(user_pool_token_account), Some(pool_mint), Some(pool_mint_program)) = (
+            ctx.accounts.stake_pool_vault.as_ref(),
+            ctx.accounts.user_pool_token_account.as_ref(),
+            ctx.accounts.pool_mint.as_ref(),
+            ctx.accounts.pool_mint_program.as_ref(),
+        ) else {
+            return err!(ReceiptErrorCode::InvalidStakePool);
+        };
+        require_keys_eq!(stake_pool_vault.mint, pool_mint.key(), ReceiptErrorCode::InvalidStakePool);
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.vault_authority.to_account_info(),
+            stake_pool_vault.to_account_info(),
+            user_pool_token_account.to_account_info(),
+            pool_mint.to_account_info(),
+            pool_mint_program.to_account_info(),
+            pool_tokens,
+            pool_mint.decimals,
+            signer_seeds,
+        )?;
+    }
+
+    // Keep the snapshot ratio for the holders still to redeem
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    receipt_state.shutdown_underlying -= underlying;
+    receipt_state.shutdown_stake_pool_tokens -= pool_tokens;
+    receipt_state.shutdown_receipt_supply -= receipt_amount;
+    if receipt_state.shutdown_stake_pool_tokens == 0 {
+        // The vault is empty, nothing left to value
+        receipt_state.stake_pool_value = 0;
+    }
+    Ok(underlying)
+}
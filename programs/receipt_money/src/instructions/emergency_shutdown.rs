@@ -0,0 +1,70 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::{errors::ReceiptErrorCode, events::EmergencyShutdown, state::ReceiptState};
+
+#[derive(Accounts)]
+pub struct EmergencyShutdownMarket<'info> {
+    #[account(
+        constraint = guardian.key() == receipt_state.guardian
+            || guardian.key() == receipt_state.authority
+            @ ReceiptErrorCode::Unauthorized,
+    )]
+    pub guardian: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = token_mint_vault,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Market stake pool vault, required once a stake pool is set
+    #[account(address = receipt_state.stake_pool_vault @ ReceiptErrorCode::InvalidStakePool)]
+    pub stake_pool_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+}
+
+/// Shut the market down for good and snapshot what backs each receipt, so
+/// every holder redeems the same share through `emergency_redeem`.
+pub fn handle_emergency_shutdown(ctx: Context<EmergencyShutdownMarket>) -> Result<()> {
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    require!(!receipt_state.shutdown, ReceiptErrorCode::MarketShutdown);
+    require!(
+        receipt_state.flash_loan_outstanding == 0,
+        ReceiptErrorCode::FlashLoanInProgress
+    );
+    let stake_pool_tokens = if receipt_state.stake_pool_vault == Pubkey::default() {
+        0
+    } else {
+        ctx.accounts
+            .stake_pool_vault
+            .as_ref()
+            .ok_or(ReceiptErrorCode::InvalidStakePool)?
+            .amount
+    };
+
+    receipt_state.shutdown = true;
+    receipt_state.shutdown_underlying = ctx.accounts.token_mint_vault.amount;
+    receipt_state.shutdown_stake_pool_tokens = stake_pool_tokens;
+    receipt_state.shutdown_receipt_supply = receipt_state.receipt_supply(ctx.accounts.crypto_receipt_mint.supply)?;
+    emit!(EmergencyShutdown {
+        receipt_state: receipt_state.key(),
+        triggered_by: ctx.accounts.guardian.key(),
+        underlying: receipt_state.shutdown_underlying,
+        stake_pool_tokens,
+        receipt_supply: receipt_state.shutdown_receipt_supply,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
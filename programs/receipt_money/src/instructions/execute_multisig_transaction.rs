@@ -0,0 +1,67 @@
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{Multisig, MultisigTransaction},
+};
+
+#[derive(Accounts)]
+pub struct ExecuteMultisigTransaction<'info> {
+    /// Not mutable here so a transaction changing the multisig itself is not
+    /// overwritten when this instruction exits
+    pub multisig: Box<Account<'info, Multisig>>,
+
+    /// CHECK: Signs the stored instruction
+    #[account(
+        seeds = [Multisig::MULTISIG_SIGNER_SEED.as_bytes(), multisig.key().as_ref()],
+        bump = multisig.signer_bump,
+    )]
+    pub multisig_signer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = proposer,
+        has_one = multisig,
+        has_one = proposer,
+        constraint = !transaction.executed @ ReceiptErrorCode::AlreadyExecuted,
+        constraint = transaction.owner_set_seqno == multisig.owner_set_seqno
+            @ ReceiptErrorCode::StaleMultisigTransaction,
+    )]
+    pub transaction: Box<Account<'info, MultisigTransaction>>,
+
+    /// CHECK: Gets the transaction rent back
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+/// Permissionless once the threshold is met. The stored instruction's
+/// accounts and program are passed as remaining accounts, and the
+/// transaction is closed to its proposer afterwards.
+pub fn handle_execute_multisig_transaction<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteMultisigTransaction<'info>>,
+) -> Result<()> {
+    let multisig = &ctx.accounts.multisig;
+    require!(
+        ctx.accounts.transaction.approval_count() >= multisig.threshold as usize,
+        ReceiptErrorCode::NotEnoughApprovals
+    );
+
+    // Persist `executed` first so the stored instruction cannot replay itself
+    let transaction = &mut ctx.accounts.transaction;
+    transaction.executed = true;
+    transaction.exit(&crate::ID)?;
+
+    let multisig_key = multisig.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        Multisig::MULTISIG_SIGNER_SEED.as_bytes(),
+        multisig_key.as_ref(),
+        &[multisig.signer_bump],
+    ]];
+    let mut account_infos = ctx.remaining_accounts.to_vec();
+    account_infos.push(ctx.accounts.multisig_signer.to_account_info());
+    invoke_signed(
+        &transaction.instruction(&ctx.accounts.multisig_signer.key()),
+        &account_infos,
+        signer_seeds,
+    )
+    .map_err(Into::into)
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use crate::{
+    errors::ReceiptErrorCode,
+    events::ProposalExecuted,
+    state::{ForeignEmitter, MarketParamChange, Proposal, ReceiptState},
+};
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(
+        mut,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        mut,
+        close = proposer,
+        has_one = receipt_state,
+        has_one = proposer,
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+
+    /// CHECK: Gets the proposal rent back
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    /// Pays for a new emitter account, only needed by `ForeignEmitter` proposals
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>,
+
+    /// Emitter written by a `ForeignEmitter` proposal
+    #[account(
+        init_if_needed,
+        seeds = [
+            ForeignEmitter::FOREIGN_EMITTER_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            &proposal.change.foreign_emitter_chain().unwrap_or_default().to_le_bytes(),
+        ],
+        bump,
+        payer = payer,
+        space = ForeignEmitter::LEN,
+    )]
+    pub foreign_emitter: Option<Box<Account<'info, ForeignEmitter>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Permissionless, applies a proposal once its eta has passed.
+pub fn handle_execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+    let proposal = &ctx.accounts.proposal;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= proposal.eta, ReceiptErrorCode::ProposalNotReady);
+    require!(
+        now <= proposal.eta.saturating_add(Proposal::GRACE_PERIOD),
+        ReceiptErrorCode::ProposalExpired
+    );
+
+    ctx.accounts
+        .receipt_state
+        .apply_param_change(proposal.change.clone())?;
+    if let MarketParamChange::ForeignEmitter { chain, address } = proposal.change {
+        let receipt_state = &mut ctx.accounts.receipt_state;
+        let foreign_emitter = ctx
+            .accounts
+            .foreign_emitter
+            .as_mut()
+            .ok_or(ReceiptErrorCode::MissingForeignEmitter)?;
+        if foreign_emitter.address == [0; 32] {
+            receipt_state.foreign_emitter_count = receipt_state
+                .foreign_emitter_count
+                .checked_add(1)
+                .ok_or(ReceiptErrorCode::MathOverflow)?;
+        }
+        foreign_emitter.set(
+            receipt_state.key(),
+            chain,
+            address,
+            ctx.bumps.foreign_emitter.ok_or(ReceiptErrorCode::MissingForeignEmitter)?,
+        );
+    }
+    emit!(ProposalExecuted {
+        receipt_state: ctx.accounts.receipt_state.key(),
+        proposal: proposal.key(),
+        id: proposal.id,
+    });
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+    solana_program::{
+        instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT},
+        sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+    },
+    Discriminator,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    instructions::flash_repay::FLASH_REPAY_RECEIPT_STATE_INDEX,
+    state::ReceiptState,
+    utils::transfer_from_pool_vault_to_user,
+};
+
+#[derive(Accounts)]
+pub struct FlashBorrow<'info> {
+    pub borrower: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = token_mint,
+        has_one = token_mint_vault,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: This is both vault authority and mint authority of crToken
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::token_program = token_mint_program,
+    )]
+    pub borrower_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Instructions sysvar used to find the matching `flash_repay`
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// Spl token program or token program 2022
+    pub token_mint_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handle_flash_borrow(ctx: Context<FlashBorrow>, amount: u64) -> Result<()> {
+    let receipt_state = &ctx.accounts.receipt_state;
+    require!(!receipt_state.shutdown, ReceiptErrorCode::MarketShutdown);
+    require!(receipt_state.flash_loan_enabled, ReceiptErrorCode::FlashLoanDisabled);
+    require!(
+        receipt_state.flash_loan_outstanding == 0,
+        ReceiptErrorCode::FlashLoanInProgress
+    );
+    require!(amount > 0, ReceiptErrorCode::InvalidInput);
+    // Introspection only sees top-level instructions, so a CPI caller could
+    // otherwise point us at a repay that belongs to someone else.
+    require!(
+        get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT,
+        ReceiptErrorCode::FlashLoanCpiNotAllowed
+    );
+    require_repay_follows(
+        &ctx.accounts.instructions.to_account_info(),
+        &receipt_state.key(),
+    )?;
+
+    let receipt_state_key = receipt_state.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+        receipt_state_key.as_ref(),
+        &[receipt_state.vault_authority_bump],
+    ]];
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.vault_authority.to_account_info(),
+        ctx.accounts.token_mint_vault.to_account_info(),
+        ctx.accounts.borrower_token_account.to_account_info(),
+        ctx.accounts.token_mint.to_account_info(),
+        ctx.accounts.token_mint_program.to_account_info(),
+        amount,
+        ctx.accounts.token_mint.decimals,
+        signer_seeds,
+    )?;
+
+    ctx.accounts.receipt_state.flash_loan_outstanding = amount;
+    Ok(())
+}
+
+/// Scan the instructions after the current one for a `flash_repay` against the same market.
+fn require_repay_follows(instructions: &AccountInfo, receipt_state: &Pubkey) -> Result<()> {
+    let current_index = load_current_index_checked(instructions)? as usize;
+    let mut index = current_index + 1;
+    while let Ok(ix) = load_instruction_at_checked(index, instructions) {
+        if ix.program_id == crate::ID
+            && ix.data.get(..8) == Some(&crate::instruction::FlashRepay::DISCRIMINATOR[..])
+            && ix
+                .accounts
+                .get(FLASH_REPAY_RECEIPT_STATE_INDEX)
+                .is_some_and(|meta| meta.pubkey == *receipt_state)
+        {
+            return Ok(());
+        }
+        index += 1;
+    }
+    err!(ReceiptErrorCode::MissingFlashRepay)
+}
@@ -0,0 +1,74 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    state::ReceiptState,
+    utils::{fee_amount, get_transfer_inverse_fee, transfer_from_user_to_token_vault},
+};
+
+/// Position of `receipt_state` in the `FlashRepay` accounts, checked by `flash_borrow`.
+pub const FLASH_REPAY_RECEIPT_STATE_INDEX: usize = 1;
+
+#[derive(Accounts)]
+pub struct FlashRepay<'info> {
+    pub repayer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = token_mint,
+        has_one = token_mint_vault,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::token_program = token_mint_program,
+    )]
+    pub repayer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022
+    pub token_mint_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handle_flash_repay(ctx: Context<FlashRepay>) -> Result<()> {
+    let receipt_state = &ctx.accounts.receipt_state;
+    let principal = receipt_state.flash_loan_outstanding;
+    require!(principal > 0, ReceiptErrorCode::NoFlashLoanOutstanding);
+
+    // The fee stays in the vault and raises the receipt exchange rate.
+    let fee = fee_amount(principal, receipt_state.flash_loan_fee_bps)?;
+    let repay_amount = principal
+        .checked_add(fee)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    // Gross up for Token-2022 transfer fees so the vault receives the full amount
+    let transfer_fee =
+        get_transfer_inverse_fee(&ctx.accounts.token_mint.to_account_info(), repay_amount)?;
+    transfer_from_user_to_token_vault(
+        ctx.accounts.repayer.to_account_info(),
+        ctx.accounts.repayer_token_account.to_account_info(),
+        ctx.accounts.token_mint_vault.to_account_info(),
+        ctx.accounts.token_mint.to_account_info(),
+        ctx.accounts.token_mint_program.to_account_info(),
+        repay_amount
+            .checked_add(transfer_fee)
+            .ok_or(ReceiptErrorCode::MathOverflow)?,
+        ctx.accounts.token_mint.decimals,
+    )?;
+
+    ctx.accounts.receipt_state.flash_loan_outstanding = 0;
+    Ok(())
+}
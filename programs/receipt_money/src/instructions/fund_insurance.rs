@@ -0,0 +1,60 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    events::InsuranceFunded,
+    state::ReceiptState,
+    utils::transfer_from_user_to_token_vault,
+};
+
+#[derive(Accounts)]
+pub struct FundInsurance<'info> {
+    pub funder: Signer<'info>,
+
+    #[account(mut, token::mint = token_mint, token::authority = funder)]
+    pub funder_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        has_one = token_mint,
+        has_one = insurance_vault @ ReceiptErrorCode::InvalidInsuranceVault,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub insurance_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022
+    pub token_mint_program: Interface<'info, TokenInterface>,
+}
+
+/// Top up the insurance vault. Anyone may contribute.
+pub fn handle_fund_insurance(ctx: Context<FundInsurance>, amount: u64) -> Result<()> {
+    require!(amount > 0, ReceiptErrorCode::InvalidInput);
+    let before = ctx.accounts.insurance_vault.amount;
+    transfer_from_user_to_token_vault(
+        ctx.accounts.funder.to_account_info(),
+        ctx.accounts.funder_token_account.to_account_info(),
+        ctx.accounts.insurance_vault.to_account_info(),
+        ctx.accounts.token_mint.to_account_info(),
+        ctx.accounts.token_mint_program.to_account_info(),
+        amount,
+        ctx.accounts.token_mint.decimals,
+    )?;
+    ctx.accounts.insurance_vault.reload()?;
+    emit!(InsuranceFunded {
+        receipt_state: ctx.accounts.receipt_state.key(),
+        amount: ctx.accounts.insurance_vault.amount - before,
+        balance: ctx.accounts.insurance_vault.amount,
+    });
+    Ok(())
+}
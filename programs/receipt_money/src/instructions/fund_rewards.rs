@@ -0,0 +1,91 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    events::RewardsFunded,
+    state::{ReceiptState, RewardPool},
+    utils::transfer_from_user_to_token_vault,
+};
+
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        mut,
+        has_one = receipt_state,
+        has_one = reward_mint,
+        has_one = reward_vault,
+    )]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+
+    #[account(mut)]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, token::mint = reward_mint, token::authority = authority)]
+    pub authority_reward_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022
+    pub reward_mint_program: Interface<'info, TokenInterface>,
+}
+
+/// Add `amount` reward tokens and spread everything not yet emitted, plus
+/// anything emitted while nothing was staked, evenly until `emission_end_ts`.
+/// What does not divide evenly stays in `unallocated_rewards`.
+pub fn handle_fund_rewards(ctx: Context<FundRewards>, amount: u64, emission_end_ts: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(emission_end_ts > now, ReceiptErrorCode::InvalidEmissionSchedule);
+    let total_staked = ctx.accounts.receipt_state.total_staked;
+    ctx.accounts.reward_pool.update(now, total_staked)?;
+
+    // Count what the vault received, the reward mint may charge a transfer fee
+    let vault_before = ctx.accounts.reward_vault.amount;
+    transfer_from_user_to_token_vault(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.authority_reward_token_account.to_account_info(),
+        ctx.accounts.reward_vault.to_account_info(),
+        ctx.accounts.reward_mint.to_account_info(),
+        ctx.accounts.reward_mint_program.to_account_info(),
+        amount,
+        ctx.accounts.reward_mint.decimals,
+    )?;
+    ctx.accounts.reward_vault.reload()?;
+    let received = ctx.accounts.reward_vault.amount - vault_before;
+
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    let unemitted = reward_pool
+        .remaining_emissions(now)?
+        .checked_add(received)
+        .and_then(|unemitted| unemitted.checked_add(reward_pool.unallocated_rewards))
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    let duration = (emission_end_ts - now) as u64;
+    let emission_per_second = unemitted / duration;
+    require!(emission_per_second > 0, ReceiptErrorCode::InvalidEmissionSchedule);
+    reward_pool.emission_per_second = emission_per_second;
+    reward_pool.emission_end_ts = emission_end_ts;
+    reward_pool.unallocated_rewards = unemitted % duration;
+
+    emit!(RewardsFunded {
+        receipt_state: reward_pool.receipt_state,
+        reward_pool: reward_pool.key(),
+        amount: received,
+        emission_per_second,
+        emission_end_ts,
+    });
+    Ok(())
+}
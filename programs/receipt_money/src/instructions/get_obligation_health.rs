@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{Obligation, ObligationHealth},
+};
+use super::lending_market::*;
+
+#[derive(Accounts)]
+pub struct GetObligationHealth<'info> {
+    pub market: LendingMarketView<'info>,
+
+    #[account(constraint = obligation.reserve == market.reserve.key() @ ReceiptErrorCode::InvalidInput)]
+    pub obligation: Box<Account<'info, Obligation>>,
+}
+
+/// Collateral value, debt with interest accrued to now, and health factor of an obligation.
+pub fn handle_get_obligation_health(ctx: Context<GetObligationHealth>) -> Result<ObligationHealth> {
+    let (reserve, pricing) = ctx.accounts.market.refresh()?;
+    ObligationHealth::new(&reserve, &ctx.accounts.obligation, &pricing)
+}
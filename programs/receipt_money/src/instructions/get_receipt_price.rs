@@ -0,0 +1,74 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::{
+    errors::ReceiptErrorCode,
+    state::ReceiptState,
+    utils::{get_checked_price, mul_div_floor},
+};
+
+/// USD price of one whole receipt token, in the oracle's exponent.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ReceiptPrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+#[derive(Accounts)]
+pub struct GetReceiptPrice<'info> {
+    #[account(
+        has_one = token_mint_vault,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Validated against the configured oracle in `get_checked_price`
+    pub oracle: UncheckedAccount<'info>,
+}
+
+pub fn handle_get_receipt_price(ctx: Context<GetReceiptPrice>) -> Result<ReceiptPrice> {
+    let oracle_price = get_checked_price(
+        &ctx.accounts.receipt_state.oracle,
+        &ctx.accounts.oracle.to_account_info(),
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    // The receipt mint shares the underlying decimals, so the raw vault/supply
+    // ratio is the amount of underlying backing one receipt.
+    let total_underlying = ctx
+        .accounts
+        .receipt_state
+        .total_underlying(ctx.accounts.token_mint_vault.amount)?;
+    let receipt_supply = ctx
+        .accounts
+        .receipt_state
+        .receipt_supply(ctx.accounts.crypto_receipt_mint.supply)?;
+    let (price, conf) = if receipt_supply == 0 {
+        (oracle_price.price as u64, oracle_price.conf)
+    } else {
+        (
+            mul_div_floor(oracle_price.price as u64, total_underlying, receipt_supply)?,
+            mul_div_floor(oracle_price.conf, total_underlying, receipt_supply)?,
+        )
+    };
+
+    Ok(ReceiptPrice {
+        price: i64::try_from(price).map_err(|_| error!(ReceiptErrorCode::MathOverflow))?,
+        conf,
+        expo: oracle_price.expo,
+        publish_time: oracle_price.publish_time,
+    })
+}
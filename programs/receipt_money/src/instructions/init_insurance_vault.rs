@@ -0,0 +1,75 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    state::ReceiptState,
+    utils::create_token_account,
+};
+
+#[derive(Accounts)]
+pub struct InitInsuranceVault<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = token_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+        constraint = receipt_state.insurance_vault == Pubkey::default() @ ReceiptErrorCode::InvalidInsuranceVault,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Created below, holds the insurance fund
+    #[account(
+        mut,
+        seeds = [
+            ReceiptState::INSURANCE_VAULT_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub insurance_vault: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: Owns the insurance vault
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Spl token program or token program 2022
+    pub token_mint_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_init_insurance_vault(ctx: Context<InitInsuranceVault>) -> Result<()> {
+    let receipt_state_key = ctx.accounts.receipt_state.key();
+    create_token_account(
+        &ctx.accounts.vault_authority.to_account_info(),
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.insurance_vault.to_account_info(),
+        &ctx.accounts.token_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.token_mint_program.to_account_info(),
+        &[
+            ReceiptState::INSURANCE_VAULT_SEED.as_bytes(),
+            receipt_state_key.as_ref(),
+            &[ctx.bumps.insurance_vault][..],
+        ][..],
+    )?;
+    ctx.accounts.receipt_state.insurance_vault = ctx.accounts.insurance_vault.key();
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{AdminAction, LendingReserve, ReceiptState},
+    utils::{create_token_account, is_supported_mint, EXCHANGE_RATE_SCALE},
+};
+
+#[derive(Accounts)]
+pub struct InitLendingReserve<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    pub borrow_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        seeds = [
+            LendingReserve::LENDING_RESERVE_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            borrow_mint.key().as_ref(),
+        ],
+        bump,
+        payer = authority,
+        space = LendingReserve::LEN,
+    )]
+    pub reserve: Box<Account<'info, LendingReserve>>,
+
+    /// CHECK: Created below, holds the lendable liquidity
+    #[account(
+        mut,
+        seeds = [
+            LendingReserve::RESERVE_LIQUIDITY_SEED.as_bytes(),
+            reserve.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub liquidity_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Created below, holds the receipts locked as collateral
+    #[account(
+        mut,
+        seeds = [
+            LendingReserve::RESERVE_COLLATERAL_SEED.as_bytes(),
+            reserve.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub collateral_vault: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: Owns the reserve vaults
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Spl token program or token program 2022
+    pub borrow_mint_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub crypto_receipt_mint_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Needs an approved `AdminAction` on a timelocked market.
+pub fn handle_init_lending_reserve(
+    ctx: Context<InitLendingReserve>,
+    ltv_bps: u16,
+    liquidation_threshold_bps: u16,
+    liquidation_bonus_bps: u16,
+    base_rate_bps: u16,
+    rate_slope_bps: u16,
+) -> Result<()> {
+    LendingReserve::validate_config(ltv_bps, liquidation_threshold_bps, liquidation_bonus_bps)?;
+    ctx.accounts
+        .receipt_state
+        .authorize_admin_action(&AdminAction::InitLendingReserve {
+            borrow_mint: ctx.accounts.borrow_mint.key(),
+            ltv_bps,
+            liquidation_threshold_bps,
+            liquidation_bonus_bps,
+            base_rate_bps,
+            rate_slope_bps,
+        })?;
+    require!(is_supported_mint(&ctx.accounts.borrow_mint)?, ReceiptErrorCode::InvalidInput);
+    // Anything but the underlying is valued through the market oracle
+    if ctx.accounts.borrow_mint.key() != ctx.accounts.receipt_state.token_mint {
+        require!(
+            ctx.accounts.receipt_state.oracle.is_configured(),
+            ReceiptErrorCode::OracleNotConfigured
+        );
+    }
+
+    let reserve_key = ctx.accounts.reserve.key();
+    create_token_account(
+        &ctx.accounts.vault_authority.to_account_info(),
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.liquidity_vault.to_account_info(),
+        &ctx.accounts.borrow_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.borrow_mint_program.to_account_info(),
+        &[
+            LendingReserve::RESERVE_LIQUIDITY_SEED.as_bytes(),
+            reserve_key.as_ref(),
+            &[ctx.bumps.liquidity_vault][..],
+        ][..],
+    )?;
+    create_token_account(
+        &ctx.accounts.vault_authority.to_account_info(),
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.collateral_vault.to_account_info(),
+        &ctx.accounts.crypto_receipt_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+        &[
+            LendingReserve::RESERVE_COLLATERAL_SEED.as_bytes(),
+            reserve_key.as_ref(),
+            &[ctx.bumps.collateral_vault][..],
+        ][..],
+    )?;
+
+    let reserve = &mut ctx.accounts.reserve;
+    reserve.receipt_state = ctx.accounts.receipt_state.key();
+    reserve.borrow_mint = ctx.accounts.borrow_mint.key();
+    reserve.liquidity_vault = ctx.accounts.liquidity_vault.key();
+    reserve.collateral_vault = ctx.accounts.collateral_vault.key();
+    reserve.ltv_bps = ltv_bps;
+    reserve.liquidation_threshold_bps = liquidation_threshold_bps;
+    reserve.liquidation_bonus_bps = liquidation_bonus_bps;
+    reserve.base_rate_bps = base_rate_bps;
+    reserve.rate_slope_bps = rate_slope_bps;
+    reserve.borrow_index = EXCHANGE_RATE_SCALE;
+    reserve.last_accrual_ts = Clock::get()?.unix_timestamp;
+    reserve.bump = ctx.bumps.reserve;
+
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    receipt_state.open_reserve_count = receipt_state
+        .open_reserve_count
+        .checked_add(1)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    Ok(())
+}
@@ -1,15 +1,35 @@
 use anchor_lang::{
-    accounts::interface_account::InterfaceAccount,
     prelude::*,
     solana_program::rent::{DEFAULT_EXEMPTION_THRESHOLD, DEFAULT_LAMPORTS_PER_BYTE_YEAR},
     system_program::{transfer, Transfer},
 };
-use anchor_spl::token_interface::{
-    Mint, TokenInterface, token_metadata_initialize, TokenMetadataInitialize,
+use anchor_spl::{
+    metadata::Metadata,
+    token_2022::{
+        spl_token_2022::extension::ExtensionType,
+        Token2022,
+    },
+    token_2022_extensions::{
+        interest_bearing_mint_initialize, transfer_hook_initialize,
+        InterestBearingMintInitialize, TransferHookInitialize,
+    },
+    token_interface::{
+        initialize_mint2, metadata_pointer_initialize, mint_close_authority_initialize,
+        token_metadata_initialize, InitializeMint2, Mint, MetadataPointerInitialize,
+        MintCloseAuthorityInitialize, TokenInterface, TokenMetadataInitialize,
+    },
 };
+use receipt_hook::program::ReceiptHook;
 use spl_token_metadata_interface::state::TokenMetadata;
+use crate::errors::ReceiptErrorCode;
 use crate::state::ReceiptState;
-use crate::utils::token::create_token_account;
+use crate::utils::{
+    token::{
+        confidential_transfer_initialize_mint, create_metaplex_metadata, create_mint_account,
+        create_token_account,
+    },
+    EXCHANGE_RATE_SCALE,
+};
 use spl_type_length_value::variable_len_pack::VariableLenPack;
 
 #[derive(AnchorDeserialize, AnchorSerialize)]
@@ -60,22 +80,17 @@ pub struct Initialize<'info> {
     )]
     pub token_mint_vault: UncheckedAccount<'info>,
     
+    /// CHECK: This is the crypto receipt mint, created in the handler because
+    /// its extensions depend on `crypto_receipt_mint_program`
     #[account(
-        init,
+        mut,
         seeds = [
             ReceiptState::MINT_SEED.as_bytes(),
             receipt_state.key().as_ref(),
         ],
         bump,
-        payer = authority,
-        mint::decimals = token_mint.decimals,
-        mint::authority = vault_authority,
-        mint::freeze_authority = vault_authority,
-        mint::token_program = crypto_receipt_mint_program,
-        extensions::metadata_pointer::authority = vault_authority,
-        extensions::metadata_pointer::metadata_address = crypto_receipt_mint,
     )]
-    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub crypto_receipt_mint: UncheckedAccount<'info>,
 
     /// CHECK: This is the crypto receipt mint vault
     #[account(
@@ -88,6 +103,24 @@ pub struct Initialize<'info> {
         bump,
     )]
     pub crypto_receipt_mint_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex metadata account, only used when the receipt mint is
+    /// a classic spl token mint. Validated by the metadata program.
+    #[account(mut)]
+    pub metadata: Option<UncheckedAccount<'info>>,
+
+    pub metadata_program: Option<Program<'info, Metadata>>,
+
+    /// Transfer hook attached to Token-2022 receipt mints when present
+    pub transfer_hook_program: Option<Program<'info, ReceiptHook>>,
+
+    /// CHECK: Extra account metas of the transfer hook, created by it
+    #[account(mut)]
+    pub extra_account_meta_list: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Hook config of the receipt mint, created by the transfer hook
+    #[account(mut)]
+    pub hook_config: Option<UncheckedAccount<'info>>,
     
     pub system_program: Program<'info, System>,
     /// Spl token program or token program 2022
@@ -100,8 +133,18 @@ pub struct Initialize<'info> {
 pub fn handle_initialize(
     ctx: Context<Initialize>, 
     args: TokenMetadataArgs,
+    interest_bearing: bool,
+    confidential_transfer_auditor: Option<[u8; 32]>,
 ) -> Result<()> {
     let TokenMetadataArgs { name, symbol, uri } = args;
+    let is_token_2022 = ctx.accounts.crypto_receipt_mint_program.key() == Token2022::id();
+    let receipt_state_key = ctx.accounts.receipt_state.key();
+    let auth_seeds = &[
+        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(), 
+        receipt_state_key.as_ref(), 
+        &[ctx.bumps.vault_authority]
+    ];
+    let signer = &[&auth_seeds[..]];
 
     // due to stack/heap limitations, we have to create redundant new token vault accounts ourselves
     create_token_account(
@@ -119,6 +162,124 @@ pub fn handle_initialize(
         ][..],
     )?;
 
+    let transfer_hook = match (
+        ctx.accounts.transfer_hook_program.as_ref(),
+        ctx.accounts.extra_account_meta_list.as_ref(),
+        ctx.accounts.hook_config.as_ref(),
+    ) {
+        (None, _, _) => None,
+        (Some(program), Some(extra_account_meta_list), Some(hook_config)) if is_token_2022 => {
+            Some((program, extra_account_meta_list, hook_config))
+        }
+        _ => return err!(ReceiptErrorCode::InvalidTransferHook),
+    };
+    require!(
+        !interest_bearing || is_token_2022,
+        ReceiptErrorCode::InterestBearingNotSupported
+    );
+    require!(
+        confidential_transfer_auditor.is_none() || is_token_2022,
+        ReceiptErrorCode::ConfidentialTransferNotSupported
+    );
+
+    // Token-2022 receipts embed their metadata in the mint and can be closed
+    // once empty, classic spl token receipts get a Metaplex metadata account
+    let mut extensions = Vec::new();
+    if is_token_2022 {
+        extensions.extend([ExtensionType::MetadataPointer, ExtensionType::MintCloseAuthority]);
+    }
+    if transfer_hook.is_some() {
+        extensions.push(ExtensionType::TransferHook);
+    }
+    if interest_bearing {
+        extensions.push(ExtensionType::InterestBearingConfig);
+    }
+    if confidential_transfer_auditor.is_some() {
+        extensions.push(ExtensionType::ConfidentialTransferMint);
+    }
+    create_mint_account(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.crypto_receipt_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+        &extensions,
+        &[
+            ReceiptState::MINT_SEED.as_bytes(),
+            ctx.accounts.receipt_state.key().as_ref(),
+            &[ctx.bumps.crypto_receipt_mint][..],
+        ][..],
+    )?;
+    let vault_authority_key = ctx.accounts.vault_authority.key();
+    if is_token_2022 {
+        metadata_pointer_initialize(
+            CpiContext::new(
+                ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+                MetadataPointerInitialize {
+                    token_program_id: ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+                    mint: ctx.accounts.crypto_receipt_mint.to_account_info(),
+                },
+            ),
+            Some(vault_authority_key),
+            Some(ctx.accounts.crypto_receipt_mint.key()),
+        )?;
+        mint_close_authority_initialize(
+            CpiContext::new(
+                ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+                MintCloseAuthorityInitialize {
+                    token_program_id: ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+                    mint: ctx.accounts.crypto_receipt_mint.to_account_info(),
+                },
+            ),
+            Some(&vault_authority_key),
+        )?;
+    }
+    if let Some((program, _, _)) = transfer_hook {
+        transfer_hook_initialize(
+            CpiContext::new(
+                ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+                TransferHookInitialize {
+                    token_program_id: ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+                    mint: ctx.accounts.crypto_receipt_mint.to_account_info(),
+                },
+            ),
+            Some(vault_authority_key),
+            Some(program.key()),
+        )?;
+    }
+    if interest_bearing {
+        // Starts at zero, `update_interest_rate` follows the realized yield
+        interest_bearing_mint_initialize(
+            CpiContext::new(
+                ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+                InterestBearingMintInitialize {
+                    token_program_id: ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+                    mint: ctx.accounts.crypto_receipt_mint.to_account_info(),
+                },
+            ),
+            Some(vault_authority_key),
+            0,
+        )?;
+    }
+    if confidential_transfer_auditor.is_some() {
+        confidential_transfer_initialize_mint(
+            &ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+            &ctx.accounts.crypto_receipt_mint.to_account_info(),
+            &vault_authority_key,
+            confidential_transfer_auditor,
+        )?;
+    }
+    initialize_mint2(
+        CpiContext::new(
+            ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+            InitializeMint2 {
+                mint: ctx.accounts.crypto_receipt_mint.to_account_info(),
+            },
+        ),
+        ctx.accounts.token_mint.decimals,
+        &vault_authority_key,
+        Some(&vault_authority_key),
+    )?;
+
     create_token_account(
         &ctx.accounts.vault_authority.to_account_info(),
         &ctx.accounts.authority.to_account_info(),
@@ -134,54 +295,90 @@ pub fn handle_initialize(
         ][..],
     )?;
 
-     // Define token metadata
-     let token_metadata = TokenMetadata {
-        name: name.clone(),
-        symbol: symbol.clone(),
-        uri: uri.clone(),
-        ..Default::default()
-    };
-     // Add 4 extra bytes for size of MetadataExtension (2 bytes for type, 2 bytes for length)
-     let data_len = 4 + token_metadata.get_packed_len()?;
-
-     // Calculate lamports required for the additional metadata
-     let lamports =
-         data_len as u64 * DEFAULT_LAMPORTS_PER_BYTE_YEAR * DEFAULT_EXEMPTION_THRESHOLD as u64;
-    // Transfer additional lamports to mint account for metadata
-    transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.authority.to_account_info(),
-                to: ctx.accounts.crypto_receipt_mint.to_account_info(),
-            },
-        ),
-        lamports,
-    )?;
-    let receipt_state_key = ctx.accounts.receipt_state.key();
-    let auth_seeds = &[
-        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(), 
-        receipt_state_key.as_ref(), 
-        &[ctx.bumps.vault_authority]
-    ];
-    let signer = &[&auth_seeds[..]];
-    // Initialize token metadata
-    token_metadata_initialize(
-        CpiContext::new_with_signer(
-            ctx.accounts.crypto_receipt_mint_program.to_account_info(),
-            TokenMetadataInitialize {
-                token_program_id: ctx.accounts.crypto_receipt_mint_program.to_account_info(),
-                mint: ctx.accounts.crypto_receipt_mint.to_account_info(),
-                metadata: ctx.accounts.crypto_receipt_mint.to_account_info(),
-                mint_authority: ctx.accounts.vault_authority.to_account_info(),
-                update_authority: ctx.accounts.vault_authority.to_account_info(),
-            },
-            signer,
-        ),
-        name,
-        symbol,
-        uri,
-    )?;
+    if let Some((program, extra_account_meta_list, hook_config)) = transfer_hook {
+        receipt_hook::cpi::initialize_extra_account_meta_list(
+            CpiContext::new_with_signer(
+                program.to_account_info(),
+                receipt_hook::cpi::accounts::InitializeExtraAccountMetaList {
+                    payer: ctx.accounts.authority.to_account_info(),
+                    mint_authority: ctx.accounts.vault_authority.to_account_info(),
+                    mint: ctx.accounts.crypto_receipt_mint.to_account_info(),
+                    receipt_state: ctx.accounts.receipt_state.to_account_info(),
+                    token_mint_vault: ctx.accounts.token_mint_vault.to_account_info(),
+                    extra_account_meta_list: extra_account_meta_list.to_account_info(),
+                    hook_config: hook_config.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                },
+                signer,
+            ),
+            ctx.accounts.authority.key(),
+        )?;
+    }
+
+    if is_token_2022 {
+        // Define token metadata
+        let token_metadata = TokenMetadata {
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            ..Default::default()
+        };
+        // Add 4 extra bytes for size of MetadataExtension (2 bytes for type, 2 bytes for length)
+        let data_len = 4 + token_metadata.get_packed_len()?;
+
+        // Calculate lamports required for the additional metadata
+        let lamports =
+            data_len as u64 * DEFAULT_LAMPORTS_PER_BYTE_YEAR * DEFAULT_EXEMPTION_THRESHOLD as u64;
+        // Transfer additional lamports to mint account for metadata
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.crypto_receipt_mint.to_account_info(),
+                },
+            ),
+            lamports,
+        )?;
+        // Initialize token metadata
+        token_metadata_initialize(
+            CpiContext::new_with_signer(
+                ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+                TokenMetadataInitialize {
+                    token_program_id: ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+                    mint: ctx.accounts.crypto_receipt_mint.to_account_info(),
+                    metadata: ctx.accounts.crypto_receipt_mint.to_account_info(),
+                    mint_authority: ctx.accounts.vault_authority.to_account_info(),
+                    update_authority: ctx.accounts.vault_authority.to_account_info(),
+                },
+                signer,
+            ),
+            name,
+            symbol,
+            uri,
+        )?;
+    } else {
+        let (Some(metadata), Some(metadata_program)) = (
+            ctx.accounts.metadata.as_ref(),
+            ctx.accounts.metadata_program.as_ref(),
+        ) else {
+            return err!(ReceiptErrorCode::MissingMetadataAccounts);
+        };
+        create_metaplex_metadata(
+            &name,
+            &symbol,
+            &uri,
+            &ctx.accounts.crypto_receipt_mint.to_account_info(),
+            &ctx.accounts.vault_authority.to_account_info(),
+            &ctx.accounts.vault_authority.to_account_info(),
+            metadata,
+            metadata_program,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.rent.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            auth_seeds,
+        )?;
+    }
 
     let receipt_state = &mut ctx.accounts.receipt_state;
     receipt_state.authority = ctx.accounts.authority.key();
@@ -194,5 +391,8 @@ pub fn handle_initialize(
     receipt_state.token_mint_vault_bump = ctx.bumps.token_mint_vault;
     receipt_state.receipt_mint_bump = ctx.bumps.crypto_receipt_mint;
     receipt_state.receipt_mint_vault_bump = ctx.bumps.crypto_receipt_mint_vault;
+    receipt_state.version = ReceiptState::CURRENT_VERSION;
+    receipt_state.interest_last_exchange_rate = EXCHANGE_RATE_SCALE;
+    receipt_state.interest_last_update_ts = Clock::get()?.unix_timestamp;
     Ok(())
-} 
\ No newline at end of file
+} 
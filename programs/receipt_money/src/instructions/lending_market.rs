@@ -0,0 +1,317 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{CollateralPricing, LendingReserve, Obligation, ReceiptState},
+    utils::{
+        get_checked_price, token_burn, token_mint_to, transfer_checked_with_hook,
+        transfer_from_pool_vault_to_user, transfer_from_user_to_token_vault,
+    },
+};
+
+/// Accounts shared by the lending instructions that touch a reserve.
+/// The market side is writable for the leverage instructions, which mint and
+/// redeem receipts against the vault.
+#[derive(Accounts)]
+pub struct LendingMarket<'info> {
+    #[account(
+        mut,
+        has_one = token_mint_vault,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        mut,
+        has_one = receipt_state,
+        has_one = borrow_mint,
+        has_one = liquidity_vault,
+        has_one = collateral_vault,
+        seeds = [
+            LendingReserve::LENDING_RESERVE_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            borrow_mint.key().as_ref(),
+        ],
+        bump = reserve.bump,
+    )]
+    pub reserve: Box<Account<'info, LendingReserve>>,
+
+    #[account(mut)]
+    pub liquidity_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub collateral_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub borrow_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Market oracle, checked by `get_checked_price`. Required when the
+    /// reserve lends a mint other than the underlying
+    pub oracle: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: Owns the reserve vaults
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Spl token program or token program 2022
+    pub borrow_mint_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub crypto_receipt_mint_program: Interface<'info, TokenInterface>,
+}
+
+/// Read-only side of `LendingMarket` for queries, which must not write lock
+/// the market or the reserve.
+#[derive(Accounts)]
+pub struct LendingMarketView<'info> {
+    #[account(
+        has_one = token_mint_vault,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        has_one = receipt_state,
+        has_one = borrow_mint,
+        has_one = liquidity_vault,
+        seeds = [
+            LendingReserve::LENDING_RESERVE_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            borrow_mint.key().as_ref(),
+        ],
+        bump = reserve.bump,
+    )]
+    pub reserve: Box<Account<'info, LendingReserve>>,
+
+    pub liquidity_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub borrow_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Market oracle, checked by `get_checked_price`. Required when the
+    /// reserve lends a mint other than the underlying
+    pub oracle: Option<UncheckedAccount<'info>>,
+}
+
+impl<'info> LendingMarketView<'info> {
+    /// The reserve with interest accrued up to now, left unwritten, and
+    /// receipts priced in borrow mint units.
+    pub fn refresh(&self) -> Result<(LendingReserve, CollateralPricing)> {
+        let now = Clock::get()?.unix_timestamp;
+        let mut reserve = LendingReserve::clone(&self.reserve);
+        reserve.accrue(now, self.liquidity_vault.amount)?;
+        let pricing = price_collateral(
+            &self.receipt_state,
+            &reserve,
+            self.oracle.as_ref(),
+            &self.token_mint_vault,
+            &self.crypto_receipt_mint,
+            &self.borrow_mint,
+            now,
+        )?;
+        Ok((reserve, pricing))
+    }
+}
+
+/// Price receipts in borrow mint units, through the market oracle unless the
+/// reserve lends the underlying.
+fn price_collateral(
+    receipt_state: &ReceiptState,
+    reserve: &LendingReserve,
+    oracle: Option<&UncheckedAccount>,
+    token_mint_vault: &TokenAccount,
+    crypto_receipt_mint: &Mint,
+    borrow_mint: &Mint,
+    now: i64,
+) -> Result<CollateralPricing> {
+    let price = if reserve.borrow_mint == receipt_state.token_mint {
+        None
+    } else {
+        let oracle = oracle.ok_or(ReceiptErrorCode::InvalidOracle)?;
+        Some(get_checked_price(&receipt_state.oracle, &oracle.to_account_info(), now)?)
+    };
+    Ok(CollateralPricing {
+        total_underlying: receipt_state.total_underlying(token_mint_vault.amount)?,
+        receipt_supply: receipt_state.receipt_supply(crypto_receipt_mint.supply)?,
+        price,
+        underlying_decimals: crypto_receipt_mint.decimals,
+        borrow_decimals: borrow_mint.decimals,
+    })
+}
+
+impl<'info> LendingMarket<'info> {
+    /// Accrue interest up to now and price receipts in borrow mint units.
+    pub fn refresh(&mut self) -> Result<CollateralPricing> {
+        let now = Clock::get()?.unix_timestamp;
+        self.reserve.accrue(now, self.liquidity_vault.amount)?;
+        price_collateral(
+            &self.receipt_state,
+            &self.reserve,
+            self.oracle.as_ref(),
+            &self.token_mint_vault,
+            &self.crypto_receipt_mint,
+            &self.borrow_mint,
+            now,
+        )
+    }
+
+    /// Move `amount` of the borrow mint from `authority`'s account into the
+    /// reserve. Returns what the reserve received.
+    pub fn receive_liquidity(
+        &mut self,
+        authority: AccountInfo<'info>,
+        from: AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<u64> {
+        let before = self.liquidity_vault.amount;
+        transfer_from_user_to_token_vault(
+            authority,
+            from,
+            self.liquidity_vault.to_account_info(),
+            self.borrow_mint.to_account_info(),
+            self.borrow_mint_program.to_account_info(),
+            amount,
+            self.borrow_mint.decimals,
+        )?;
+        self.liquidity_vault.reload()?;
+        Ok(self.liquidity_vault.amount - before)
+    }
+
+    /// Add `amount` of debt to `obligation`, rounding its shares up.
+    pub fn add_debt(&mut self, obligation: &mut Obligation, amount: u64) -> Result<()> {
+        let shares = self.reserve.shares_for(amount, true)?;
+        self.reserve.total_borrow_shares = self
+            .reserve
+            .total_borrow_shares
+            .checked_add(shares)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        obligation.borrow_shares = obligation
+            .borrow_shares
+            .checked_add(shares)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn pay_out_liquidity(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        require!(amount <= self.liquidity_vault.amount, ReceiptErrorCode::InsufficientLiquidity);
+        transfer_from_pool_vault_to_user(
+            self.vault_authority.to_account_info(),
+            self.liquidity_vault.to_account_info(),
+            to,
+            self.borrow_mint.to_account_info(),
+            self.borrow_mint_program.to_account_info(),
+            amount,
+            self.borrow_mint.decimals,
+            &[&self.vault_authority_seeds()],
+        )
+    }
+
+    /// Move `receipts` out of the collateral vault. `hook_accounts` carry the
+    /// receipt mint's transfer hook accounts, if it has a hook.
+    pub fn release_collateral(
+        &mut self,
+        to: AccountInfo<'info>,
+        receipts: u64,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        self.reserve.total_collateral -= receipts;
+        transfer_checked_with_hook(
+            self.vault_authority.to_account_info(),
+            self.collateral_vault.to_account_info(),
+            to,
+            self.crypto_receipt_mint.to_account_info(),
+            self.crypto_receipt_mint_program.to_account_info(),
+            receipts,
+            self.crypto_receipt_mint.decimals,
+            hook_accounts,
+            &[&self.vault_authority_seeds()],
+        )
+    }
+
+    /// Mint `receipts` straight into the collateral vault.
+    pub fn mint_collateral(&mut self, receipts: u64) -> Result<()> {
+        self.reserve.total_collateral = self
+            .reserve
+            .total_collateral
+            .checked_add(receipts)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        token_mint_to(
+            self.vault_authority.to_account_info(),
+            self.crypto_receipt_mint_program.to_account_info(),
+            self.crypto_receipt_mint.to_account_info(),
+            self.collateral_vault.to_account_info(),
+            receipts,
+            &[&self.vault_authority_seeds()],
+        )
+    }
+
+    /// Burn `receipts` out of the collateral vault.
+    pub fn burn_collateral(&mut self, receipts: u64) -> Result<()> {
+        self.reserve.total_collateral -= receipts;
+        token_burn(
+            self.vault_authority.to_account_info(),
+            self.crypto_receipt_mint_program.to_account_info(),
+            self.crypto_receipt_mint.to_account_info(),
+            self.collateral_vault.to_account_info(),
+            receipts,
+            &[&self.vault_authority_seeds()],
+        )
+    }
+
+    /// Move `amount` of underlying out of the market vault. Only valid for
+    /// reserves lending the underlying.
+    pub fn pay_out_underlying(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        transfer_from_pool_vault_to_user(
+            self.vault_authority.to_account_info(),
+            self.token_mint_vault.to_account_info(),
+            to,
+            self.borrow_mint.to_account_info(),
+            self.borrow_mint_program.to_account_info(),
+            amount,
+            self.borrow_mint.decimals,
+            &[&self.vault_authority_seeds()],
+        )
+    }
+
+    /// Re-read the balances and supply an instruction changed before pricing again.
+    pub fn reload(&mut self) -> Result<()> {
+        self.token_mint_vault.reload()?;
+        self.crypto_receipt_mint.reload()?;
+        self.liquidity_vault.reload()?;
+        self.collateral_vault.reload()
+    }
+
+    pub fn vault_authority_seeds(&self) -> [&[u8]; 3] {
+        [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            self.reserve.receipt_state.as_ref(),
+            std::slice::from_ref(&self.receipt_state.vault_authority_bump),
+        ]
+    }
+}
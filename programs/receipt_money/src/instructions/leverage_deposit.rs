@@ -0,0 +1,159 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::TokenAccount;
+use crate::{
+    errors::ReceiptErrorCode,
+    events::LeverageDeposited,
+    state::{Obligation, ObligationHealth},
+    utils::{
+        fee_amount, get_transfer_fee, mul_div_floor, transfer_from_user_to_token_vault,
+        underlying_to_receipts, BASIS_POINTS_DIVISOR,
+    },
+};
+use super::{deposit::pay_protocol_fee, lending_market::*};
+
+#[derive(Accounts)]
+pub struct LeverageDeposit<'info> {
+    pub market: LendingMarket<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            Obligation::OBLIGATION_SEED.as_bytes(),
+            market.reserve.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = Obligation::LEN,
+    )]
+    pub obligation: Box<Account<'info, Obligation>>,
+
+    #[account(mut, token::mint = market.borrow_mint, token::authority = owner)]
+    pub owner_mint_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Protocol fee account, required while deposit fees are enabled
+    #[account(
+        mut,
+        address = market.receipt_state.fee_receiver @ ReceiptErrorCode::InvalidFeeReceiver,
+    )]
+    pub fee_receiver: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Market insurance vault, required while an insurance fee share is set
+    #[account(
+        mut,
+        address = market.receipt_state.insurance_vault @ ReceiptErrorCode::InvalidInsuranceVault,
+    )]
+    pub insurance_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposit `amount` of underlying as `target_leverage_bps` times as much
+/// receipt collateral in one step. The vault flash-mints the whole position
+/// into the collateral vault, then the reserve lends the missing underlying
+/// into the vault against it. Deposit fees apply to the whole position, which
+/// lowers the leverage the LTV allows.
+/// Returns the receipts added to the obligation's collateral.
+pub fn handle_leverage_deposit(
+    ctx: Context<LeverageDeposit>,
+    amount: u64,
+    target_leverage_bps: u64,
+    min_collateral: u64,
+) -> Result<u64> {
+    require!(amount > 0, ReceiptErrorCode::InvalidInput);
+    let accounts = ctx.accounts;
+    let market = &mut accounts.market;
+    require_keys_eq!(
+        market.reserve.borrow_mint,
+        market.receipt_state.token_mint,
+        ReceiptErrorCode::LeverageUnavailable
+    );
+    let max_leverage_bps = market.reserve.max_leverage_bps(market.receipt_state.deposit_fee_bps)?;
+    require!(
+        target_leverage_bps >= BASIS_POINTS_DIVISOR && target_leverage_bps <= max_leverage_bps,
+        ReceiptErrorCode::InvalidLeverage
+    );
+    let obligation = &mut accounts.obligation;
+    obligation.reserve = market.reserve.key();
+    obligation.owner = accounts.owner.key();
+    obligation.bump = ctx.bumps.obligation;
+
+    // Price the position before any balance changes
+    let pricing = market.refresh()?;
+    let position = mul_div_floor(amount, target_leverage_bps, BASIS_POINTS_DIVISOR)?;
+    let borrowed = position - amount;
+    let fee = fee_amount(position, market.receipt_state.deposit_fee_bps)?;
+    let from_owner = amount.checked_sub(fee).ok_or(ReceiptErrorCode::InvalidInput)?;
+    let token_mint = market.borrow_mint.to_account_info();
+    let net_amount = (from_owner - get_transfer_fee(&token_mint, from_owner)?)
+        .checked_add(borrowed - get_transfer_fee(&token_mint, borrowed)?)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    let receipts = underlying_to_receipts(net_amount, pricing.total_underlying, pricing.receipt_supply)?;
+    require!(receipts > 0, ReceiptErrorCode::InvalidInput);
+    require!(receipts >= min_collateral, ReceiptErrorCode::SlippageExceeded);
+    // A deposit the owner cannot pay for must not count against the inflow limit
+    require!(
+        amount <= accounts.owner_mint_token_account.amount,
+        ReceiptErrorCode::InsufficientBalance
+    );
+    let receipt_state_key = market.receipt_state.key();
+    let vault_amount = market.token_mint_vault.amount;
+    if !market.receipt_state.record_flow(receipt_state_key, vault_amount, from_owner + borrowed, 0)? {
+        return Ok(0);
+    }
+
+    if fee > 0 {
+        pay_protocol_fee(
+            &market.receipt_state,
+            fee,
+            accounts.owner.to_account_info(),
+            accounts.owner_mint_token_account.to_account_info(),
+            accounts.fee_receiver.as_deref(),
+            accounts.insurance_vault.as_deref_mut(),
+            &market.borrow_mint,
+            market.borrow_mint_program.to_account_info(),
+        )?;
+    }
+    transfer_from_user_to_token_vault(
+        accounts.owner.to_account_info(),
+        accounts.owner_mint_token_account.to_account_info(),
+        market.token_mint_vault.to_account_info(),
+        token_mint,
+        market.borrow_mint_program.to_account_info(),
+        from_owner,
+        market.borrow_mint.decimals,
+    )?;
+    market.mint_collateral(receipts)?;
+    obligation.collateral = obligation
+        .collateral
+        .checked_add(receipts)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    // The reserve loan settles the flash mint
+    if borrowed > 0 {
+        market.add_debt(obligation, borrowed)?;
+        let token_mint_vault = market.token_mint_vault.to_account_info();
+        market.pay_out_liquidity(token_mint_vault, borrowed)?;
+    }
+
+    market.reload()?;
+    let pricing = market.refresh()?;
+    let health = ObligationHealth::new(&market.reserve, obligation, &pricing)?;
+    require!(
+        health.within_ltv(market.reserve.ltv_bps)?,
+        ReceiptErrorCode::BorrowLimitExceeded
+    );
+    emit!(LeverageDeposited {
+        reserve: market.reserve.key(),
+        owner: obligation.owner,
+        amount,
+        borrowed,
+        receipts,
+    });
+    Ok(receipts)
+}
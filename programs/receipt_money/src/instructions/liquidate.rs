@@ -0,0 +1,74 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::TokenAccount;
+use crate::{
+    errors::ReceiptErrorCode,
+    events::ObligationLiquidated,
+    state::{liquidation_seize_value, LendingReserve, Obligation, ObligationHealth},
+    utils::{mul_div_floor, BASIS_POINTS_DIVISOR},
+};
+use super::{lending_market::*, repay::reduce_debt};
+
+#[derive(Accounts)]
+pub struct Liquidate<'info> {
+    pub market: LendingMarket<'info>,
+
+    pub liquidator: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = obligation.reserve == market.reserve.key() @ ReceiptErrorCode::InvalidInput,
+    )]
+    pub obligation: Box<Account<'info, Obligation>>,
+
+    #[account(mut, token::mint = market.borrow_mint, token::authority = liquidator)]
+    pub liquidator_borrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = market.crypto_receipt_mint)]
+    pub liquidator_crypto_receipt_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Repay up to the close factor of an unhealthy obligation's debt and seize
+/// its collateral worth the repayment plus the liquidation bonus.
+/// Returns the receipts seized. The remaining accounts carry the receipt
+/// mint's transfer hook accounts.
+pub fn handle_liquidate<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Liquidate<'info>>,
+    repay_amount: u64,
+) -> Result<u64> {
+    require!(repay_amount > 0, ReceiptErrorCode::InvalidInput);
+    let pricing = ctx.accounts.market.refresh()?;
+    let health = ObligationHealth::new(&ctx.accounts.market.reserve, &ctx.accounts.obligation, &pricing)?;
+    require!(health.is_liquidatable(), ReceiptErrorCode::ObligationHealthy);
+    let max_repay = mul_div_floor(health.debt, LendingReserve::CLOSE_FACTOR_BPS, BASIS_POINTS_DIVISOR)?;
+    require!(repay_amount <= max_repay.max(1), ReceiptErrorCode::LiquidationTooLarge);
+
+    let market = &mut ctx.accounts.market;
+    let repaid = market.receive_liquidity(
+        ctx.accounts.liquidator.to_account_info(),
+        ctx.accounts.liquidator_borrow_token_account.to_account_info(),
+        repay_amount,
+    )?;
+    let obligation = &mut ctx.accounts.obligation;
+    reduce_debt(market, obligation, repaid)?;
+    let seized = pricing
+        .receipts_for(liquidation_seize_value(repaid, market.reserve.liquidation_bonus_bps)?)?
+        .min(obligation.collateral);
+    obligation.collateral -= seized;
+    market.release_collateral(
+        ctx.accounts.liquidator_crypto_receipt_token_account.to_account_info(),
+        seized,
+        ctx.remaining_accounts,
+    )?;
+
+    emit!(ObligationLiquidated {
+        reserve: market.reserve.key(),
+        obligation: obligation.key(),
+        liquidator: ctx.accounts.liquidator.key(),
+        repaid,
+        seized,
+    });
+    Ok(seized)
+}
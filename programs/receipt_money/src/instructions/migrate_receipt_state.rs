@@ -0,0 +1,78 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+    Discriminator,
+};
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{ReceiptState, ReceiptStateV0},
+};
+
+#[derive(Accounts)]
+pub struct MigrateReceiptState<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Deserialized and validated by hand because the old layout does
+    /// not fit `Account<ReceiptState>`
+    #[account(mut, owner = crate::ID)]
+    pub receipt_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_migrate_receipt_state(ctx: Context<MigrateReceiptState>) -> Result<()> {
+    let receipt_state_info = ctx.accounts.receipt_state.to_account_info();
+
+    let v0 = {
+        let data = receipt_state_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == ReceiptState::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        require!(
+            data.len() == ReceiptStateV0::LEN,
+            ReceiptErrorCode::AlreadyMigrated
+        );
+        ReceiptStateV0::deserialize(&mut &data[8..])?
+    };
+    require_keys_eq!(
+        v0.authority,
+        ctx.accounts.authority.key(),
+        ErrorCode::ConstraintHasOne
+    );
+    let expected_key = Pubkey::create_program_address(
+        &[
+            ReceiptState::STATE_SEED.as_bytes(),
+            v0.token_mint.as_ref(),
+            &[v0.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| error!(ErrorCode::ConstraintSeeds))?;
+    require_keys_eq!(expected_key, receipt_state_info.key(), ErrorCode::ConstraintSeeds);
+
+    // Top up rent for the larger account before growing it
+    let required_lamports = Rent::get()?
+        .minimum_balance(ReceiptState::LEN)
+        .saturating_sub(receipt_state_info.lamports());
+    if required_lamports > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: receipt_state_info.clone(),
+                },
+            ),
+            required_lamports,
+        )?;
+    }
+    receipt_state_info.realloc(ReceiptState::LEN, true)?;
+
+    let receipt_state = ReceiptState::from_v0(v0);
+    let mut data = receipt_state_info.try_borrow_mut_data()?;
+    receipt_state.try_serialize(&mut &mut data[..])?;
+    msg!("Migrated receipt state to version {}", ReceiptState::CURRENT_VERSION);
+    Ok(())
+}
@@ -1,5 +1,141 @@
 pub mod initialize;
 pub mod deposit;
+pub mod set_oracle_config;
+pub mod get_receipt_price;
+pub mod set_flash_loan_config;
+pub mod flash_borrow;
+pub mod flash_repay;
+pub mod set_swap_program;
+pub mod swap_and_deposit;
+pub mod verify_backing;
+pub mod redeem;
+pub mod preview;
+pub mod migrate_receipt_state;
+pub mod close_market;
+pub mod set_fee_config;
+pub mod register_referrer;
+pub mod open_user_position;
+pub mod transfer_position;
+pub mod update_interest_rate;
+pub mod set_confidential_transfer_auditor;
+pub mod set_authority;
+pub mod set_timelock;
+pub mod propose_param_change;
+pub mod cancel_proposal;
+pub mod execute_proposal;
+pub mod create_multisig;
+pub mod create_multisig_transaction;
+pub mod approve_multisig_transaction;
+pub mod execute_multisig_transaction;
+pub mod set_multisig_config;
+pub mod set_flow_limits;
+pub mod reset_flow_limits;
+pub mod add_reward_pool;
+pub mod fund_rewards;
+pub mod stake;
+pub mod unstake;
+pub mod claim_rewards;
+pub mod create_distribution;
+pub mod claim;
+pub mod clawback;
+pub mod set_bridge_config;
+pub mod register_foreign_emitter;
+pub mod bridge_out;
+pub mod bridge_in;
+pub mod lending_market;
+pub mod init_lending_reserve;
+pub mod supply_reserve_liquidity;
+pub mod withdraw_reserve_liquidity;
+pub mod deposit_collateral;
+pub mod withdraw_collateral;
+pub mod borrow;
+pub mod repay;
+pub mod liquidate;
+pub mod get_obligation_health;
+pub mod leverage_deposit;
+pub mod deleverage;
+pub mod set_stake_pool;
+pub mod deposit_stake_pool_tokens;
+pub mod refresh_stake_pool_value;
+pub mod init_insurance_vault;
+pub mod set_insurance_fee_share;
+pub mod fund_insurance;
+pub mod cover_loss;
+pub mod emergency_shutdown;
+pub mod emergency_redeem;
+pub mod apply_confidential_transfer_auditor;
+pub mod sweep_unallocated_rewards;
+pub mod close_lending_reserve;
+pub mod close_foreign_emitter;
+pub mod redeem_stake_pool_tokens;
 
 pub use initialize::*;
-pub use deposit::*; 
\ No newline at end of file
+pub use deposit::*;
+pub use set_oracle_config::*;
+pub use get_receipt_price::*;
+pub use set_flash_loan_config::*;
+pub use flash_borrow::*;
+pub use flash_repay::*;
+pub use set_swap_program::*;
+pub use swap_and_deposit::*;
+pub use verify_backing::*;
+pub use redeem::*;
+pub use preview::*;
+pub use migrate_receipt_state::*;
+pub use close_market::*;
+pub use set_fee_config::*;
+pub use register_referrer::*;
+pub use open_user_position::*;
+pub use transfer_position::*;
+pub use update_interest_rate::*;
+pub use set_confidential_transfer_auditor::*;
+pub use set_authority::*;
+pub use set_timelock::*;
+pub use propose_param_change::*;
+pub use cancel_proposal::*;
+pub use execute_proposal::*;
+pub use create_multisig::*;
+pub use create_multisig_transaction::*;
+pub use approve_multisig_transaction::*;
+pub use execute_multisig_transaction::*;
+pub use set_multisig_config::*;
+pub use set_flow_limits::*;
+pub use reset_flow_limits::*;
+pub use add_reward_pool::*;
+pub use fund_rewards::*;
+pub use stake::*;
+pub use unstake::*;
+pub use claim_rewards::*;
+pub use create_distribution::*;
+pub use claim::*;
+pub use clawback::*;
+pub use set_bridge_config::*;
+pub use register_foreign_emitter::*;
+pub use bridge_out::*;
+pub use bridge_in::*;
+pub use lending_market::*;
+pub use init_lending_reserve::*;
+pub use supply_reserve_liquidity::*;
+pub use withdraw_reserve_liquidity::*;
+pub use deposit_collateral::*;
+pub use withdraw_collateral::*;
+pub use borrow::*;
+pub use repay::*;
+pub use liquidate::*;
+pub use get_obligation_health::*;
+pub use leverage_deposit::*;
+pub use deleverage::*;
+pub use set_stake_pool::*;
+pub use deposit_stake_pool_tokens::*;
+pub use refresh_stake_pool_value::*;
+pub use init_insurance_vault::*;
+pub use set_insurance_fee_share::*;
+pub use fund_insurance::*;
+pub use cover_loss::*;
+pub use emergency_shutdown::*;
+pub use emergency_redeem::*;
+pub use apply_confidential_transfer_auditor::*;
+pub use sweep_unallocated_rewards::*;
+pub use close_lending_reserve::*;
+pub use close_foreign_emitter::*;
+pub use redeem_stake_pool_tokens::*;
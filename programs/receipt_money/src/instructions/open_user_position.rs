@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::state::{ReceiptState, UserPosition};
+
+#[derive(Accounts)]
+pub struct OpenUserPosition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Wallet the position tracks, it does not need to sign
+    pub owner: UncheckedAccount<'info>,
+
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        init,
+        seeds = [
+            UserPosition::USER_POSITION_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = UserPosition::LEN,
+    )]
+    pub user_position: Box<Account<'info, UserPosition>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_open_user_position(ctx: Context<OpenUserPosition>) -> Result<()> {
+    let user_position = &mut ctx.accounts.user_position;
+    user_position.receipt_state = ctx.accounts.receipt_state.key();
+    user_position.owner = ctx.accounts.owner.key();
+    user_position.bump = ctx.bumps.user_position;
+    Ok(())
+}
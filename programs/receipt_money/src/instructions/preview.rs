@@ -0,0 +1,98 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::{
+    state::ReceiptState,
+    utils::{quote_deposit, quote_redeem, underlying_to_receipts},
+};
+
+/// Read-only accounts needed to quote deposits and redemptions.
+#[derive(Accounts)]
+pub struct Preview<'info> {
+    #[account(
+        has_one = token_mint,
+        has_one = token_mint_vault,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+#[derive(Accounts)]
+pub struct MaxRedeem<'info> {
+    pub preview: Preview<'info>,
+
+    #[account(token::mint = preview.crypto_receipt_mint)]
+    pub owner_crypto_receipt_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Receipts `deposit(amount)` would mint.
+pub fn handle_preview_deposit(ctx: Context<Preview>, amount: u64) -> Result<u64> {
+    let accounts = &ctx.accounts;
+    let quote = quote_deposit(
+        &accounts.receipt_state,
+        &accounts.token_mint.to_account_info(),
+        accounts.token_mint_vault.amount,
+        accounts.receipt_state.receipt_supply(accounts.crypto_receipt_mint.supply)?,
+        amount,
+    )?;
+    Ok(quote.receipts)
+}
+
+/// Underlying the user would receive from `redeem(receipt_amount)`, after transfer fees.
+pub fn handle_preview_redeem(ctx: Context<Preview>, receipt_amount: u64) -> Result<u64> {
+    let accounts = &ctx.accounts;
+    let quote = quote_redeem(
+        &accounts.receipt_state,
+        &accounts.token_mint.to_account_info(),
+        accounts.token_mint_vault.amount,
+        accounts.receipt_state.receipt_supply(accounts.crypto_receipt_mint.supply)?,
+        receipt_amount,
+    )?;
+    Ok(quote.received)
+}
+
+/// Largest underlying amount `deposit` currently accepts: zero while the
+/// market is shut down or paused, and no more than the inflow limit has left.
+pub fn handle_max_deposit(ctx: Context<Preview>) -> Result<u64> {
+    let accounts = &ctx.accounts;
+    let receipt_state = &accounts.receipt_state;
+    let (inflow_headroom, _) = receipt_state.flow_headroom(accounts.token_mint_vault.amount)?;
+    let total_underlying = receipt_state.total_underlying(accounts.token_mint_vault.amount)?;
+    Ok((u64::MAX - total_underlying).min(inflow_headroom))
+}
+
+/// Largest receipt amount the owner can redeem right now, limited by their
+/// balance, by the underlying sitting in the vault and by the outflow limit.
+/// Zero while the market is shut down or paused.
+pub fn handle_max_redeem(ctx: Context<MaxRedeem>) -> Result<u64> {
+    let accounts = &ctx.accounts.preview;
+    let (_, outflow_headroom) = accounts
+        .receipt_state
+        .flow_headroom(accounts.token_mint_vault.amount)?;
+    let total_underlying = accounts
+        .receipt_state
+        .total_underlying(accounts.token_mint_vault.amount)?;
+    let liquid_receipts = underlying_to_receipts(
+        accounts.token_mint_vault.amount.min(outflow_headroom),
+        total_underlying,
+        accounts.receipt_state.receipt_supply(accounts.crypto_receipt_mint.supply)?,
+    )?;
+    Ok(ctx
+        .accounts
+        .owner_crypto_receipt_token_account
+        .amount
+        .min(liquid_receipts))
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::{
+    errors::ReceiptErrorCode,
+    events::ProposalQueued,
+    state::{MarketParamChange, Proposal, ReceiptState},
+};
+
+#[derive(Accounts)]
+pub struct ProposeParamChange<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        init,
+        seeds = [
+            Proposal::PROPOSAL_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            &receipt_state.proposal_count.to_le_bytes(),
+        ],
+        bump,
+        payer = authority,
+        space = Proposal::LEN,
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_propose_param_change(
+    ctx: Context<ProposeParamChange>,
+    change: MarketParamChange,
+) -> Result<()> {
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    require!(receipt_state.is_timelocked(), ReceiptErrorCode::TimelockNotEnabled);
+    change.validate()?;
+
+    let eta = Clock::get()?
+        .unix_timestamp
+        .checked_add(receipt_state.timelock_delay)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.receipt_state = receipt_state.key();
+    proposal.proposer = ctx.accounts.authority.key();
+    proposal.id = receipt_state.proposal_count;
+    proposal.change = change.clone();
+    proposal.eta = eta;
+    proposal.bump = ctx.bumps.proposal;
+    receipt_state.proposal_count += 1;
+
+    emit!(ProposalQueued {
+        receipt_state: proposal.receipt_state,
+        proposal: proposal.key(),
+        id: proposal.id,
+        change,
+        eta,
+    });
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{ReceiptState, UserPosition},
+    utils::{quote_redeem, token_burn, transfer_from_pool_vault_to_user},
+};
+use super::deposit::revalue_stake_pool_tokens;
+
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_mint_program,
+    )]
+    pub user_mint_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = crypto_receipt_mint,
+        associated_token::authority = user,
+        associated_token::token_program = crypto_receipt_mint_program,
+    )]
+    pub user_crypto_receipt_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = token_mint,
+        has_one = token_mint_vault,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: This is both vault authority and mint authority of crToken
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Spl token program or token program 2022
+    pub token_mint_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub crypto_receipt_mint_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: User's position, may not exist. Updated with the realized
+    /// yield of this redemption when it does
+    #[account(
+        mut,
+        seeds = [
+            UserPosition::USER_POSITION_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            user.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_position: UncheckedAccount<'info>,
+
+    /// CHECK: Parsed by `StakePoolState::load`. With `stake_pool_vault`,
+    /// revalues the market's pool tokens before pricing the redemption
+    #[account(address = receipt_state.stake_pool @ ReceiptErrorCode::InvalidStakePool)]
+    pub stake_pool: Option<UncheckedAccount<'info>>,
+
+    #[account(address = receipt_state.stake_pool_vault @ ReceiptErrorCode::InvalidStakePool)]
+    pub stake_pool_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+}
+
+impl<'info> Redeem<'info> {
+    /// Burn `receipt_amount` of the user's receipts and pay out the underlying they are worth.
+    /// Returns zero, moving nothing, when the redemption trips the outflow limit.
+    pub fn redeem_receipts(&mut self, receipt_amount: u64) -> Result<u64> {
+        require!(receipt_amount > 0, ReceiptErrorCode::InvalidInput);
+        require!(
+            receipt_amount <= self.user_crypto_receipt_token_account.amount,
+            ReceiptErrorCode::InsufficientPublicBalance
+        );
+        revalue_stake_pool_tokens(
+            &mut self.receipt_state,
+            self.stake_pool.as_ref(),
+            self.stake_pool_vault.as_deref(),
+        )?;
+        let receipt_state = &self.receipt_state;
+        let quote = quote_redeem(
+            receipt_state,
+            &self.token_mint.to_account_info(),
+            self.token_mint_vault.amount,
+            receipt_state.receipt_supply(self.crypto_receipt_mint.supply)?,
+            receipt_amount,
+        )?;
+        require!(quote.underlying > 0, ReceiptErrorCode::InvalidInput);
+        require!(
+            quote.underlying <= self.token_mint_vault.amount,
+            ReceiptErrorCode::InsufficientLiquidity
+        );
+        let receipt_state_pubkey = receipt_state.key();
+        if !self.receipt_state.record_flow(
+            receipt_state_pubkey,
+            self.token_mint_vault.amount,
+            0,
+            quote.underlying,
+        )? {
+            return Ok(0);
+        }
+        let receipt_state = &self.receipt_state;
+
+        token_burn(
+            self.user.to_account_info(),
+            self.crypto_receipt_mint_program.to_account_info(),
+            self.crypto_receipt_mint.to_account_info(),
+            self.user_crypto_receipt_token_account.to_account_info(),
+            receipt_amount,
+            &[],
+        )?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state_pubkey.as_ref(),
+            &[receipt_state.vault_authority_bump],
+        ]];
+        transfer_from_pool_vault_to_user(
+            self.vault_authority.to_account_info(),
+            self.token_mint_vault.to_account_info(),
+            self.user_mint_token_account.to_account_info(),
+            self.token_mint.to_account_info(),
+            self.token_mint_program.to_account_info(),
+            quote.underlying,
+            self.token_mint.decimals,
+            signer_seeds,
+        )?;
+        UserPosition::update_if_open(&self.user_position, |position| {
+            position.record_redeem(receipt_amount, quote.received)
+        })?;
+        Ok(quote.underlying)
+    }
+}
+
+pub fn handle_redeem(ctx: Context<Redeem>, receipt_amount: u64) -> Result<()> {
+    ctx.accounts.redeem_receipts(receipt_amount)?;
+    Ok(())
+}
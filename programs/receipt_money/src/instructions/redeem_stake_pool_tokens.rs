@@ -0,0 +1,135 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    events::StakePoolValueRefreshed,
+    state::{ReceiptState, StakePoolState},
+    utils::{receipts_to_underlying, token_burn, transfer_from_pool_vault_to_user},
+};
+
+#[derive(Accounts)]
+pub struct RedeemStakePoolTokens<'info> {
+    pub user: Signer<'info>,
+
+    #[account(mut, token::mint = crypto_receipt_mint, token::authority = user)]
+    pub user_crypto_receipt_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = pool_mint)]
+    pub user_pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = token_mint_vault,
+        has_one = crypto_receipt_mint,
+        has_one = stake_pool,
+        has_one = stake_pool_vault,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    /// CHECK: Parsed by `StakePoolState::load`
+    pub stake_pool: UncheckedAccount<'info>,
+
+    pub pool_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub stake_pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: This is both vault authority and mint authority of crToken
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Spl token program or token program 2022
+    pub pool_mint_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub crypto_receipt_mint_program: Interface<'info, TokenInterface>,
+}
+
+/// Burn `receipt_amount` of the user's receipts for the stake pool tokens
+/// they are worth at the pool's current rate, the way out for pool tokens
+/// `deposit_stake_pool_tokens` took in. Returns the pool tokens paid out, or
+/// zero, moving nothing, when the redemption trips the outflow limit.
+///
+/// Emptying the stake pool vault zeroes `stake_pool_value`, which
+/// `close_market` requires.
+pub fn handle_redeem_stake_pool_tokens(ctx: Context<RedeemStakePoolTokens>, receipt_amount: u64) -> Result<u64> {
+    require!(receipt_amount > 0, ReceiptErrorCode::InvalidInput);
+    require!(
+        receipt_amount <= ctx.accounts.user_crypto_receipt_token_account.amount,
+        ReceiptErrorCode::InsufficientBalance
+    );
+    let pool = StakePoolState::load_current(&ctx.accounts.stake_pool.to_account_info())?;
+    require_keys_eq!(pool.pool_mint, ctx.accounts.pool_mint.key(), ReceiptErrorCode::InvalidStakePool);
+
+    // Revalue the pool tokens held so the redemption is priced at the current rate
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    receipt_state.set_stake_pool_value(&pool, ctx.accounts.stake_pool_vault.amount)?;
+    let value = receipts_to_underlying(
+        receipt_amount,
+        receipt_state.total_underlying(ctx.accounts.token_mint_vault.amount)?,
+        receipt_state.receipt_supply(ctx.accounts.crypto_receipt_mint.supply)?,
+    )?;
+    let pool_tokens = pool.pool_tokens_for(value)?;
+    require!(pool_tokens > 0, ReceiptErrorCode::InvalidInput);
+    require!(
+        pool_tokens <= ctx.accounts.stake_pool_vault.amount,
+        ReceiptErrorCode::InsufficientLiquidity
+    );
+    let receipt_state_key = receipt_state.key();
+    if !receipt_state.record_flow(receipt_state_key, ctx.accounts.token_mint_vault.amount, 0, value)? {
+        return Ok(0);
+    }
+
+    token_burn(
+        ctx.accounts.user.to_account_info(),
+        ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+        ctx.accounts.crypto_receipt_mint.to_account_info(),
+        ctx.accounts.user_crypto_receipt_token_account.to_account_info(),
+        receipt_amount,
+        &[],
+    )?;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+        receipt_state_key.as_ref(),
+        &[ctx.accounts.receipt_state.vault_authority_bump],
+    ]];
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.vault_authority.to_account_info(),
+        ctx.accounts.stake_pool_vault.to_account_info(),
+        ctx.accounts.user_pool_token_account.to_account_info(),
+        ctx.accounts.pool_mint.to_account_info(),
+        ctx.accounts.pool_mint_program.to_account_info(),
+        pool_tokens,
+        ctx.accounts.pool_mint.decimals,
+        signer_seeds,
+    )?;
+
+    ctx.accounts.stake_pool_vault.reload()?;
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    receipt_state.set_stake_pool_value(&pool, ctx.accounts.stake_pool_vault.amount)?;
+    emit!(StakePoolValueRefreshed {
+        receipt_state: receipt_state_key,
+        pool_tokens: ctx.accounts.stake_pool_vault.amount,
+        value: receipt_state.stake_pool_value,
+        epoch: pool.last_update_epoch,
+    });
+    Ok(pool_tokens)
+}
@@ -0,0 +1,46 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::TokenAccount;
+use crate::{
+    events::StakePoolValueRefreshed,
+    state::{ReceiptState, StakePoolState},
+};
+
+#[derive(Accounts)]
+pub struct RefreshStakePoolValue<'info> {
+    #[account(
+        mut,
+        has_one = stake_pool,
+        has_one = stake_pool_vault,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    /// CHECK: Parsed by `StakePoolState::load`
+    pub stake_pool: UncheckedAccount<'info>,
+
+    pub stake_pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Revalue the market's stake pool tokens at the pool's current rate. Anyone
+/// may call this once the pool is updated for the epoch, keeping the receipt
+/// price views current. `deposit` and `redeem` fail on a value from an
+/// earlier epoch unless they are passed the pool to revalue it themselves.
+pub fn handle_refresh_stake_pool_value(ctx: Context<RefreshStakePoolValue>) -> Result<()> {
+    let pool = StakePoolState::load_current(&ctx.accounts.stake_pool.to_account_info())?;
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    receipt_state.set_stake_pool_value(&pool, ctx.accounts.stake_pool_vault.amount)?;
+    emit!(StakePoolValueRefreshed {
+        receipt_state: receipt_state.key(),
+        pool_tokens: ctx.accounts.stake_pool_vault.amount,
+        value: receipt_state.stake_pool_value,
+        epoch: pool.last_update_epoch,
+    });
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{ForeignEmitter, MarketParamChange, ReceiptState},
+};
+
+#[derive(Accounts)]
+#[instruction(chain: u16)]
+pub struct RegisterForeignEmitter<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            ForeignEmitter::FOREIGN_EMITTER_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            &chain.to_le_bytes(),
+        ],
+        bump,
+        payer = authority,
+        space = ForeignEmitter::LEN,
+    )]
+    pub foreign_emitter: Box<Account<'info, ForeignEmitter>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Register the market's emitter on `chain`. A registered emitter can only
+/// be replaced by a `ForeignEmitter` proposal, so it never changes under
+/// the feet of receipts already bridged out.
+pub fn handle_register_foreign_emitter(
+    ctx: Context<RegisterForeignEmitter>,
+    chain: u16,
+    address: [u8; 32],
+) -> Result<()> {
+    ctx.accounts
+        .receipt_state
+        .apply_direct_change(MarketParamChange::ForeignEmitter { chain, address })?;
+    let foreign_emitter = &mut ctx.accounts.foreign_emitter;
+    require!(
+        foreign_emitter.address == [0; 32],
+        ReceiptErrorCode::ForeignEmitterRegistered
+    );
+    foreign_emitter.set(
+        ctx.accounts.receipt_state.key(),
+        chain,
+        address,
+        ctx.bumps.foreign_emitter,
+    );
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    receipt_state.foreign_emitter_count = receipt_state
+        .foreign_emitter_count
+        .checked_add(1)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    Ok(())
+}
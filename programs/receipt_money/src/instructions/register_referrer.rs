@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::state::{ReceiptState, ReferralStats};
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        init,
+        seeds = [
+            ReferralStats::REFERRAL_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            referrer.key().as_ref(),
+        ],
+        bump,
+        payer = referrer,
+        space = ReferralStats::LEN,
+    )]
+    pub referral_stats: Box<Account<'info, ReferralStats>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+    let referral_stats = &mut ctx.accounts.referral_stats;
+    referral_stats.receipt_state = ctx.accounts.receipt_state.key();
+    referral_stats.referrer = ctx.accounts.referrer.key();
+    referral_stats.bump = ctx.bumps.referral_stats;
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::TokenAccount;
+use crate::{errors::ReceiptErrorCode, state::Obligation};
+use super::lending_market::*;
+
+#[derive(Accounts)]
+pub struct Repay<'info> {
+    pub market: LendingMarket<'info>,
+
+    /// Anyone may repay an obligation's debt
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = obligation.reserve == market.reserve.key() @ ReceiptErrorCode::InvalidInput,
+    )]
+    pub obligation: Box<Account<'info, Obligation>>,
+
+    #[account(mut, token::mint = market.borrow_mint, token::authority = payer)]
+    pub payer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Burn the debt shares `repaid` borrow mint units cover. Repaying the whole
+/// debt clears every share.
+pub fn reduce_debt(market: &mut LendingMarket, obligation: &mut Obligation, repaid: u64) -> Result<()> {
+    let debt = market.reserve.debt_of(obligation.borrow_shares)?;
+    let shares = if repaid >= debt {
+        obligation.borrow_shares
+    } else {
+        market.reserve.shares_for(repaid, false)?
+    };
+    obligation.borrow_shares -= shares;
+    market.reserve.total_borrow_shares -= shares;
+    Ok(())
+}
+
+/// Repay up to `amount` of the obligation's debt. Returns the amount repaid.
+pub fn handle_repay(ctx: Context<Repay>, amount: u64) -> Result<u64> {
+    require!(amount > 0, ReceiptErrorCode::InvalidInput);
+    let market = &mut ctx.accounts.market;
+    market.refresh()?;
+    let debt = market.reserve.debt_of(ctx.accounts.obligation.borrow_shares)?;
+    let repaid = market.receive_liquidity(
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.payer_token_account.to_account_info(),
+        amount.min(debt),
+    )?;
+    reduce_debt(market, &mut ctx.accounts.obligation, repaid)?;
+    Ok(repaid)
+}
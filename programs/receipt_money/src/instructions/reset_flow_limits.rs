@@ -0,0 +1,44 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::TokenAccount;
+use crate::{errors::ReceiptErrorCode, events::FlowLimitsReset, state::ReceiptState};
+
+#[derive(Accounts)]
+pub struct ResetFlowLimits<'info> {
+    #[account(
+        constraint = guardian.key() == receipt_state.guardian
+            || guardian.key() == receipt_state.authority
+            @ ReceiptErrorCode::Unauthorized,
+    )]
+    pub guardian: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = token_mint_vault,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Unpause the market and clear the recorded flow, starting a new window.
+pub fn handle_reset_flow_limits(ctx: Context<ResetFlowLimits>) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    let tvl = receipt_state.total_underlying(ctx.accounts.token_mint_vault.amount)?;
+    receipt_state.paused = false;
+    receipt_state.flow_limits.reset(slot, tvl);
+    emit!(FlowLimitsReset {
+        receipt_state: receipt_state.key(),
+        guardian: ctx.accounts.guardian.key(),
+        slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use crate::state::{MarketParamChange, ReceiptState};
+
+#[derive(Accounts)]
+pub struct SetAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+}
+
+pub fn handle_set_authority(ctx: Context<SetAuthority>, new_authority: Pubkey) -> Result<()> {
+    ctx.accounts
+        .receipt_state
+        .apply_direct_change(MarketParamChange::Authority(new_authority))
+}
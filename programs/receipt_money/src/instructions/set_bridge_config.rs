@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::state::{MarketParamChange, ReceiptState};
+
+#[derive(Accounts)]
+pub struct SetBridgeConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+}
+
+pub fn handle_set_bridge_config(
+    ctx: Context<SetBridgeConfig>,
+    core_bridge_program: Pubkey,
+    escrow: bool,
+) -> Result<()> {
+    ctx.accounts
+        .receipt_state
+        .apply_direct_change(MarketParamChange::BridgeConfig { core_bridge_program, escrow })
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::Token2022;
+use crate::{
+    instructions::update_mint_auditor,
+    state::{MarketParamChange, ReceiptState},
+};
+
+#[derive(Accounts)]
+pub struct SetConfidentialTransferAuditor<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    /// CHECK: Confidential transfer authority of the receipt mint
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Receipt mint, validated by `receipt_state` and the token program
+    #[account(mut)]
+    pub crypto_receipt_mint: UncheckedAccount<'info>,
+
+    pub crypto_receipt_mint_program: Program<'info, Token2022>,
+}
+
+/// Rotate or remove the auditor ElGamal key of a confidential receipt mint.
+/// Timelocked markets queue a `ConfidentialTransferAuditor` proposal instead.
+pub fn handle_set_confidential_transfer_auditor(
+    ctx: Context<SetConfidentialTransferAuditor>,
+    auditor_elgamal_pubkey: Option<[u8; 32]>,
+) -> Result<()> {
+    ctx.accounts
+        .receipt_state
+        .apply_direct_change(MarketParamChange::ConfidentialTransferAuditor(auditor_elgamal_pubkey))?;
+    update_mint_auditor(
+        &mut ctx.accounts.receipt_state,
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.crypto_receipt_mint,
+        &ctx.accounts.crypto_receipt_mint_program,
+    )
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::state::{MarketParamChange, ReceiptState};
+
+#[derive(Accounts)]
+pub struct SetFeeConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+}
+
+pub fn handle_set_fee_config(
+    ctx: Context<SetFeeConfig>,
+    deposit_fee_bps: u16,
+    referral_fee_share_bps: u16,
+    fee_receiver: Pubkey,
+) -> Result<()> {
+    ctx.accounts.receipt_state.apply_direct_change(MarketParamChange::FeeConfig {
+        deposit_fee_bps,
+        referral_fee_share_bps,
+        fee_receiver,
+    })
+}
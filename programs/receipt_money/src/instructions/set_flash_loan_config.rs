@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::state::{MarketParamChange, ReceiptState};
+
+#[derive(Accounts)]
+pub struct SetFlashLoanConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+}
+
+pub fn handle_set_flash_loan_config(
+    ctx: Context<SetFlashLoanConfig>,
+    enabled: bool,
+    fee_bps: u16,
+) -> Result<()> {
+    ctx.accounts
+        .receipt_state
+        .apply_direct_change(MarketParamChange::FlashLoanConfig { enabled, fee_bps })
+}
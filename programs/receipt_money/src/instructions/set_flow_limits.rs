@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::state::{MarketParamChange, ReceiptState};
+
+#[derive(Accounts)]
+pub struct SetFlowLimits<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+}
+
+pub fn handle_set_flow_limits(
+    ctx: Context<SetFlowLimits>,
+    window_slots: u64,
+    max_inflow_bps: u16,
+    max_outflow_bps: u16,
+) -> Result<()> {
+    ctx.accounts.receipt_state.apply_direct_change(MarketParamChange::FlowLimits {
+        window_slots,
+        max_inflow_bps,
+        max_outflow_bps,
+    })
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::state::Multisig;
+
+#[derive(Accounts)]
+pub struct SetMultisigConfig<'info> {
+    #[account(mut)]
+    pub multisig: Box<Account<'info, Multisig>>,
+
+    /// Only reachable through an executed multisig transaction
+    #[account(
+        seeds = [Multisig::MULTISIG_SIGNER_SEED.as_bytes(), multisig.key().as_ref()],
+        bump = multisig.signer_bump,
+    )]
+    pub multisig_signer: Signer<'info>,
+}
+
+pub fn handle_set_multisig_config(
+    ctx: Context<SetMultisigConfig>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    Multisig::validate_signers(&signers, threshold)?;
+    let multisig = &mut ctx.accounts.multisig;
+    multisig.signers = signers;
+    multisig.threshold = threshold;
+    multisig.owner_set_seqno = multisig.owner_set_seqno.wrapping_add(1);
+    Ok(())
+}
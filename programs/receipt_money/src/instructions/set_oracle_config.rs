@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use crate::state::{MarketParamChange, OracleConfig, ReceiptState};
+
+#[derive(Accounts)]
+pub struct SetOracleConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+}
+
+pub fn handle_set_oracle_config(ctx: Context<SetOracleConfig>, config: OracleConfig) -> Result<()> {
+    ctx.accounts
+        .receipt_state
+        .apply_direct_change(MarketParamChange::OracleConfig(config))
+}
@@ -0,0 +1,99 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::{
+    token::spl_token::native_mint,
+    token_interface::{Mint, TokenInterface},
+};
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{MarketParamChange, ReceiptState, StakePoolState},
+    utils::{create_token_account, is_supported_mint},
+};
+
+#[derive(Accounts)]
+pub struct SetStakePool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+        // Stake pools are valued in lamports
+        constraint = receipt_state.token_mint == native_mint::ID @ ReceiptErrorCode::InvalidStakePool,
+        constraint = receipt_state.stake_pool_vault == Pubkey::default() @ ReceiptErrorCode::InvalidStakePool,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    /// CHECK: Parsed by `StakePoolState::load`
+    pub stake_pool: UncheckedAccount<'info>,
+
+    pub pool_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Created below, holds the deposited pool tokens
+    #[account(
+        mut,
+        seeds = [
+            StakePoolState::STAKE_POOL_VAULT_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            pool_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub stake_pool_vault: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: Owns the stake pool vault
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Spl token program or token program 2022
+    pub pool_mint_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accept the pool token of an SPL stake pool as an alternate deposit asset.
+/// A market takes a single stake pool, set once. A timelocked market only
+/// opens the vault of the pool an executed `StakePool` proposal approved.
+pub fn handle_set_stake_pool(ctx: Context<SetStakePool>) -> Result<()> {
+    let stake_pool_key = ctx.accounts.stake_pool.key();
+    if ctx.accounts.receipt_state.stake_pool != stake_pool_key {
+        ctx.accounts
+            .receipt_state
+            .apply_direct_change(MarketParamChange::StakePool(stake_pool_key))?;
+    }
+    let pool = StakePoolState::load(&ctx.accounts.stake_pool.to_account_info())?;
+    require_keys_eq!(pool.pool_mint, ctx.accounts.pool_mint.key(), ReceiptErrorCode::InvalidStakePool);
+    require!(is_supported_mint(&ctx.accounts.pool_mint)?, ReceiptErrorCode::InvalidInput);
+
+    let receipt_state_key = ctx.accounts.receipt_state.key();
+    let pool_mint_key = ctx.accounts.pool_mint.key();
+    create_token_account(
+        &ctx.accounts.vault_authority.to_account_info(),
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.stake_pool_vault.to_account_info(),
+        &ctx.accounts.pool_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.pool_mint_program.to_account_info(),
+        &[
+            StakePoolState::STAKE_POOL_VAULT_SEED.as_bytes(),
+            receipt_state_key.as_ref(),
+            pool_mint_key.as_ref(),
+            &[ctx.bumps.stake_pool_vault][..],
+        ][..],
+    )?;
+
+    ctx.accounts.receipt_state.stake_pool_vault = ctx.accounts.stake_pool_vault.key();
+    Ok(())
+}
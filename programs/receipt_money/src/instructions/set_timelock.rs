@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+use crate::state::{MarketParamChange, ReceiptState};
+
+#[derive(Accounts)]
+pub struct SetTimelock<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+}
+
+/// Turn on the timelock. Afterwards every admin change, including this one,
+/// has to be queued with `propose_param_change`.
+pub fn handle_set_timelock(ctx: Context<SetTimelock>, guardian: Pubkey, delay: i64) -> Result<()> {
+    ctx.accounts
+        .receipt_state
+        .apply_direct_change(MarketParamChange::Timelock { guardian, delay })
+}
@@ -0,0 +1,152 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{ReceiptState, RewardPool, StakePosition},
+    utils::transfer_checked_with_hook,
+};
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            StakePosition::STAKE_POSITION_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = StakePosition::LEN,
+    )]
+    pub stake_position: Box<Account<'info, StakePosition>>,
+
+    #[account(mut, token::mint = crypto_receipt_mint, token::authority = owner)]
+    pub owner_crypto_receipt_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RewardPool::STAKE_VAULT_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Spl token program or token program 2022
+    pub crypto_receipt_mint_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Split the remaining accounts into the market's reward pools, writable and in
+/// index order, and the transfer hook accounts of the receipt mint after them.
+pub fn split_remaining_accounts<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    receipt_state: &ReceiptState,
+) -> Result<(&'info [AccountInfo<'info>], &'info [AccountInfo<'info>])> {
+    let reward_pool_count = receipt_state.reward_pool_count as usize;
+    require!(
+        remaining_accounts.len() >= reward_pool_count,
+        ReceiptErrorCode::InvalidRewardPool
+    );
+    Ok(remaining_accounts.split_at(reward_pool_count))
+}
+
+/// Accrue every reward pool of the market and settle the position against
+/// each before its stake changes.
+pub fn settle_reward_pools<'info>(
+    reward_pool_accounts: &'info [AccountInfo<'info>],
+    receipt_state: &Account<ReceiptState>,
+    stake_position: &mut StakePosition,
+) -> Result<Vec<Account<'info, RewardPool>>> {
+    let now = Clock::get()?.unix_timestamp;
+    reward_pool_accounts
+        .iter()
+        .enumerate()
+        .map(|(index, info)| {
+            let mut reward_pool = Account::<RewardPool>::try_from(info)?;
+            require!(
+                reward_pool.receipt_state == receipt_state.key() && reward_pool.index as usize == index,
+                ReceiptErrorCode::InvalidRewardPool
+            );
+            reward_pool.update(now, receipt_state.total_staked)?;
+            stake_position.settle(&mut reward_pool)?;
+            Ok(reward_pool)
+        })
+        .collect()
+}
+
+/// Reset the position's reward debt after its stake changed and persist the pools.
+pub fn checkpoint_reward_pools(
+    reward_pools: &[Account<RewardPool>],
+    stake_position: &mut StakePosition,
+) -> Result<()> {
+    for reward_pool in reward_pools {
+        stake_position.checkpoint(reward_pool);
+        reward_pool.exit(&crate::ID)?;
+    }
+    Ok(())
+}
+
+pub fn handle_stake<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Stake<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ReceiptErrorCode::InvalidInput);
+    let stake_position = &mut ctx.accounts.stake_position;
+    stake_position.receipt_state = ctx.accounts.receipt_state.key();
+    stake_position.owner = ctx.accounts.owner.key();
+    stake_position.bump = ctx.bumps.stake_position;
+    let (reward_pool_accounts, hook_accounts) =
+        split_remaining_accounts(ctx.remaining_accounts, &ctx.accounts.receipt_state)?;
+    let reward_pools = settle_reward_pools(
+        reward_pool_accounts,
+        &ctx.accounts.receipt_state,
+        stake_position,
+    )?;
+
+    transfer_checked_with_hook(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.owner_crypto_receipt_token_account.to_account_info(),
+        ctx.accounts.stake_vault.to_account_info(),
+        ctx.accounts.crypto_receipt_mint.to_account_info(),
+        ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+        amount,
+        ctx.accounts.crypto_receipt_mint.decimals,
+        hook_accounts,
+        &[],
+    )?;
+
+    let stake_position = &mut ctx.accounts.stake_position;
+    stake_position.staked = stake_position
+        .staked
+        .checked_add(amount)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    checkpoint_reward_pools(&reward_pools, stake_position)?;
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    receipt_state.total_staked = receipt_state
+        .total_staked
+        .checked_add(amount)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::TokenAccount;
+use crate::errors::ReceiptErrorCode;
+use super::lending_market::*;
+
+#[derive(Accounts)]
+pub struct SupplyReserveLiquidity<'info> {
+    pub market: LendingMarket<'info>,
+
+    #[account(address = market.receipt_state.authority @ ReceiptErrorCode::Unauthorized)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, token::mint = market.borrow_mint, token::authority = authority)]
+    pub authority_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Add lendable liquidity to the reserve.
+pub fn handle_supply_reserve_liquidity(ctx: Context<SupplyReserveLiquidity>, amount: u64) -> Result<()> {
+    require!(amount > 0, ReceiptErrorCode::InvalidInput);
+    ctx.accounts.market.refresh()?;
+    ctx.accounts.market.receive_liquidity(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.authority_token_account.to_account_info(),
+        amount,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::TokenAccount;
+use crate::errors::ReceiptErrorCode;
+use super::deposit::*;
+
+#[derive(Accounts)]
+pub struct SwapAndDeposit<'info> {
+    pub deposit: Deposit<'info>,
+
+    #[account(
+        mut,
+        token::authority = deposit.user,
+    )]
+    pub user_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Swap program configured by the market authority
+    #[account(
+        executable,
+        address = deposit.receipt_state.swap_program @ ReceiptErrorCode::InvalidSwapProgram,
+    )]
+    pub swap_program: UncheckedAccount<'info>,
+}
+
+/// Swap `amount_in` of any token into `token_mint` through the configured swap
+/// program, then deposit everything the swap produced.
+///
+/// `swap_data` is the swap program's instruction data and the remaining accounts
+/// are its accounts, in order. The swap must deliver `token_mint` into
+/// `user_mint_token_account`.
+#[cfg(feature = "jupiter-cpi")]
+pub fn handle_swap_and_deposit<'info>(
+    ctx: Context<'_, '_, '_, 'info, SwapAndDeposit<'info>>,
+    amount_in: u64,
+    min_amount_out: u64,
+    swap_data: Vec<u8>,
+) -> Result<()> {
+    use anchor_lang::solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke,
+    };
+
+    require!(amount_in > 0, ReceiptErrorCode::InvalidInput);
+    let input_before = ctx.accounts.user_input_token_account.amount;
+    let output_before = ctx.accounts.deposit.user_mint_token_account.amount;
+
+    let swap_ix = Instruction {
+        program_id: ctx.accounts.swap_program.key(),
+        accounts: ctx
+            .remaining_accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: swap_data,
+    };
+    let mut swap_account_infos = ctx.remaining_accounts.to_vec();
+    swap_account_infos.push(ctx.accounts.swap_program.to_account_info());
+    invoke(&swap_ix, &swap_account_infos)?;
+
+    ctx.accounts.user_input_token_account.reload()?;
+    ctx.accounts.deposit.user_mint_token_account.reload()?;
+    let amount_spent = input_before.saturating_sub(ctx.accounts.user_input_token_account.amount);
+    let amount_out = ctx
+        .accounts
+        .deposit
+        .user_mint_token_account
+        .amount
+        .saturating_sub(output_before);
+    require!(amount_spent <= amount_in, ReceiptErrorCode::SlippageExceeded);
+    require!(amount_out >= min_amount_out, ReceiptErrorCode::SlippageExceeded);
+    msg!("Swapped {} input for {} underlying", amount_spent, amount_out);
+
+    ctx.accounts.deposit.deposit_underlying(amount_out)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "jupiter-cpi"))]
+pub fn handle_swap_and_deposit<'info>(
+    _ctx: Context<'_, '_, '_, 'info, SwapAndDeposit<'info>>,
+    _amount_in: u64,
+    _min_amount_out: u64,
+    _swap_data: Vec<u8>,
+) -> Result<()> {
+    err!(ReceiptErrorCode::SwapNotEnabled)
+}
@@ -0,0 +1,100 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    events::UnallocatedRewardsSwept,
+    state::{ReceiptState, RewardPool},
+    utils::transfer_from_pool_vault_to_user,
+};
+
+#[derive(Accounts)]
+pub struct SweepUnallocatedRewards<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        mut,
+        has_one = receipt_state,
+        has_one = reward_mint,
+        has_one = reward_vault,
+    )]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+
+    #[account(mut)]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, token::mint = reward_mint)]
+    pub authority_reward_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: Owns the reward vault
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Spl token program or token program 2022
+    pub reward_mint_program: Interface<'info, TokenInterface>,
+}
+
+/// Withdraw the rewards owed to nobody. Once emissions ended with nothing
+/// staked, that is everything in the vault but the rewards stakers have yet
+/// to claim, including accrual rounding and tokens sent to the vault directly.
+/// Returns the amount swept.
+pub fn handle_sweep_unallocated_rewards(ctx: Context<SweepUnallocatedRewards>) -> Result<u64> {
+    let now = Clock::get()?.unix_timestamp;
+    let total_staked = ctx.accounts.receipt_state.total_staked;
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    reward_pool.update(now, total_staked)?;
+    let amount = if now >= reward_pool.emission_end_ts && total_staked == 0 {
+        ctx.accounts
+            .reward_vault
+            .amount
+            .checked_sub(reward_pool.unclaimed_rewards)
+            .ok_or(ReceiptErrorCode::MathOverflow)?
+    } else {
+        reward_pool.unallocated_rewards
+    };
+    reward_pool.unallocated_rewards = 0;
+
+    let receipt_state_key = ctx.accounts.receipt_state.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+        receipt_state_key.as_ref(),
+        &[ctx.accounts.receipt_state.vault_authority_bump],
+    ]];
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.vault_authority.to_account_info(),
+        ctx.accounts.reward_vault.to_account_info(),
+        ctx.accounts.authority_reward_token_account.to_account_info(),
+        ctx.accounts.reward_mint.to_account_info(),
+        ctx.accounts.reward_mint_program.to_account_info(),
+        amount,
+        ctx.accounts.reward_mint.decimals,
+        signer_seeds,
+    )?;
+
+    emit!(UnallocatedRewardsSwept {
+        receipt_state: receipt_state_key,
+        reward_pool: ctx.accounts.reward_pool.key(),
+        amount,
+    });
+    Ok(amount)
+}
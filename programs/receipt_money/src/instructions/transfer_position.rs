@@ -0,0 +1,65 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use receipt_hook::HOOK_AUTHORITY_SEED;
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{ReceiptState, UserPosition},
+    utils::receipts_to_underlying,
+};
+
+#[derive(Accounts)]
+pub struct TransferPosition<'info> {
+    /// Only signs from inside a receipt_hook transfer hook
+    #[account(
+        seeds = [HOOK_AUTHORITY_SEED.as_bytes()],
+        bump,
+        seeds::program = receipt_hook::ID,
+    )]
+    pub hook_authority: Signer<'info>,
+
+    #[account(
+        has_one = token_mint_vault,
+        has_one = crypto_receipt_mint,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, has_one = receipt_state)]
+    pub source_position: Option<Box<Account<'info, UserPosition>>>,
+
+    #[account(mut, has_one = receipt_state)]
+    pub destination_position: Option<Box<Account<'info, UserPosition>>>,
+}
+
+/// Moves cost basis along with `amount` transferred receipts. Receipts the
+/// source did not track enter the destination at the current exchange rate.
+pub fn handle_transfer_position(ctx: Context<TransferPosition>, amount: u64) -> Result<()> {
+    let (tracked, basis) = match ctx.accounts.source_position.as_mut() {
+        Some(source_position) => source_position.record_transfer_out(amount)?,
+        None => (0, 0),
+    };
+    if let Some(destination_position) = ctx.accounts.destination_position.as_mut() {
+        let total_underlying = ctx
+            .accounts
+            .receipt_state
+            .total_underlying(ctx.accounts.token_mint_vault.amount)?;
+        let untracked_basis = receipts_to_underlying(
+            amount - tracked,
+            total_underlying,
+            ctx.accounts
+                .receipt_state
+                .receipt_supply(ctx.accounts.crypto_receipt_mint.supply)?,
+        )?;
+        let cost_basis = basis
+            .checked_add(untracked_basis)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        destination_position.record_transfer_in(amount, cost_basis)?;
+    }
+    Ok(())
+}
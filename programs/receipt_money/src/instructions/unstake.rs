@@ -0,0 +1,111 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{ReceiptState, RewardPool, StakePosition},
+    utils::transfer_checked_with_hook,
+};
+use super::stake::{checkpoint_reward_pools, settle_reward_pools, split_remaining_accounts};
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    #[account(
+        mut,
+        has_one = receipt_state,
+        has_one = owner,
+        seeds = [
+            StakePosition::STAKE_POSITION_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump = stake_position.bump,
+    )]
+    pub stake_position: Box<Account<'info, StakePosition>>,
+
+    #[account(mut, token::mint = crypto_receipt_mint)]
+    pub owner_crypto_receipt_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RewardPool::STAKE_VAULT_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref()
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    /// CHECK: Owns the stake vault
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Spl token program or token program 2022
+    pub crypto_receipt_mint_program: Interface<'info, TokenInterface>,
+}
+
+/// Return staked receipts to the owner. Earned rewards stay claimable.
+pub fn handle_unstake<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Unstake<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ReceiptErrorCode::InvalidInput);
+    require!(
+        amount <= ctx.accounts.stake_position.staked,
+        ReceiptErrorCode::InsufficientStake
+    );
+    let (reward_pool_accounts, hook_accounts) =
+        split_remaining_accounts(ctx.remaining_accounts, &ctx.accounts.receipt_state)?;
+    let reward_pools = settle_reward_pools(
+        reward_pool_accounts,
+        &ctx.accounts.receipt_state,
+        &mut ctx.accounts.stake_position,
+    )?;
+
+    let receipt_state_key = ctx.accounts.receipt_state.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+        receipt_state_key.as_ref(),
+        &[ctx.accounts.receipt_state.vault_authority_bump],
+    ]];
+    transfer_checked_with_hook(
+        ctx.accounts.vault_authority.to_account_info(),
+        ctx.accounts.stake_vault.to_account_info(),
+        ctx.accounts.owner_crypto_receipt_token_account.to_account_info(),
+        ctx.accounts.crypto_receipt_mint.to_account_info(),
+        ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+        amount,
+        ctx.accounts.crypto_receipt_mint.decimals,
+        hook_accounts,
+        signer_seeds,
+    )?;
+
+    let stake_position = &mut ctx.accounts.stake_position;
+    stake_position.staked -= amount;
+    checkpoint_reward_pools(&reward_pools, stake_position)?;
+    ctx.accounts.receipt_state.total_staked -= amount;
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::{
+    token_2022::Token2022,
+    token_2022_extensions::{interest_bearing_mint_update_rate, InterestBearingMintUpdateRate},
+    token_interface::{Mint, TokenAccount},
+};
+use crate::{
+    errors::ReceiptErrorCode,
+    state::ReceiptState,
+    utils::{annualized_rate_bps, exchange_rate},
+};
+
+#[derive(Accounts)]
+pub struct UpdateInterestRate<'info> {
+    #[account(
+        mut,
+        has_one = token_mint_vault,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    /// CHECK: Rate authority of the receipt mint
+    #[account(
+        seeds = [
+            ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+            receipt_state.key().as_ref(),
+        ],
+        bump = receipt_state.vault_authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub crypto_receipt_mint_program: Program<'info, Token2022>,
+}
+
+/// Permissionless keeper crank. Sets the receipt mint's interest rate to the
+/// annualized growth of the exchange rate since the previous update, so the
+/// UI amount wallets display follows the vault's realized yield.
+pub fn handle_update_interest_rate(ctx: Context<UpdateInterestRate>) -> Result<i16> {
+    let receipt_state = &ctx.accounts.receipt_state;
+    let now = Clock::get()?.unix_timestamp;
+    let elapsed = now - receipt_state.interest_last_update_ts;
+    require!(
+        elapsed >= ReceiptState::MIN_INTEREST_UPDATE_INTERVAL,
+        ReceiptErrorCode::InterestRateUpdateTooSoon
+    );
+
+    let total_underlying = receipt_state.total_underlying(ctx.accounts.token_mint_vault.amount)?;
+    let current_rate = exchange_rate(
+        total_underlying,
+        receipt_state.receipt_supply(ctx.accounts.crypto_receipt_mint.supply)?,
+    )?;
+    let rate_bps = annualized_rate_bps(
+        receipt_state.interest_last_exchange_rate,
+        current_rate,
+        elapsed as u64,
+    )?;
+
+    let receipt_state_key = receipt_state.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ReceiptState::VAULT_AUTHORITY_SEED.as_bytes(),
+        receipt_state_key.as_ref(),
+        &[receipt_state.vault_authority_bump],
+    ]];
+    interest_bearing_mint_update_rate(
+        CpiContext::new_with_signer(
+            ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+            InterestBearingMintUpdateRate {
+                token_program_id: ctx.accounts.crypto_receipt_mint_program.to_account_info(),
+                mint: ctx.accounts.crypto_receipt_mint.to_account_info(),
+                rate_authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        rate_bps,
+    )?;
+
+    let receipt_state = &mut ctx.accounts.receipt_state;
+    receipt_state.interest_last_exchange_rate = current_rate;
+    receipt_state.interest_last_update_ts = now;
+    Ok(rate_bps)
+}
@@ -0,0 +1,56 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::{
+    errors::ReceiptErrorCode,
+    events::BackingReport,
+    state::ReceiptState,
+    utils::{mul_div_floor, BASIS_POINTS_DIVISOR},
+};
+
+#[derive(Accounts)]
+pub struct VerifyBacking<'info> {
+    #[account(
+        has_one = token_mint_vault,
+        has_one = crypto_receipt_mint,
+        seeds = [
+            ReceiptState::STATE_SEED.as_bytes(),
+            receipt_state.token_mint.as_ref(),
+        ],
+        bump = receipt_state.bump,
+    )]
+    pub receipt_state: Box<Account<'info, ReceiptState>>,
+
+    pub token_mint_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub crypto_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+/// Permissionless proof-of-reserves check. Fails when receipts are not fully
+/// backed so it can be used as a guard instruction in other transactions.
+pub fn handle_verify_backing(ctx: Context<VerifyBacking>) -> Result<BackingReport> {
+    let receipt_state = &ctx.accounts.receipt_state;
+    let total_underlying = receipt_state.total_underlying(ctx.accounts.token_mint_vault.amount)?;
+    let receipt_supply = receipt_state.receipt_supply(ctx.accounts.crypto_receipt_mint.supply)?;
+    let collateralization_bps = if receipt_supply == 0 {
+        u64::MAX
+    } else {
+        mul_div_floor(total_underlying, BASIS_POINTS_DIVISOR, receipt_supply)?
+    };
+
+    let report = BackingReport {
+        receipt_state: receipt_state.key(),
+        total_underlying,
+        receipt_supply,
+        collateralization_bps,
+        slot: Clock::get()?.slot,
+    };
+    emit!(report.clone());
+    require!(
+        collateralization_bps >= BASIS_POINTS_DIVISOR,
+        ReceiptErrorCode::Undercollateralized
+    );
+    Ok(report)
+}
@@ -0,0 +1,52 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::TokenAccount;
+use crate::{
+    errors::ReceiptErrorCode,
+    state::{Obligation, ObligationHealth},
+};
+use super::lending_market::*;
+
+#[derive(Accounts)]
+pub struct WithdrawCollateral<'info> {
+    pub market: LendingMarket<'info>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        constraint = obligation.reserve == market.reserve.key() @ ReceiptErrorCode::InvalidInput,
+    )]
+    pub obligation: Box<Account<'info, Obligation>>,
+
+    #[account(mut, token::mint = market.crypto_receipt_mint)]
+    pub owner_crypto_receipt_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Unlock receipts, as long as the remaining collateral covers the debt at the reserve's LTV.
+/// The remaining accounts carry the receipt mint's transfer hook accounts.
+pub fn handle_withdraw_collateral<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawCollateral<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ReceiptErrorCode::InvalidInput);
+    let pricing = ctx.accounts.market.refresh()?;
+    let obligation = &mut ctx.accounts.obligation;
+    obligation.collateral = obligation
+        .collateral
+        .checked_sub(amount)
+        .ok_or(ReceiptErrorCode::InsufficientCollateral)?;
+    let health = ObligationHealth::new(&ctx.accounts.market.reserve, obligation, &pricing)?;
+    require!(
+        health.within_ltv(ctx.accounts.market.reserve.ltv_bps)?,
+        ReceiptErrorCode::BorrowLimitExceeded
+    );
+    ctx.accounts.market.release_collateral(
+        ctx.accounts.owner_crypto_receipt_token_account.to_account_info(),
+        amount,
+        ctx.remaining_accounts,
+    )
+}
@@ -0,0 +1,34 @@
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount,
+    prelude::*,
+};
+use anchor_spl::token_interface::TokenAccount;
+use crate::{errors::ReceiptErrorCode, state::AdminAction};
+use super::lending_market::*;
+
+#[derive(Accounts)]
+pub struct WithdrawReserveLiquidity<'info> {
+    pub market: LendingMarket<'info>,
+
+    #[account(address = market.receipt_state.authority @ ReceiptErrorCode::Unauthorized)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, token::mint = market.borrow_mint)]
+    pub receiver_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Withdraw idle liquidity, including repaid interest, from the reserve.
+/// Needs an approved `AdminAction` on a timelocked market.
+pub fn handle_withdraw_reserve_liquidity(ctx: Context<WithdrawReserveLiquidity>, amount: u64) -> Result<()> {
+    require!(amount > 0, ReceiptErrorCode::InvalidInput);
+    let action = AdminAction::WithdrawReserveLiquidity {
+        borrow_mint: ctx.accounts.market.borrow_mint.key(),
+        amount,
+        receiver_token_account: ctx.accounts.receiver_token_account.key(),
+    };
+    ctx.accounts.market.receipt_state.authorize_admin_action(&action)?;
+    ctx.accounts.market.refresh()?;
+    ctx.accounts
+        .market
+        .pay_out_liquidity(ctx.accounts.receiver_token_account.to_account_info(), amount)
+}
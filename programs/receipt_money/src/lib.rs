@@ -4,9 +4,12 @@ pub mod instructions;
 pub mod state;
 pub mod utils;
 pub mod errors;
+pub mod events;
 
 use instructions::*;
 use instructions::initialize::TokenMetadataArgs;
+use events::BackingReport;
+use state::{MarketParamChange, ObligationHealth, OracleConfig, TransactionAccount};
 
 declare_id!("RMcr2nvyrwCh89SvH47916S9TCvPkoGBPNR8E1d1LWa");
 
@@ -14,16 +17,398 @@ declare_id!("RMcr2nvyrwCh89SvH47916S9TCvPkoGBPNR8E1d1LWa");
 pub mod receipt_money {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, name: String, symbol: String, uri: String) -> Result<()> {
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        name: String,
+        symbol: String,
+        uri: String,
+        interest_bearing: bool,
+        confidential_transfer_auditor: Option<[u8; 32]>,
+    ) -> Result<()> {
         let args = TokenMetadataArgs {
             name,
             symbol,
             uri,
         };
-        instructions::initialize::handle_initialize(ctx, args)
+        instructions::initialize::handle_initialize(ctx, args, interest_bearing, confidential_transfer_auditor)
     }
 
     pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
         instructions::deposit::handle_deposit(ctx, amount)
     }
+
+    pub fn redeem(ctx: Context<Redeem>, receipt_amount: u64) -> Result<()> {
+        instructions::redeem::handle_redeem(ctx, receipt_amount)
+    }
+
+    pub fn set_oracle_config(ctx: Context<SetOracleConfig>, config: OracleConfig) -> Result<()> {
+        instructions::set_oracle_config::handle_set_oracle_config(ctx, config)
+    }
+
+    pub fn get_receipt_price(ctx: Context<GetReceiptPrice>) -> Result<ReceiptPrice> {
+        instructions::get_receipt_price::handle_get_receipt_price(ctx)
+    }
+
+    pub fn set_flash_loan_config(ctx: Context<SetFlashLoanConfig>, enabled: bool, fee_bps: u16) -> Result<()> {
+        instructions::set_flash_loan_config::handle_set_flash_loan_config(ctx, enabled, fee_bps)
+    }
+
+    pub fn flash_borrow(ctx: Context<FlashBorrow>, amount: u64) -> Result<()> {
+        instructions::flash_borrow::handle_flash_borrow(ctx, amount)
+    }
+
+    pub fn flash_repay(ctx: Context<FlashRepay>) -> Result<()> {
+        instructions::flash_repay::handle_flash_repay(ctx)
+    }
+
+    pub fn set_swap_program(ctx: Context<SetSwapProgram>, swap_program: Pubkey) -> Result<()> {
+        instructions::set_swap_program::handle_set_swap_program(ctx, swap_program)
+    }
+
+    pub fn swap_and_deposit<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapAndDeposit<'info>>,
+        amount_in: u64,
+        min_amount_out: u64,
+        swap_data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::swap_and_deposit::handle_swap_and_deposit(ctx, amount_in, min_amount_out, swap_data)
+    }
+
+    pub fn verify_backing(ctx: Context<VerifyBacking>) -> Result<BackingReport> {
+        instructions::verify_backing::handle_verify_backing(ctx)
+    }
+
+    pub fn preview_deposit(ctx: Context<Preview>, amount: u64) -> Result<u64> {
+        instructions::preview::handle_preview_deposit(ctx, amount)
+    }
+
+    pub fn preview_redeem(ctx: Context<Preview>, receipt_amount: u64) -> Result<u64> {
+        instructions::preview::handle_preview_redeem(ctx, receipt_amount)
+    }
+
+    pub fn max_deposit(ctx: Context<Preview>) -> Result<u64> {
+        instructions::preview::handle_max_deposit(ctx)
+    }
+
+    pub fn max_redeem(ctx: Context<MaxRedeem>) -> Result<u64> {
+        instructions::preview::handle_max_redeem(ctx)
+    }
+
+    pub fn migrate_receipt_state(ctx: Context<MigrateReceiptState>) -> Result<()> {
+        instructions::migrate_receipt_state::handle_migrate_receipt_state(ctx)
+    }
+
+    pub fn close_market<'info>(ctx: Context<'_, '_, 'info, 'info, CloseMarket<'info>>) -> Result<()> {
+        instructions::close_market::handle_close_market(ctx)
+    }
+
+    pub fn set_fee_config(
+        ctx: Context<SetFeeConfig>,
+        deposit_fee_bps: u16,
+        referral_fee_share_bps: u16,
+        fee_receiver: Pubkey,
+    ) -> Result<()> {
+        instructions::set_fee_config::handle_set_fee_config(ctx, deposit_fee_bps, referral_fee_share_bps, fee_receiver)
+    }
+
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        instructions::register_referrer::handle_register_referrer(ctx)
+    }
+
+    pub fn open_user_position(ctx: Context<OpenUserPosition>) -> Result<()> {
+        instructions::open_user_position::handle_open_user_position(ctx)
+    }
+
+    pub fn transfer_position(ctx: Context<TransferPosition>, amount: u64) -> Result<()> {
+        instructions::transfer_position::handle_transfer_position(ctx, amount)
+    }
+
+    pub fn update_interest_rate(ctx: Context<UpdateInterestRate>) -> Result<i16> {
+        instructions::update_interest_rate::handle_update_interest_rate(ctx)
+    }
+
+    pub fn set_confidential_transfer_auditor(
+        ctx: Context<SetConfidentialTransferAuditor>,
+        auditor_elgamal_pubkey: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::set_confidential_transfer_auditor::handle_set_confidential_transfer_auditor(
+            ctx,
+            auditor_elgamal_pubkey,
+        )
+    }
+
+    pub fn set_authority(ctx: Context<SetAuthority>, new_authority: Pubkey) -> Result<()> {
+        instructions::set_authority::handle_set_authority(ctx, new_authority)
+    }
+
+    pub fn set_timelock(ctx: Context<SetTimelock>, guardian: Pubkey, delay: i64) -> Result<()> {
+        instructions::set_timelock::handle_set_timelock(ctx, guardian, delay)
+    }
+
+    pub fn propose_param_change(ctx: Context<ProposeParamChange>, change: MarketParamChange) -> Result<()> {
+        instructions::propose_param_change::handle_propose_param_change(ctx, change)
+    }
+
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        instructions::cancel_proposal::handle_cancel_proposal(ctx)
+    }
+
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        instructions::execute_proposal::handle_execute_proposal(ctx)
+    }
+
+    pub fn create_multisig(ctx: Context<CreateMultisig>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        instructions::create_multisig::handle_create_multisig(ctx, signers, threshold)
+    }
+
+    pub fn create_multisig_transaction(
+        ctx: Context<CreateMultisigTransaction>,
+        program_id: Pubkey,
+        accounts: Vec<TransactionAccount>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::create_multisig_transaction::handle_create_multisig_transaction(
+            ctx, program_id, accounts, data,
+        )
+    }
+
+    pub fn approve_multisig_transaction(ctx: Context<ApproveMultisigTransaction>) -> Result<()> {
+        instructions::approve_multisig_transaction::handle_approve_multisig_transaction(ctx)
+    }
+
+    pub fn execute_multisig_transaction<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteMultisigTransaction<'info>>,
+    ) -> Result<()> {
+        instructions::execute_multisig_transaction::handle_execute_multisig_transaction(ctx)
+    }
+
+    pub fn set_multisig_config(
+        ctx: Context<SetMultisigConfig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        instructions::set_multisig_config::handle_set_multisig_config(ctx, signers, threshold)
+    }
+
+    pub fn set_flow_limits(
+        ctx: Context<SetFlowLimits>,
+        window_slots: u64,
+        max_inflow_bps: u16,
+        max_outflow_bps: u16,
+    ) -> Result<()> {
+        instructions::set_flow_limits::handle_set_flow_limits(ctx, window_slots, max_inflow_bps, max_outflow_bps)
+    }
+
+    pub fn reset_flow_limits(ctx: Context<ResetFlowLimits>) -> Result<()> {
+        instructions::reset_flow_limits::handle_reset_flow_limits(ctx)
+    }
+
+    pub fn add_reward_pool(ctx: Context<AddRewardPool>) -> Result<()> {
+        instructions::add_reward_pool::handle_add_reward_pool(ctx)
+    }
+
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64, emission_end_ts: i64) -> Result<()> {
+        instructions::fund_rewards::handle_fund_rewards(ctx, amount, emission_end_ts)
+    }
+
+    pub fn stake<'info>(ctx: Context<'_, '_, 'info, 'info, Stake<'info>>, amount: u64) -> Result<()> {
+        instructions::stake::handle_stake(ctx, amount)
+    }
+
+    pub fn unstake<'info>(ctx: Context<'_, '_, 'info, 'info, Unstake<'info>>, amount: u64) -> Result<()> {
+        instructions::unstake::handle_unstake(ctx, amount)
+    }
+
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<u64> {
+        instructions::claim_rewards::handle_claim_rewards(ctx)
+    }
+
+    pub fn create_distribution(
+        ctx: Context<CreateDistribution>,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        num_nodes: u64,
+        expiry_ts: i64,
+    ) -> Result<()> {
+        instructions::create_distribution::handle_create_distribution(
+            ctx,
+            merkle_root,
+            total_amount,
+            num_nodes,
+            expiry_ts,
+        )
+    }
+
+    pub fn claim(ctx: Context<Claim>, index: u64, amount: u64, proof: Vec<[u8; 32]>) -> Result<()> {
+        instructions::claim::handle_claim(ctx, index, amount, proof)
+    }
+
+    pub fn clawback(ctx: Context<Clawback>) -> Result<()> {
+        instructions::clawback::handle_clawback(ctx)
+    }
+
+    pub fn set_bridge_config(
+        ctx: Context<SetBridgeConfig>,
+        core_bridge_program: Pubkey,
+        escrow: bool,
+    ) -> Result<()> {
+        instructions::set_bridge_config::handle_set_bridge_config(ctx, core_bridge_program, escrow)
+    }
+
+    pub fn register_foreign_emitter(
+        ctx: Context<RegisterForeignEmitter>,
+        chain: u16,
+        address: [u8; 32],
+    ) -> Result<()> {
+        instructions::register_foreign_emitter::handle_register_foreign_emitter(ctx, chain, address)
+    }
+
+    pub fn bridge_out<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BridgeOut<'info>>,
+        amount: u64,
+        target_chain: u16,
+        recipient: [u8; 32],
+        nonce: u32,
+    ) -> Result<()> {
+        instructions::bridge_out::handle_bridge_out(ctx, amount, target_chain, recipient, nonce)
+    }
+
+    pub fn bridge_in<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BridgeIn<'info>>,
+        sequence: u64,
+    ) -> Result<()> {
+        instructions::bridge_in::handle_bridge_in(ctx, sequence)
+    }
+
+    pub fn init_lending_reserve(
+        ctx: Context<InitLendingReserve>,
+        ltv_bps: u16,
+        liquidation_threshold_bps: u16,
+        liquidation_bonus_bps: u16,
+        base_rate_bps: u16,
+        rate_slope_bps: u16,
+    ) -> Result<()> {
+        instructions::init_lending_reserve::handle_init_lending_reserve(
+            ctx,
+            ltv_bps,
+            liquidation_threshold_bps,
+            liquidation_bonus_bps,
+            base_rate_bps,
+            rate_slope_bps,
+        )
+    }
+
+    pub fn supply_reserve_liquidity(ctx: Context<SupplyReserveLiquidity>, amount: u64) -> Result<()> {
+        instructions::supply_reserve_liquidity::handle_supply_reserve_liquidity(ctx, amount)
+    }
+
+    pub fn withdraw_reserve_liquidity(ctx: Context<WithdrawReserveLiquidity>, amount: u64) -> Result<()> {
+        instructions::withdraw_reserve_liquidity::handle_withdraw_reserve_liquidity(ctx, amount)
+    }
+
+    pub fn deposit_collateral<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DepositCollateral<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::deposit_collateral::handle_deposit_collateral(ctx, amount)
+    }
+
+    pub fn withdraw_collateral<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawCollateral<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_collateral::handle_withdraw_collateral(ctx, amount)
+    }
+
+    pub fn borrow(ctx: Context<Borrow>, amount: u64) -> Result<()> {
+        instructions::borrow::handle_borrow(ctx, amount)
+    }
+
+    pub fn repay(ctx: Context<Repay>, amount: u64) -> Result<u64> {
+        instructions::repay::handle_repay(ctx, amount)
+    }
+
+    pub fn liquidate<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Liquidate<'info>>,
+        repay_amount: u64,
+    ) -> Result<u64> {
+        instructions::liquidate::handle_liquidate(ctx, repay_amount)
+    }
+
+    pub fn get_obligation_health(ctx: Context<GetObligationHealth>) -> Result<ObligationHealth> {
+        instructions::get_obligation_health::handle_get_obligation_health(ctx)
+    }
+
+    pub fn leverage_deposit(
+        ctx: Context<LeverageDeposit>,
+        amount: u64,
+        target_leverage_bps: u64,
+        min_collateral: u64,
+    ) -> Result<u64> {
+        instructions::leverage_deposit::handle_leverage_deposit(ctx, amount, target_leverage_bps, min_collateral)
+    }
+
+    pub fn deleverage(ctx: Context<Deleverage>, receipts: u64, min_underlying_out: u64) -> Result<u64> {
+        instructions::deleverage::handle_deleverage(ctx, receipts, min_underlying_out)
+    }
+
+    pub fn set_stake_pool(ctx: Context<SetStakePool>) -> Result<()> {
+        instructions::set_stake_pool::handle_set_stake_pool(ctx)
+    }
+
+    pub fn deposit_stake_pool_tokens(ctx: Context<DepositStakePoolTokens>, amount: u64) -> Result<u64> {
+        instructions::deposit_stake_pool_tokens::handle_deposit_stake_pool_tokens(ctx, amount)
+    }
+
+    pub fn refresh_stake_pool_value(ctx: Context<RefreshStakePoolValue>) -> Result<()> {
+        instructions::refresh_stake_pool_value::handle_refresh_stake_pool_value(ctx)
+    }
+
+    pub fn init_insurance_vault(ctx: Context<InitInsuranceVault>) -> Result<()> {
+        instructions::init_insurance_vault::handle_init_insurance_vault(ctx)
+    }
+
+    pub fn set_insurance_fee_share(ctx: Context<SetInsuranceFeeShare>, share_bps: u16) -> Result<()> {
+        instructions::set_insurance_fee_share::handle_set_insurance_fee_share(ctx, share_bps)
+    }
+
+    pub fn fund_insurance(ctx: Context<FundInsurance>, amount: u64) -> Result<()> {
+        instructions::fund_insurance::handle_fund_insurance(ctx, amount)
+    }
+
+    pub fn cover_loss(ctx: Context<CoverLoss>, amount: u64) -> Result<()> {
+        instructions::cover_loss::handle_cover_loss(ctx, amount)
+    }
+
+    pub fn emergency_shutdown(ctx: Context<EmergencyShutdownMarket>) -> Result<()> {
+        instructions::emergency_shutdown::handle_emergency_shutdown(ctx)
+    }
+
+    pub fn emergency_redeem(ctx: Context<EmergencyRedeem>, receipt_amount: u64) -> Result<u64> {
+        instructions::emergency_redeem::handle_emergency_redeem(ctx, receipt_amount)
+    }
+
+    pub fn apply_confidential_transfer_auditor(
+        ctx: Context<ApplyConfidentialTransferAuditor>,
+    ) -> Result<()> {
+        instructions::apply_confidential_transfer_auditor::handle_apply_confidential_transfer_auditor(
+            ctx,
+        )
+    }
+
+    pub fn sweep_unallocated_rewards(ctx: Context<SweepUnallocatedRewards>) -> Result<u64> {
+        instructions::sweep_unallocated_rewards::handle_sweep_unallocated_rewards(ctx)
+    }
+
+    pub fn close_lending_reserve(ctx: Context<CloseLendingReserve>) -> Result<()> {
+        instructions::close_lending_reserve::handle_close_lending_reserve(ctx)
+    }
+
+    pub fn close_foreign_emitter(ctx: Context<CloseForeignEmitter>) -> Result<()> {
+        instructions::close_foreign_emitter::handle_close_foreign_emitter(ctx)
+    }
+
+    pub fn redeem_stake_pool_tokens(ctx: Context<RedeemStakePoolTokens>, receipt_amount: u64) -> Result<u64> {
+        instructions::redeem_stake_pool_tokens::handle_redeem_stake_pool_tokens(ctx, receipt_amount)
+    }
 }
@@ -0,0 +1,131 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        hash::hash,
+        instruction::{AccountMeta, Instruction},
+    },
+};
+use crate::errors::ReceiptErrorCode;
+
+/// Emitter registered for a market on another chain. `bridge_in` only
+/// accepts VAAs emitted by it.
+#[account]
+pub struct ForeignEmitter {
+    pub receipt_state: Pubkey,
+    pub chain: u16,
+    pub address: [u8; 32],
+    pub bump: u8,
+}
+
+impl ForeignEmitter {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // receipt_state
+        2 + // chain
+        32 + // address
+        1; // bump
+
+    pub const FOREIGN_EMITTER_SEED: &'static str = "foreign_emitter";
+
+    pub fn set(&mut self, receipt_state: Pubkey, chain: u16, address: [u8; 32], bump: u8) {
+        self.receipt_state = receipt_state;
+        self.chain = chain;
+        self.address = address;
+        self.bump = bump;
+    }
+}
+
+/// Marks a message as redeemed by `bridge_in`. Keyed on the message id
+/// (emitter chain, emitter address, sequence) rather than the posted VAA
+/// account, so the same message posted twice is still redeemed once.
+#[account]
+pub struct BridgeClaim {
+    pub receipt_state: Pubkey,
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub sequence: u64,
+    pub bump: u8,
+}
+
+impl BridgeClaim {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // receipt_state
+        2 + // emitter_chain
+        32 + // emitter_address
+        8 + // sequence
+        1; // bump
+
+    pub const BRIDGE_CLAIM_SEED: &'static str = "bridge_claim";
+    pub const BRIDGE_EMITTER_SEED: &'static str = "bridge_emitter";
+    pub const BRIDGE_ESCROW_SEED: &'static str = "bridge_escrow";
+    /// Chain id of this chain in bridge messages.
+    pub const CHAIN_ID: u16 = 1;
+    /// Finalized, the core bridge only relays messages after finality.
+    pub const CONSISTENCY_LEVEL: u8 = 1;
+}
+
+/// Payload of every message posted by `bridge_out`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BridgeTransfer {
+    /// Underlying mint of the market, identifies the market across chains.
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub target_chain: u16,
+    /// Recipient wallet on the target chain.
+    pub recipient: [u8; 32],
+}
+
+/// A VAA the core bridge verified and posted, read without depending on the
+/// core bridge crate so the program stays configurable.
+///
+/// The core bridge interface here is our own, not Wormhole's: the posted VAA
+/// is an Anchor account and `post_message` an Anchor instruction, which
+/// `mock_core_bridge` implements. Wormhole's core bridge lays out posted VAAs
+/// and encodes `post_message` differently, so bridging does not work against
+/// it until these two are ported to its formats.
+#[derive(AnchorDeserialize)]
+pub struct PostedVaa {
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub sequence: u64,
+    pub posted_at: i64,
+    pub payload: Vec<u8>,
+}
+
+impl PostedVaa {
+    /// Deserialize a posted VAA, which must be owned by `core_bridge_program`.
+    pub fn load(account: &AccountInfo, core_bridge_program: &Pubkey) -> Result<Self> {
+        require_keys_eq!(*account.owner, *core_bridge_program, ReceiptErrorCode::InvalidBridgeMessage);
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == hash(b"account:PostedVaa").to_bytes()[..8],
+            ReceiptErrorCode::InvalidBridgeMessage
+        );
+        Self::deserialize(&mut &data[8..]).map_err(|_| error!(ReceiptErrorCode::InvalidBridgeMessage))
+    }
+}
+
+/// The core bridge's `post_message` instruction, in the bespoke interface
+/// described on `PostedVaa`.
+pub fn post_message_instruction(
+    core_bridge_program: Pubkey,
+    payer: Pubkey,
+    emitter: Pubkey,
+    sequence: Pubkey,
+    message: Pubkey,
+    nonce: u32,
+    payload: Vec<u8>,
+) -> Result<Instruction> {
+    let mut data = hash(b"global:post_message").to_bytes()[..8].to_vec();
+    (nonce, payload, BridgeClaim::CONSISTENCY_LEVEL).serialize(&mut data)?;
+    Ok(Instruction {
+        program_id: core_bridge_program,
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(emitter, true),
+            AccountMeta::new(sequence, false),
+            AccountMeta::new(message, false),
+            AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+        ],
+        data,
+    })
+}
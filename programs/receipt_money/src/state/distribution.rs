@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::errors::ReceiptErrorCode;
+
+/// A merkle airdrop funded by the market authority. Each leaf pays a fixed
+/// amount to one wallet and is claimed at most once, tracked in `claimed`.
+#[account]
+pub struct Distribution {
+    pub receipt_state: Pubkey,
+    pub id: u64,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    /// Leaves in the tree, claim indices run from zero to `num_nodes - 1`.
+    pub num_nodes: u64,
+    /// Claims close at this time and the authority may claw back the rest.
+    pub expiry_ts: i64,
+    pub clawed_back: bool,
+    pub bump: u8,
+    /// One bit per leaf, set once claimed.
+    pub claimed: Vec<u8>,
+}
+
+impl Distribution {
+    pub const DISTRIBUTION_SEED: &'static str = "distribution";
+    pub const DISTRIBUTION_VAULT_SEED: &'static str = "distribution_vault";
+    /// Keeps the account within the size a program can allocate in one instruction.
+    pub const MAX_NODES: u64 = 80_000;
+
+    pub fn space(num_nodes: u64) -> usize {
+        8 + // discriminator
+            32 + // receipt_state
+            8 + // id
+            32 + // mint
+            32 + // vault
+            32 + // merkle_root
+            8 + // total_amount
+            8 + // claimed_amount
+            8 + // num_nodes
+            8 + // expiry_ts
+            1 + // clawed_back
+            1 + // bump
+            4 + Self::bitmap_len(num_nodes) // claimed
+    }
+
+    pub fn bitmap_len(num_nodes: u64) -> usize {
+        num_nodes.div_ceil(8) as usize
+    }
+
+    pub fn is_claimed(&self, index: u64) -> bool {
+        self.claimed[(index / 8) as usize] & (1 << (index % 8)) != 0
+    }
+
+    /// Mark `index` claimed and count `amount` against the total.
+    pub fn record_claim(&mut self, index: u64, amount: u64) -> Result<()> {
+        require!(index < self.num_nodes, ReceiptErrorCode::InvalidMerkleProof);
+        require!(!self.is_claimed(index), ReceiptErrorCode::AlreadyClaimed);
+        self.claimed_amount = self
+            .claimed_amount
+            .checked_add(amount)
+            .filter(|claimed| *claimed <= self.total_amount)
+            .ok_or(ReceiptErrorCode::InvalidDistribution)?;
+        self.claimed[(index / 8) as usize] |= 1 << (index % 8);
+        Ok(())
+    }
+}
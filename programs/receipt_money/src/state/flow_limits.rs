@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use crate::{
+    errors::ReceiptErrorCode,
+    utils::{mul_div_floor, BASIS_POINTS_DIVISOR},
+};
+
+/// Caps on underlying entering and leaving a market over a sliding window of
+/// `window_slots`, as basis points of the TVL when the window started. The
+/// previous window counts in proportion to how much of it still overlaps.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct FlowLimits {
+    /// Zero disables the limits.
+    pub window_slots: u64,
+    pub max_inflow_bps: u16,
+    pub max_outflow_bps: u16,
+    /// First slot of the current window, a multiple of `window_slots`.
+    pub window_start_slot: u64,
+    pub window_start_tvl: u64,
+    pub window_inflow: u64,
+    pub window_outflow: u64,
+    pub previous_inflow: u64,
+    pub previous_outflow: u64,
+}
+
+impl FlowLimits {
+    pub const LEN: usize = 8 + // window_slots
+        2 + // max_inflow_bps
+        2 + // max_outflow_bps
+        8 + // window_start_slot
+        8 + // window_start_tvl
+        8 + // window_inflow
+        8 + // window_outflow
+        8 + // previous_inflow
+        8; // previous_outflow
+
+    pub fn is_enabled(&self) -> bool {
+        self.window_slots > 0
+    }
+
+    /// Forget all recorded flow and start a new window at `slot`.
+    pub fn reset(&mut self, slot: u64, tvl: u64) {
+        if self.is_enabled() {
+            self.window_start_slot = slot - slot % self.window_slots;
+        }
+        self.window_start_tvl = tvl;
+        self.window_inflow = 0;
+        self.window_outflow = 0;
+        self.previous_inflow = 0;
+        self.previous_outflow = 0;
+    }
+
+    /// Add a flow, returning false without recording it when it would break
+    /// a limit. `tvl` is the market's underlying before the flow.
+    pub fn record(&mut self, slot: u64, tvl: u64, inflow: u64, outflow: u64) -> Result<bool> {
+        if !self.is_enabled() {
+            return Ok(true);
+        }
+        self.roll(slot, tvl);
+        if self.window_start_tvl == 0 {
+            // Nothing to take a share of yet, the next window starts limiting
+            return Ok(true);
+        }
+
+        let window_inflow = self.window_inflow.checked_add(inflow).ok_or(ReceiptErrorCode::MathOverflow)?;
+        let window_outflow = self.window_outflow.checked_add(outflow).ok_or(ReceiptErrorCode::MathOverflow)?;
+        let rolling_inflow = self.rolling(slot, window_inflow, self.previous_inflow)?;
+        let rolling_outflow = self.rolling(slot, window_outflow, self.previous_outflow)?;
+        let max_inflow = self.max_flow(self.max_inflow_bps)?;
+        let max_outflow = self.max_flow(self.max_outflow_bps)?;
+        if (inflow > 0 && rolling_inflow > max_inflow) || (outflow > 0 && rolling_outflow > max_outflow) {
+            return Ok(false);
+        }
+        self.window_inflow = window_inflow;
+        self.window_outflow = window_outflow;
+        Ok(true)
+    }
+
+    /// Underlying that can still flow in and out at `slot` without breaking a
+    /// limit, `u64::MAX` where nothing limits it.
+    pub fn headroom(&self, slot: u64, tvl: u64) -> Result<(u64, u64)> {
+        if !self.is_enabled() {
+            return Ok((u64::MAX, u64::MAX));
+        }
+        let mut limits = *self;
+        limits.roll(slot, tvl);
+        if limits.window_start_tvl == 0 {
+            return Ok((u64::MAX, u64::MAX));
+        }
+        let rolling_inflow = limits.rolling(slot, limits.window_inflow, limits.previous_inflow)?;
+        let rolling_outflow = limits.rolling(slot, limits.window_outflow, limits.previous_outflow)?;
+        Ok((
+            limits.max_flow(limits.max_inflow_bps)?.saturating_sub(rolling_inflow),
+            limits.max_flow(limits.max_outflow_bps)?.saturating_sub(rolling_outflow),
+        ))
+    }
+
+    /// `window_flow` plus the share of `previous_flow` still overlapping the window.
+    fn rolling(&self, slot: u64, window_flow: u64, previous_flow: u64) -> Result<u64> {
+        let overlap = self.window_slots - (slot - self.window_start_slot);
+        window_flow
+            .checked_add(mul_div_floor(previous_flow, overlap, self.window_slots)?)
+            .ok_or(error!(ReceiptErrorCode::MathOverflow))
+    }
+
+    fn max_flow(&self, max_bps: u16) -> Result<u64> {
+        mul_div_floor(self.window_start_tvl, max_bps as u64, BASIS_POINTS_DIVISOR)
+    }
+
+    fn roll(&mut self, slot: u64, tvl: u64) {
+        let window_start_slot = slot - slot % self.window_slots;
+        if window_start_slot == self.window_start_slot {
+            return;
+        }
+        let adjacent = window_start_slot == self.window_start_slot + self.window_slots;
+        self.previous_inflow = if adjacent { self.window_inflow } else { 0 };
+        self.previous_outflow = if adjacent { self.window_outflow } else { 0 };
+        self.window_start_slot = window_start_slot;
+        self.window_start_tvl = tvl;
+        self.window_inflow = 0;
+        self.window_outflow = 0;
+    }
+}
@@ -0,0 +1,274 @@
+use anchor_lang::prelude::*;
+use crate::{
+    errors::ReceiptErrorCode,
+    utils::{
+        mul_div_ceil, mul_div_floor, receipts_to_underlying, underlying_to_receipts, OraclePrice,
+        BASIS_POINTS_DIVISOR,
+        EXCHANGE_RATE_SCALE, SECONDS_PER_YEAR,
+    },
+};
+
+/// Isolated lending pool of one market: receipts are the only collateral and
+/// `borrow_mint`, the underlying or a stable mint priced by the market
+/// oracle, the only borrowable asset. Liquidity is supplied by the authority.
+#[account]
+pub struct LendingReserve {
+    pub receipt_state: Pubkey,
+    pub borrow_mint: Pubkey,
+    pub liquidity_vault: Pubkey,
+    pub collateral_vault: Pubkey,
+    /// Maximum debt as basis points of the collateral value when borrowing.
+    pub ltv_bps: u16,
+    /// Debt above this share of the collateral value can be liquidated.
+    pub liquidation_threshold_bps: u16,
+    /// Extra collateral value paid to liquidators, in basis points of the repaid debt.
+    pub liquidation_bonus_bps: u16,
+    /// Annual borrow rate at zero utilization.
+    pub base_rate_bps: u16,
+    /// Annual borrow rate added at full utilization.
+    pub rate_slope_bps: u16,
+    /// Debt per borrow share, scaled by `EXCHANGE_RATE_SCALE`.
+    pub borrow_index: u128,
+    pub last_accrual_ts: i64,
+    pub total_borrow_shares: u64,
+    /// Receipts locked in `collateral_vault`.
+    pub total_collateral: u64,
+    pub bump: u8,
+}
+
+impl LendingReserve {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // receipt_state
+        32 + // borrow_mint
+        32 + // liquidity_vault
+        32 + // collateral_vault
+        2 + // ltv_bps
+        2 + // liquidation_threshold_bps
+        2 + // liquidation_bonus_bps
+        2 + // base_rate_bps
+        2 + // rate_slope_bps
+        16 + // borrow_index
+        8 + // last_accrual_ts
+        8 + // total_borrow_shares
+        8 + // total_collateral
+        1; // bump
+
+    pub const LENDING_RESERVE_SEED: &'static str = "lending_reserve";
+    pub const RESERVE_LIQUIDITY_SEED: &'static str = "reserve_liquidity";
+    pub const RESERVE_COLLATERAL_SEED: &'static str = "reserve_collateral";
+    /// Share of an obligation's debt one liquidation may repay.
+    pub const CLOSE_FACTOR_BPS: u64 = 5_000;
+
+    pub fn validate_config(
+        ltv_bps: u16,
+        liquidation_threshold_bps: u16,
+        liquidation_bonus_bps: u16,
+    ) -> Result<()> {
+        require!(
+            ltv_bps > 0
+                && ltv_bps <= liquidation_threshold_bps
+                && (liquidation_threshold_bps as u64) < BASIS_POINTS_DIVISOR
+                // A liquidation must leave the obligation healthier, not seize more than it repays
+                && (liquidation_threshold_bps as u64) * (BASIS_POINTS_DIVISOR + liquidation_bonus_bps as u64)
+                    < BASIS_POINTS_DIVISOR * BASIS_POINTS_DIVISOR,
+            ReceiptErrorCode::InvalidLendingConfig
+        );
+        Ok(())
+    }
+
+    /// Highest leverage an obligation can open while staying within the LTV,
+    /// once a deposit fee of `fee_bps` on the whole position is taken out of
+    /// its collateral.
+    pub fn max_leverage_bps(&self, fee_bps: u16) -> Result<u64> {
+        let collateral_ltv_bps = mul_div_floor(
+            self.ltv_bps as u64,
+            BASIS_POINTS_DIVISOR - fee_bps as u64,
+            BASIS_POINTS_DIVISOR,
+        )?;
+        mul_div_floor(
+            BASIS_POINTS_DIVISOR,
+            BASIS_POINTS_DIVISOR,
+            BASIS_POINTS_DIVISOR - collateral_ltv_bps,
+        )
+    }
+
+    pub fn total_debt(&self) -> Result<u64> {
+        self.debt_of(self.total_borrow_shares)
+    }
+
+    /// Debt owed for `shares`, rounded up.
+    pub fn debt_of(&self, shares: u64) -> Result<u64> {
+        let debt = (shares as u128)
+            .checked_mul(self.borrow_index)
+            .ok_or(ReceiptErrorCode::MathOverflow)?
+            .div_ceil(EXCHANGE_RATE_SCALE);
+        u64::try_from(debt).map_err(|_| error!(ReceiptErrorCode::MathOverflow))
+    }
+
+    /// Shares worth `amount` of debt, rounded up when borrowing and down when repaying.
+    pub fn shares_for(&self, amount: u64, round_up: bool) -> Result<u64> {
+        let scaled = (amount as u128)
+            .checked_mul(EXCHANGE_RATE_SCALE)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        let shares = if round_up {
+            scaled.div_ceil(self.borrow_index)
+        } else {
+            scaled / self.borrow_index
+        };
+        u64::try_from(shares).map_err(|_| error!(ReceiptErrorCode::MathOverflow))
+    }
+
+    /// Annual borrow rate at the current utilization.
+    pub fn borrow_rate_bps(&self, available_liquidity: u64) -> Result<u64> {
+        let debt = self.total_debt()?;
+        let utilization_bps = if debt == 0 {
+            0
+        } else {
+            mul_div_floor(debt, BASIS_POINTS_DIVISOR, debt.saturating_add(available_liquidity))?
+        };
+        Ok(self.base_rate_bps as u64 + mul_div_floor(self.rate_slope_bps as u64, utilization_bps, BASIS_POINTS_DIVISOR)?)
+    }
+
+    /// Grow the borrow index up to `now` with simple interest since the last accrual.
+    pub fn accrue(&mut self, now: i64, available_liquidity: u64) -> Result<()> {
+        let elapsed = now.saturating_sub(self.last_accrual_ts);
+        if elapsed <= 0 {
+            return Ok(());
+        }
+        let rate_bps = self.borrow_rate_bps(available_liquidity)?;
+        let growth = self
+            .borrow_index
+            .checked_mul(rate_bps as u128 * elapsed as u128)
+            .ok_or(ReceiptErrorCode::MathOverflow)?
+            / (BASIS_POINTS_DIVISOR as u128 * SECONDS_PER_YEAR as u128);
+        self.borrow_index = self
+            .borrow_index
+            .checked_add(growth)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        self.last_accrual_ts = now;
+        Ok(())
+    }
+}
+
+/// Collateral and debt of one borrower in a `LendingReserve`.
+#[account]
+pub struct Obligation {
+    pub reserve: Pubkey,
+    pub owner: Pubkey,
+    /// Receipts locked as collateral.
+    pub collateral: u64,
+    pub borrow_shares: u64,
+    pub bump: u8,
+}
+
+impl Obligation {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve
+        32 + // owner
+        8 + // collateral
+        8 + // borrow_shares
+        1; // bump
+
+    pub const OBLIGATION_SEED: &'static str = "obligation";
+}
+
+/// Everything needed to value receipts in units of the borrow mint.
+pub struct CollateralPricing {
+    pub total_underlying: u64,
+    pub receipt_supply: u64,
+    /// Underlying price, `None` when the reserve lends the underlying itself.
+    pub price: Option<OraclePrice>,
+    pub underlying_decimals: u8,
+    pub borrow_decimals: u8,
+}
+
+impl CollateralPricing {
+    /// Power of ten converting underlying times price into borrow mint units.
+    fn exponent(&self, price: &OraclePrice) -> (bool, u128) {
+        let exponent = price.expo + self.borrow_decimals as i32 - self.underlying_decimals as i32;
+        (exponent >= 0, 10u128.pow(exponent.unsigned_abs().min(38)))
+    }
+
+    /// Value of `receipts` at the market exchange rate, in borrow mint units.
+    pub fn value(&self, receipts: u64) -> Result<u64> {
+        let underlying = receipts_to_underlying(receipts, self.total_underlying, self.receipt_supply)?;
+        let Some(price) = self.price else {
+            return Ok(underlying);
+        };
+        let (scale_up, scale) = self.exponent(&price);
+        let value = underlying as u128 * price.price as u128;
+        let value = if scale_up { value.checked_mul(scale) } else { Some(value / scale) };
+        value
+            .and_then(|value| u64::try_from(value).ok())
+            .ok_or(error!(ReceiptErrorCode::MathOverflow))
+    }
+
+    /// Receipts worth `value` borrow mint units, rounded down.
+    pub fn receipts_for(&self, value: u64) -> Result<u64> {
+        let underlying = match self.price {
+            None => value,
+            Some(price) => {
+                let (scale_up, scale) = self.exponent(&price);
+                let underlying = if scale_up {
+                    scale
+                        .checked_mul(price.price as u128)
+                        .map(|divisor| value as u128 / divisor)
+                } else {
+                    (value as u128)
+                        .checked_mul(scale)
+                        .map(|scaled| scaled / price.price as u128)
+                };
+                underlying
+                    .and_then(|underlying| u64::try_from(underlying).ok())
+                    .ok_or(error!(ReceiptErrorCode::MathOverflow))?
+            }
+        };
+        underlying_to_receipts(underlying, self.total_underlying, self.receipt_supply)
+    }
+}
+
+/// Health of an obligation, in borrow mint units.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ObligationHealth {
+    pub collateral_value: u64,
+    pub debt: u64,
+    /// Debt allowed before liquidation over debt, `u64::MAX` without debt.
+    /// Below 10_000 the obligation can be liquidated.
+    pub health_factor_bps: u64,
+}
+
+impl ObligationHealth {
+    pub fn new(reserve: &LendingReserve, obligation: &Obligation, pricing: &CollateralPricing) -> Result<Self> {
+        let collateral_value = pricing.value(obligation.collateral)?;
+        let debt = reserve.debt_of(obligation.borrow_shares)?;
+        let liquidation_value = mul_div_floor(
+            collateral_value,
+            reserve.liquidation_threshold_bps as u64,
+            BASIS_POINTS_DIVISOR,
+        )?;
+        let health_factor_bps = if debt == 0 {
+            u64::MAX
+        } else {
+            mul_div_floor(liquidation_value, BASIS_POINTS_DIVISOR, debt)?
+        };
+        Ok(Self { collateral_value, debt, health_factor_bps })
+    }
+
+    pub fn is_liquidatable(&self) -> bool {
+        self.health_factor_bps < BASIS_POINTS_DIVISOR
+    }
+
+    /// Whether the debt stays within `ltv_bps` of the collateral value.
+    pub fn within_ltv(&self, ltv_bps: u16) -> Result<bool> {
+        Ok(self.debt <= mul_div_floor(self.collateral_value, ltv_bps as u64, BASIS_POINTS_DIVISOR)?)
+    }
+}
+
+/// Collateral seized for repaying `repay_amount`, including the liquidation bonus.
+pub fn liquidation_seize_value(repay_amount: u64, liquidation_bonus_bps: u16) -> Result<u64> {
+    mul_div_ceil(
+        repay_amount,
+        BASIS_POINTS_DIVISOR + liquidation_bonus_bps as u64,
+        BASIS_POINTS_DIVISOR,
+    )
+}
@@ -1,3 +1,25 @@
+pub mod bridge;
+pub mod distribution;
+pub mod flow_limits;
+pub mod lending;
+pub mod multisig;
+pub mod oracle;
 pub mod receipt_state;
+pub mod referral_stats;
+pub mod rewards;
+pub mod stake_pool;
+pub mod timelock;
+pub mod user_position;
 
-pub use receipt_state::*; 
\ No newline at end of file
+pub use bridge::*;
+pub use distribution::*;
+pub use flow_limits::*;
+pub use lending::*;
+pub use multisig::*;
+pub use oracle::*;
+pub use receipt_state::*;
+pub use referral_stats::*;
+pub use rewards::*;
+pub use stake_pool::*;
+pub use timelock::*;
+pub use user_position::*;
@@ -0,0 +1,122 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction};
+use crate::errors::ReceiptErrorCode;
+
+/// M-of-N signer set. Its `MULTISIG_SIGNER_SEED` PDA is meant to be used as
+/// a market `authority`, signing the instructions of executed transactions.
+#[account]
+pub struct Multisig {
+    /// Any key, only used to derive the multisig address.
+    pub create_key: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+    /// Bumped on every signer set change, invalidating pending transactions.
+    pub owner_set_seqno: u32,
+    pub transaction_count: u64,
+    pub bump: u8,
+    pub signer_bump: u8,
+}
+
+impl Multisig {
+    pub const MAX_SIGNERS: usize = 10;
+    pub const LEN: usize = 8 + // discriminator
+        32 + // create_key
+        4 + 32 * Self::MAX_SIGNERS + // signers
+        1 + // threshold
+        4 + // owner_set_seqno
+        8 + // transaction_count
+        1 + // bump
+        1; // signer_bump
+
+    pub const MULTISIG_SEED: &'static str = "multisig";
+    pub const MULTISIG_SIGNER_SEED: &'static str = "multisig_signer";
+
+    pub fn validate_signers(signers: &[Pubkey], threshold: u8) -> Result<()> {
+        let unique = signers
+            .iter()
+            .enumerate()
+            .all(|(i, signer)| !signers[..i].contains(signer));
+        require!(
+            unique
+                && signers.len() <= Self::MAX_SIGNERS
+                && threshold > 0
+                && threshold as usize <= signers.len(),
+            ReceiptErrorCode::InvalidMultisigConfig
+        );
+        Ok(())
+    }
+
+    pub fn signer_index(&self, key: &Pubkey) -> Result<usize> {
+        self.signers
+            .iter()
+            .position(|signer| signer == key)
+            .ok_or(error!(ReceiptErrorCode::NotMultisigSigner))
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct TransactionAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl TransactionAccount {
+    pub const LEN: usize = 32 + // pubkey
+        1 + // is_signer
+        1; // is_writable
+}
+
+/// An instruction waiting for `threshold` approvals of a `Multisig`.
+#[account]
+pub struct MultisigTransaction {
+    pub multisig: Pubkey,
+    pub proposer: Pubkey,
+    pub index: u64,
+    pub program_id: Pubkey,
+    pub accounts: Vec<TransactionAccount>,
+    pub data: Vec<u8>,
+    /// One flag per multisig signer, in signer order.
+    pub approvals: Vec<bool>,
+    pub owner_set_seqno: u32,
+    pub executed: bool,
+    pub bump: u8,
+}
+
+impl MultisigTransaction {
+    pub const MULTISIG_TRANSACTION_SEED: &'static str = "multisig_transaction";
+
+    pub fn space(accounts_len: usize, data_len: usize) -> usize {
+        8 + // discriminator
+            32 + // multisig
+            32 + // proposer
+            8 + // index
+            32 + // program_id
+            4 + TransactionAccount::LEN * accounts_len + // accounts
+            4 + data_len + // data
+            4 + Multisig::MAX_SIGNERS + // approvals
+            4 + // owner_set_seqno
+            1 + // executed
+            1 // bump
+    }
+
+    pub fn approval_count(&self) -> usize {
+        self.approvals.iter().filter(|approved| **approved).count()
+    }
+
+    /// The stored instruction, with the multisig signer PDA marked as signer.
+    pub fn instruction(&self, multisig_signer: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: self
+                .accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.pubkey,
+                    is_signer: account.is_signer || account.pubkey == *multisig_signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: self.data.clone(),
+        }
+    }
+}
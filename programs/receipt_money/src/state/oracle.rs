@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+/// Price feed used to value a receipt market in USD.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct OracleConfig {
+    /// Pyth v2 price account of the underlying `token_mint`, other oracle
+    /// layouts such as Switchboard are not supported.
+    /// `Pubkey::default()` means no oracle is configured.
+    pub oracle: Pubkey,
+    /// Maximum age in seconds of the aggregate price.
+    pub max_staleness: u64,
+    /// Maximum confidence interval as basis points of the price.
+    pub max_confidence_bps: u16,
+}
+
+impl OracleConfig {
+    pub const LEN: usize = 32 + // oracle
+        8 + // max_staleness
+        2; // max_confidence_bps
+
+    pub fn is_configured(&self) -> bool {
+        self.oracle != Pubkey::default()
+    }
+}
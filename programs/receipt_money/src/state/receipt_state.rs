@@ -1,6 +1,16 @@
 use anchor_lang::prelude::*;
-use crate::ID;
+use anchor_spl::token::spl_token::native_mint;
+use crate::{
+    errors::ReceiptErrorCode,
+    utils::{mul_div_floor, BASIS_POINTS_DIVISOR},
+    ID,
+};
+use crate::events::FlowLimitTripped;
+use super::{AdminAction, FlowLimits, MarketParamChange, OracleConfig, StakePoolState};
 
+/// Market state. The layout is versioned: new fields are carved out of
+/// `reserved`, which keeps `LEN` fixed and lets zero be their default, and
+/// `version` is bumped only when existing accounts need `migrate_receipt_state`.
 #[account]
 pub struct ReceiptState {
     pub authority: Pubkey,
@@ -13,6 +23,110 @@ pub struct ReceiptState {
     pub token_mint_vault_bump: u8,
     pub receipt_mint_bump: u8,
     pub receipt_mint_vault_bump: u8,
+    /// Layout version, see `ReceiptState::CURRENT_VERSION`.
+    pub version: u8,
+    pub oracle: OracleConfig,
+    pub flash_loan_enabled: bool,
+    pub flash_loan_fee_bps: u16,
+    /// Underlying lent out of `token_mint_vault` by an in-flight flash loan.
+    pub flash_loan_outstanding: u64,
+    /// Program `swap_and_deposit` routes swaps through.
+    pub swap_program: Pubkey,
+    /// Protocol fee taken from deposits, in basis points.
+    pub deposit_fee_bps: u16,
+    /// Share of the deposit fee paid to the referrer, in basis points of the fee.
+    pub referral_fee_share_bps: u16,
+    /// Underlying token account receiving protocol fees.
+    pub fee_receiver: Pubkey,
+    /// Exchange rate at the last interest-bearing rate update, scaled by `EXCHANGE_RATE_SCALE`.
+    pub interest_last_exchange_rate: u128,
+    pub interest_last_update_ts: i64,
+    /// May cancel queued proposals.
+    pub guardian: Pubkey,
+    /// Seconds a proposal waits before execution, zero when admin changes are immediate.
+    pub timelock_delay: i64,
+    /// Proposals queued so far, the next proposal id.
+    pub proposal_count: u64,
+    /// Set when a flow limit trips, blocks deposits and redemptions until the guardian resets it.
+    pub paused: bool,
+    pub flow_limits: FlowLimits,
+    /// Receipts held in the stake vault for reward emissions.
+    pub total_staked: u64,
+    /// Reward pools added so far, the next pool index.
+    pub reward_pool_count: u8,
+    /// Distributions created so far, the next distribution id.
+    pub distribution_count: u64,
+    /// Core bridge program `bridge_out` posts messages to and `bridge_in` reads
+    /// VAAs from. It must implement the interface described on `PostedVaa`.
+    pub core_bridge_program: Pubkey,
+    /// Bridge out by escrowing receipts rather than burning them.
+    pub bridge_escrow: bool,
+    /// Receipts bridged out and not yet bridged back.
+    pub bridged_out_supply: u64,
+    /// SPL stake pool whose pool token is accepted as an alternate deposit.
+    pub stake_pool: Pubkey,
+    /// Holds the deposited stake pool tokens.
+    pub stake_pool_vault: Pubkey,
+    /// Underlying the stake pool tokens in `stake_pool_vault` were worth at the last refresh.
+    pub stake_pool_value: u64,
+    /// Underlying account absorbing strategy losses, funded by a cut of the protocol fee.
+    pub insurance_vault: Pubkey,
+    /// Share of the protocol fee paid to the insurance vault, in basis points of the fee.
+    pub insurance_fee_share_bps: u16,
+    /// Underlying moved from the insurance vault into `token_mint_vault` by `cover_loss`.
+    pub insurance_losses_covered: u64,
+    /// Set by `emergency_shutdown`, freezes deposits and redemptions for good,
+    /// leaving only `emergency_redeem` at the snapshot below.
+    pub shutdown: bool,
+    /// Underlying in `token_mint_vault` backing the receipts at shutdown,
+    /// plus any loss covered since.
+    pub shutdown_underlying: u64,
+    /// Stake pool tokens in `stake_pool_vault` at shutdown.
+    pub shutdown_stake_pool_tokens: u64,
+    /// Receipts outstanding at shutdown.
+    pub shutdown_receipt_supply: u64,
+    /// Auditor ElGamal key queued for the receipt mint, all zero to remove the auditor.
+    pub pending_auditor_elgamal_pubkey: [u8; 32],
+    /// Set until `apply_confidential_transfer_auditor` moves the queued key to the mint.
+    pub auditor_update_pending: bool,
+    /// Lending reserves opened by `init_lending_reserve` and not closed yet.
+    pub open_reserve_count: u16,
+    /// Pool epoch `stake_pool_value` was priced at, it is stale once the
+    /// current epoch is past it.
+    pub stake_pool_value_epoch: u64,
+    /// Foreign emitters registered and not closed yet.
+    pub foreign_emitter_count: u16,
+    /// Distributions created and not clawed back yet.
+    pub open_distribution_count: u64,
+    /// Hash of the `AdminAction` approved by an executed proposal and not
+    /// taken yet, all zero when there is none.
+    pub approved_admin_action: [u8; 32],
+    /// Zeroed space for future fields.
+    pub reserved: [u8; ReceiptState::RESERVED_LEN],
+}
+
+// Version 1 accounts are allocated at this size, new fields must come out of `reserved`
+const _: () = assert!(ReceiptState::LEN == 771);
+
+/// Layout of accounts created before `ReceiptState` was versioned.
+#[derive(AnchorDeserialize)]
+pub struct ReceiptStateV0 {
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_mint_vault: Pubkey,
+    pub crypto_receipt_mint: Pubkey,
+    pub crypto_receipt_vault: Pubkey,
+    pub bump: u8,
+    pub vault_authority_bump: u8,
+    pub token_mint_vault_bump: u8,
+    pub receipt_mint_bump: u8,
+    pub receipt_mint_vault_bump: u8,
+}
+
+impl ReceiptStateV0 {
+    pub const LEN: usize = 8 + // discriminator
+        32 * 5 + // pubkeys
+        5; // bumps
 }
 
 impl ReceiptState {
@@ -26,12 +140,291 @@ impl ReceiptState {
         1 + // vault_authority_bump
         1 + // token_mint_vault_bump
         1 + // receipt_mint_bump
-        1; // receipt_mint_vault_bump
+        1 + // receipt_mint_vault_bump
+        1 + // version
+        OracleConfig::LEN + // oracle
+        1 + // flash_loan_enabled
+        2 + // flash_loan_fee_bps
+        8 + // flash_loan_outstanding
+        32 + // swap_program
+        2 + // deposit_fee_bps
+        2 + // referral_fee_share_bps
+        32 + // fee_receiver
+        16 + // interest_last_exchange_rate
+        8 + // interest_last_update_ts
+        32 + // guardian
+        8 + // timelock_delay
+        8 + // proposal_count
+        1 + // paused
+        FlowLimits::LEN + // flow_limits
+        8 + // total_staked
+        1 + // reward_pool_count
+        8 + // distribution_count
+        32 + // core_bridge_program
+        1 + // bridge_escrow
+        8 + // bridged_out_supply
+        32 + // stake_pool
+        32 + // stake_pool_vault
+        8 + // stake_pool_value
+        32 + // insurance_vault
+        2 + // insurance_fee_share_bps
+        8 + // insurance_losses_covered
+        1 + // shutdown
+        8 + // shutdown_underlying
+        8 + // shutdown_stake_pool_tokens
+        8 + // shutdown_receipt_supply
+        32 + // pending_auditor_elgamal_pubkey
+        1 + // auditor_update_pending
+        2 + // open_reserve_count
+        8 + // stake_pool_value_epoch
+        2 + // foreign_emitter_count
+        8 + // open_distribution_count
+        32 + // approved_admin_action
+        Self::RESERVED_LEN; // reserved
+
+    pub const RESERVED_LEN: usize = 61;
+    pub const CURRENT_VERSION: u8 = 1;
 
     pub const STATE_SEED: &'static str = "receipt_state";
     pub const VAULT_AUTHORITY_SEED: &'static str = "receipt_vault_authority";
     pub const MINT_SEED: &'static str = "receipt_mint";
     pub const MINT_VAULT_SEED: &'static str = "receipt_mint_vault";
+    pub const INSURANCE_VAULT_SEED: &'static str = "insurance_vault";
+
+    /// Minimum seconds between `update_interest_rate` calls.
+    pub const MIN_INTEREST_UPDATE_INTERVAL: i64 = 3_600;
+
+    /// Underlying owned by the market, including any flash loan in flight and
+    /// the value of deposited stake pool tokens at the last refresh.
+    pub fn total_underlying(&self, vault_amount: u64) -> Result<u64> {
+        vault_amount
+            .checked_add(self.flash_loan_outstanding)
+            .and_then(|total| total.checked_add(self.stake_pool_value))
+            .ok_or(error!(ReceiptErrorCode::MathOverflow))
+    }
+
+    /// Value the `pool_tokens` held in `stake_pool_vault` at `pool`'s rate.
+    pub fn set_stake_pool_value(&mut self, pool: &StakePoolState, pool_tokens: u64) -> Result<()> {
+        self.stake_pool_value = pool.lamports_for(pool_tokens)?;
+        self.stake_pool_value_epoch = pool.last_update_epoch;
+        Ok(())
+    }
+
+    /// Require the stake pool tokens to be valued this epoch before receipts
+    /// are minted or burned at a price. Pool tokens gain value every epoch,
+    /// pricing against last epoch's value would sell receipts below their backing.
+    pub fn require_fresh_stake_pool_value(&self) -> Result<()> {
+        require!(
+            self.stake_pool_value == 0 || self.stake_pool_value_epoch >= Clock::get()?.epoch,
+            ReceiptErrorCode::StaleStakePool
+        );
+        Ok(())
+    }
+
+    /// Part of `protocol_fee` paid to the insurance vault.
+    pub fn insurance_cut(&self, protocol_fee: u64) -> Result<u64> {
+        mul_div_floor(protocol_fee, self.insurance_fee_share_bps as u64, BASIS_POINTS_DIVISOR)
+    }
+
+    /// Receipts outstanding, including those burned by `bridge_out` that live
+    /// on another chain.
+    pub fn receipt_supply(&self, mint_supply: u64) -> Result<u64> {
+        if self.bridge_escrow {
+            return Ok(mint_supply);
+        }
+        mint_supply
+            .checked_add(self.bridged_out_supply)
+            .ok_or(error!(ReceiptErrorCode::MathOverflow))
+    }
+
+    pub fn is_timelocked(&self) -> bool {
+        self.timelock_delay > 0
+    }
+
+    /// Apply an admin change from a `set_*` instruction, only allowed while
+    /// the market has no timelock.
+    pub fn apply_direct_change(&mut self, change: MarketParamChange) -> Result<()> {
+        require!(!self.is_timelocked(), ReceiptErrorCode::TimelockActive);
+        self.apply_param_change(change)
+    }
+
+    pub fn apply_param_change(&mut self, change: MarketParamChange) -> Result<()> {
+        change.validate()?;
+        match change {
+            MarketParamChange::OracleConfig(config) => self.oracle = config,
+            MarketParamChange::FlashLoanConfig { enabled, fee_bps } => {
+                self.flash_loan_enabled = enabled;
+                self.flash_loan_fee_bps = fee_bps;
+            }
+            MarketParamChange::SwapProgram(swap_program) => self.swap_program = swap_program,
+            MarketParamChange::FeeConfig { deposit_fee_bps, referral_fee_share_bps, fee_receiver } => {
+                self.deposit_fee_bps = deposit_fee_bps;
+                self.referral_fee_share_bps = referral_fee_share_bps;
+                self.fee_receiver = fee_receiver;
+            }
+            MarketParamChange::Authority(authority) => self.authority = authority,
+            MarketParamChange::Timelock { guardian, delay } => {
+                self.guardian = guardian;
+                self.timelock_delay = delay;
+            }
+            MarketParamChange::FlowLimits { window_slots, max_inflow_bps, max_outflow_bps } => {
+                self.flow_limits.window_slots = window_slots;
+                self.flow_limits.max_inflow_bps = max_inflow_bps;
+                self.flow_limits.max_outflow_bps = max_outflow_bps;
+                // TVL is unknown here, so limits apply from the next window
+                self.flow_limits.reset(Clock::get()?.slot, 0);
+            }
+            MarketParamChange::BridgeConfig { core_bridge_program, escrow } => {
+                // Receipts already bridged out are backed the way they left
+                require!(
+                    escrow == self.bridge_escrow || self.bridged_out_supply == 0,
+                    ReceiptErrorCode::InvalidBridgeConfig
+                );
+                self.core_bridge_program = core_bridge_program;
+                self.bridge_escrow = escrow;
+            }
+            MarketParamChange::InsuranceFeeShare(share_bps) => {
+                require!(
+                    share_bps == 0 || self.insurance_vault != Pubkey::default(),
+                    ReceiptErrorCode::InvalidInsuranceVault
+                );
+                self.insurance_fee_share_bps = share_bps;
+            }
+            MarketParamChange::ConfidentialTransferAuditor(auditor) => {
+                self.pending_auditor_elgamal_pubkey = auditor.unwrap_or_default();
+                self.auditor_update_pending = true;
+            }
+            MarketParamChange::StakePool(stake_pool) => {
+                // Stake pools are valued in lamports, and the pool can only
+                // change until `set_stake_pool` opens its vault
+                require!(
+                    self.token_mint == native_mint::ID && self.stake_pool_vault == Pubkey::default(),
+                    ReceiptErrorCode::InvalidStakePool
+                );
+                self.stake_pool = stake_pool;
+            }
+            MarketParamChange::AdminAction(action) => self.approved_admin_action = action.hash(),
+            // Applied to the `ForeignEmitter` account by the caller
+            MarketParamChange::ForeignEmitter { .. } => {}
+        }
+        Ok(())
+    }
+
+    /// Check the authority may take `action` now, using up its approval on
+    /// a timelocked market.
+    pub fn authorize_admin_action(&mut self, action: &AdminAction) -> Result<()> {
+        if !self.is_timelocked() {
+            return Ok(());
+        }
+        require!(
+            self.approved_admin_action != [0; 32] && self.approved_admin_action == action.hash(),
+            ReceiptErrorCode::AdminActionNotApproved
+        );
+        self.approved_admin_action = [0; 32];
+        Ok(())
+    }
+
+    /// Take the auditor queued by a `ConfidentialTransferAuditor` change.
+    pub fn take_pending_auditor(&mut self) -> Result<Option<[u8; 32]>> {
+        require!(self.auditor_update_pending, ReceiptErrorCode::NoPendingAuditorUpdate);
+        self.auditor_update_pending = false;
+        let auditor = std::mem::take(&mut self.pending_auditor_elgamal_pubkey);
+        Ok((auditor != [0; 32]).then_some(auditor))
+    }
+
+    /// Count underlying moving in or out of the vault against the flow limits.
+    /// A breach pauses the market instead of failing, so the pause persists,
+    /// and returns false for the caller to skip the flow.
+    pub fn record_flow(
+        &mut self,
+        receipt_state: Pubkey,
+        vault_amount: u64,
+        inflow: u64,
+        outflow: u64,
+    ) -> Result<bool> {
+        require!(!self.shutdown, ReceiptErrorCode::MarketShutdown);
+        require!(!self.paused, ReceiptErrorCode::MarketPaused);
+        let tvl = self.total_underlying(vault_amount)?;
+        let slot = Clock::get()?.slot;
+        if self.flow_limits.record(slot, tvl, inflow, outflow)? {
+            return Ok(true);
+        }
+        self.paused = true;
+        emit!(FlowLimitTripped {
+            receipt_state,
+            inflow,
+            outflow,
+            window_start_tvl: self.flow_limits.window_start_tvl,
+            slot,
+        });
+        Ok(false)
+    }
+
+    /// Underlying that can flow in and out right now, nothing while the
+    /// market is shut down or paused.
+    pub fn flow_headroom(&self, vault_amount: u64) -> Result<(u64, u64)> {
+        if self.shutdown || self.paused {
+            return Ok((0, 0));
+        }
+        self.flow_limits
+            .headroom(Clock::get()?.slot, self.total_underlying(vault_amount)?)
+    }
+
+    /// Upgrade a pre-versioning account, leaving every new field at its default.
+    pub fn from_v0(v0: ReceiptStateV0) -> Self {
+        Self {
+            authority: v0.authority,
+            token_mint: v0.token_mint,
+            token_mint_vault: v0.token_mint_vault,
+            crypto_receipt_mint: v0.crypto_receipt_mint,
+            crypto_receipt_vault: v0.crypto_receipt_vault,
+            bump: v0.bump,
+            vault_authority_bump: v0.vault_authority_bump,
+            token_mint_vault_bump: v0.token_mint_vault_bump,
+            receipt_mint_bump: v0.receipt_mint_bump,
+            receipt_mint_vault_bump: v0.receipt_mint_vault_bump,
+            version: Self::CURRENT_VERSION,
+            oracle: OracleConfig::default(),
+            flash_loan_enabled: false,
+            flash_loan_fee_bps: 0,
+            flash_loan_outstanding: 0,
+            swap_program: Pubkey::default(),
+            deposit_fee_bps: 0,
+            referral_fee_share_bps: 0,
+            fee_receiver: Pubkey::default(),
+            interest_last_exchange_rate: 0,
+            interest_last_update_ts: 0,
+            guardian: Pubkey::default(),
+            timelock_delay: 0,
+            proposal_count: 0,
+            paused: false,
+            flow_limits: FlowLimits::default(),
+            total_staked: 0,
+            reward_pool_count: 0,
+            distribution_count: 0,
+            core_bridge_program: Pubkey::default(),
+            bridge_escrow: false,
+            bridged_out_supply: 0,
+            stake_pool: Pubkey::default(),
+            stake_pool_vault: Pubkey::default(),
+            stake_pool_value: 0,
+            insurance_vault: Pubkey::default(),
+            insurance_fee_share_bps: 0,
+            insurance_losses_covered: 0,
+            shutdown: false,
+            shutdown_underlying: 0,
+            shutdown_stake_pool_tokens: 0,
+            shutdown_receipt_supply: 0,
+            pending_auditor_elgamal_pubkey: [0; 32],
+            auditor_update_pending: false,
+            open_reserve_count: 0,
+            stake_pool_value_epoch: 0,
+            foreign_emitter_count: 0,
+            open_distribution_count: 0,
+            approved_admin_action: [0; 32],
+            reserved: [0; Self::RESERVED_LEN],
+        }
+    }
 
     pub fn find_mint_vault_authority(receipt_state: &Pubkey, token_mint: &Pubkey) -> (Pubkey, u8) {
         Pubkey::find_program_address(
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+/// Deposits attributed to a referrer in one receipt market.
+#[account]
+pub struct ReferralStats {
+    pub receipt_state: Pubkey,
+    pub referrer: Pubkey,
+    /// Underlying deposited through this referrer.
+    pub total_volume: u64,
+    pub deposit_count: u64,
+    /// Deposit fees paid out to the referrer.
+    pub total_fees_earned: u64,
+    pub bump: u8,
+}
+
+impl ReferralStats {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // receipt_state
+        32 + // referrer
+        8 + // total_volume
+        8 + // deposit_count
+        8 + // total_fees_earned
+        1; // bump
+
+    pub const REFERRAL_SEED: &'static str = "referral_stats";
+}
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use crate::{errors::ReceiptErrorCode, utils::EXCHANGE_RATE_SCALE};
+
+/// Emits one reward mint to receipts staked in a market. Rewards accrue
+/// through `acc_reward_per_share`, the rewards earned by one staked receipt
+/// since the pool was created, scaled by `RewardPool::ACC_SCALE`.
+#[account]
+pub struct RewardPool {
+    pub receipt_state: Pubkey,
+    pub reward_mint: Pubkey,
+    pub reward_vault: Pubkey,
+    /// Position of this pool in `StakePosition` reward arrays.
+    pub index: u8,
+    pub acc_reward_per_share: u128,
+    pub emission_per_second: u64,
+    pub emission_end_ts: i64,
+    pub last_update_ts: i64,
+    /// Emitted while nothing was staked, or left over when a schedule does
+    /// not divide evenly, owed to nobody. Rolled into the next `fund_rewards`
+    /// schedule or swept by the authority.
+    pub unallocated_rewards: u64,
+    /// Rewards settled into stake positions and not claimed yet.
+    pub unclaimed_rewards: u64,
+    pub bump: u8,
+}
+
+impl RewardPool {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // receipt_state
+        32 + // reward_mint
+        32 + // reward_vault
+        1 + // index
+        16 + // acc_reward_per_share
+        8 + // emission_per_second
+        8 + // emission_end_ts
+        8 + // last_update_ts
+        8 + // unallocated_rewards
+        8 + // unclaimed_rewards
+        1; // bump
+
+    pub const REWARD_POOL_SEED: &'static str = "reward_pool";
+    pub const REWARD_VAULT_SEED: &'static str = "reward_vault";
+    pub const STAKE_VAULT_SEED: &'static str = "stake_vault";
+    pub const MAX_REWARD_POOLS: usize = 4;
+    pub const ACC_SCALE: u128 = EXCHANGE_RATE_SCALE;
+
+    /// Accrue emissions up to `now` over `total_staked` receipts. Emissions
+    /// while nothing is staked go to `unallocated_rewards`.
+    pub fn update(&mut self, now: i64, total_staked: u64) -> Result<()> {
+        let accrue_until = now.min(self.emission_end_ts);
+        if accrue_until > self.last_update_ts {
+            let emitted = (self.emission_per_second as u128)
+                .checked_mul((accrue_until - self.last_update_ts) as u128)
+                .ok_or(ReceiptErrorCode::MathOverflow)?;
+            if total_staked > 0 {
+                self.acc_reward_per_share = emitted
+                    .checked_mul(Self::ACC_SCALE)
+                    .map(|scaled| scaled / total_staked as u128)
+                    .and_then(|increase| self.acc_reward_per_share.checked_add(increase))
+                    .ok_or(ReceiptErrorCode::MathOverflow)?;
+            } else {
+                self.unallocated_rewards = u64::try_from(emitted)
+                    .ok()
+                    .and_then(|emitted| self.unallocated_rewards.checked_add(emitted))
+                    .ok_or(ReceiptErrorCode::MathOverflow)?;
+            }
+        }
+        self.last_update_ts = self.last_update_ts.max(now);
+        Ok(())
+    }
+
+    /// Rewards scheduled after `now` that have not been emitted yet.
+    pub fn remaining_emissions(&self, now: i64) -> Result<u64> {
+        if now >= self.emission_end_ts {
+            return Ok(0);
+        }
+        self.emission_per_second
+            .checked_mul((self.emission_end_ts - now) as u64)
+            .ok_or(error!(ReceiptErrorCode::MathOverflow))
+    }
+}
+
+/// Receipts an owner staked in a market and their unclaimed rewards.
+#[account]
+pub struct StakePosition {
+    pub receipt_state: Pubkey,
+    pub owner: Pubkey,
+    pub staked: u64,
+    /// `staked * acc_reward_per_share` of each pool at the last settlement.
+    pub reward_debt: [u128; RewardPool::MAX_REWARD_POOLS],
+    /// Settled rewards not claimed yet.
+    pub accrued: [u64; RewardPool::MAX_REWARD_POOLS],
+    pub bump: u8,
+}
+
+impl StakePosition {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // receipt_state
+        32 + // owner
+        8 + // staked
+        16 * RewardPool::MAX_REWARD_POOLS + // reward_debt
+        8 * RewardPool::MAX_REWARD_POOLS + // accrued
+        1; // bump
+
+    pub const STAKE_POSITION_SEED: &'static str = "stake_position";
+
+    /// Move rewards earned on the current stake into `accrued`. The pool
+    /// must be updated first.
+    pub fn settle(&mut self, pool: &mut RewardPool) -> Result<()> {
+        let index = pool.index as usize;
+        let earned = (self.staked as u128)
+            .checked_mul(pool.acc_reward_per_share)
+            .ok_or(ReceiptErrorCode::MathOverflow)?
+            - self.reward_debt[index];
+        let earned = u64::try_from(earned / RewardPool::ACC_SCALE)
+            .map_err(|_| error!(ReceiptErrorCode::MathOverflow))?;
+        self.accrued[index] = self.accrued[index]
+            .checked_add(earned)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        pool.unclaimed_rewards = pool
+            .unclaimed_rewards
+            .checked_add(earned)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        self.reward_debt[index] = self.staked as u128 * pool.acc_reward_per_share;
+        Ok(())
+    }
+
+    /// Reset the debt of every pool after `staked` changed.
+    pub fn checkpoint(&mut self, pool: &RewardPool) {
+        self.reward_debt[pool.index as usize] = self.staked as u128 * pool.acc_reward_per_share;
+    }
+}
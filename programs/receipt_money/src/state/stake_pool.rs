@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use crate::{errors::ReceiptErrorCode, utils::mul_div_floor};
+
+/// SPL stake pool program.
+pub const STAKE_POOL_PROGRAM_ID: Pubkey = pubkey!("SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy");
+
+/// The fields of an SPL stake pool account that value its pool token, read
+/// from the account data without depending on the stake pool crate.
+pub struct StakePoolState {
+    pub pool_mint: Pubkey,
+    /// Lamports under management, as of `last_update_epoch`.
+    pub total_lamports: u64,
+    pub pool_token_supply: u64,
+    pub last_update_epoch: u64,
+}
+
+impl StakePoolState {
+    pub const STAKE_POOL_VAULT_SEED: &'static str = "stake_pool_vault";
+
+    /// `AccountType::StakePool`.
+    const ACCOUNT_TYPE: u8 = 1;
+    /// account_type, manager, staker, stake_deposit_authority,
+    /// stake_withdraw_bump_seed, validator_list, reserve_stake
+    const POOL_MINT_OFFSET: usize = 1 + 32 * 3 + 1 + 32 * 2;
+    /// pool_mint, manager_fee_account, token_program_id
+    const TOTAL_LAMPORTS_OFFSET: usize = Self::POOL_MINT_OFFSET + 32 * 3;
+    const MIN_LEN: usize = Self::TOTAL_LAMPORTS_OFFSET + 8 * 3;
+
+    pub fn load(account: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(*account.owner, STAKE_POOL_PROGRAM_ID, ReceiptErrorCode::InvalidStakePool);
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() >= Self::MIN_LEN && data[0] == Self::ACCOUNT_TYPE,
+            ReceiptErrorCode::InvalidStakePool
+        );
+        let read_u64 = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        Ok(Self {
+            pool_mint: Pubkey::try_from(&data[Self::POOL_MINT_OFFSET..Self::POOL_MINT_OFFSET + 32]).unwrap(),
+            total_lamports: read_u64(Self::TOTAL_LAMPORTS_OFFSET),
+            pool_token_supply: read_u64(Self::TOTAL_LAMPORTS_OFFSET + 8),
+            last_update_epoch: read_u64(Self::TOTAL_LAMPORTS_OFFSET + 16),
+        })
+    }
+
+    /// Load the pool and require it to be updated for the current epoch, as
+    /// the stake pool program does before accepting deposits.
+    pub fn load_current(account: &AccountInfo) -> Result<Self> {
+        let pool = Self::load(account)?;
+        require!(
+            pool.last_update_epoch >= Clock::get()?.epoch,
+            ReceiptErrorCode::StaleStakePool
+        );
+        Ok(pool)
+    }
+
+    /// Lamports `pool_tokens` redeem for, rounded down. An empty pool is 1:1.
+    pub fn lamports_for(&self, pool_tokens: u64) -> Result<u64> {
+        if self.pool_token_supply == 0 {
+            return Ok(pool_tokens);
+        }
+        mul_div_floor(pool_tokens, self.total_lamports, self.pool_token_supply)
+    }
+
+    /// Pool tokens worth `lamports`, rounded down. An empty pool is 1:1.
+    pub fn pool_tokens_for(&self, lamports: u64) -> Result<u64> {
+        if self.pool_token_supply == 0 {
+            return Ok(lamports);
+        }
+        mul_div_floor(lamports, self.pool_token_supply, self.total_lamports)
+    }
+}
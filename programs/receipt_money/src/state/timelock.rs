@@ -0,0 +1,259 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+use crate::{errors::ReceiptErrorCode, utils::BASIS_POINTS_DIVISOR};
+use super::{BridgeClaim, OracleConfig};
+
+/// An admin change to a market. Applied immediately by the `set_*`
+/// instructions while the market has no timelock, and only through an
+/// executed `Proposal` once it does.
+///
+/// Authority instructions that cannot hurt existing depositors stay immediate
+/// under a timelock: `close_market`, `close_lending_reserve` and
+/// `close_foreign_emitter` (empty ones only), `add_reward_pool`,
+/// `fund_rewards`, `sweep_unallocated_rewards` and `init_insurance_vault`.
+/// Those listed in `AdminAction` wait for an `AdminAction` proposal.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub enum MarketParamChange {
+    OracleConfig(OracleConfig),
+    FlashLoanConfig {
+        enabled: bool,
+        fee_bps: u16,
+    },
+    SwapProgram(Pubkey),
+    FeeConfig {
+        deposit_fee_bps: u16,
+        referral_fee_share_bps: u16,
+        fee_receiver: Pubkey,
+    },
+    Authority(Pubkey),
+    /// A zero `window_slots` turns the limits off.
+    FlowLimits {
+        window_slots: u64,
+        max_inflow_bps: u16,
+        max_outflow_bps: u16,
+    },
+    /// A zero `delay` turns the timelock off.
+    Timelock {
+        guardian: Pubkey,
+        delay: i64,
+    },
+    /// `escrow` holds bridged receipts in the bridge escrow instead of burning them.
+    BridgeConfig {
+        core_bridge_program: Pubkey,
+        escrow: bool,
+    },
+    /// Share of the protocol fee paid to the insurance vault, in basis points of the fee.
+    InsuranceFeeShare(u16),
+    /// Auditor ElGamal key of a confidential receipt mint, `None` removes it.
+    /// The key lives on the mint, `apply_confidential_transfer_auditor` moves it there.
+    ConfidentialTransferAuditor(Option<[u8; 32]>),
+    /// Registers or replaces the market's emitter on `chain`. The emitter
+    /// lives in its own account, `execute_proposal` writes it there.
+    ForeignEmitter {
+        chain: u16,
+        address: [u8; 32],
+    },
+    /// SPL stake pool whose token a SOL market accepts, `set_stake_pool`
+    /// opens its vault once the change is applied.
+    StakePool(Pubkey),
+    /// Approves one `AdminAction`, which the authority then takes with its
+    /// own instruction. Executing another approval replaces it.
+    AdminAction(AdminAction),
+}
+
+impl MarketParamChange {
+    pub const LEN: usize = 1 + // variant
+        AdminAction::LEN; // largest variant
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::FlashLoanConfig { fee_bps, .. } => require!(
+                *fee_bps as u64 <= BASIS_POINTS_DIVISOR,
+                ReceiptErrorCode::InvalidBasisPoints
+            ),
+            Self::FeeConfig { deposit_fee_bps, referral_fee_share_bps, .. } => require!(
+                *deposit_fee_bps as u64 <= BASIS_POINTS_DIVISOR
+                    && *referral_fee_share_bps as u64 <= BASIS_POINTS_DIVISOR,
+                ReceiptErrorCode::InvalidBasisPoints
+            ),
+            Self::FlowLimits { max_inflow_bps, max_outflow_bps, .. } => require!(
+                *max_inflow_bps as u64 <= BASIS_POINTS_DIVISOR
+                    && *max_outflow_bps as u64 <= BASIS_POINTS_DIVISOR,
+                ReceiptErrorCode::InvalidBasisPoints
+            ),
+            Self::InsuranceFeeShare(share_bps) => require!(
+                *share_bps as u64 <= BASIS_POINTS_DIVISOR,
+                ReceiptErrorCode::InvalidBasisPoints
+            ),
+            Self::Authority(authority) => {
+                require_keys_neq!(*authority, Pubkey::default(), ReceiptErrorCode::InvalidInput)
+            }
+            Self::StakePool(stake_pool) => {
+                require_keys_neq!(*stake_pool, Pubkey::default(), ReceiptErrorCode::InvalidInput)
+            }
+            Self::Timelock { delay, .. } => require!(
+                (0..=Proposal::MAX_DELAY).contains(delay),
+                ReceiptErrorCode::InvalidTimelockDelay
+            ),
+            Self::ForeignEmitter { chain, address } => require!(
+                *chain != BridgeClaim::CHAIN_ID && *address != [0; 32],
+                ReceiptErrorCode::InvalidInput
+            ),
+            Self::OracleConfig(_)
+            | Self::SwapProgram(_)
+            | Self::BridgeConfig { .. }
+            | Self::ConfidentialTransferAuditor(_)
+            | Self::AdminAction(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Chain of the `ForeignEmitter` account written by this change, if any.
+    pub fn foreign_emitter_chain(&self) -> Option<u16> {
+        match self {
+            Self::ForeignEmitter { chain, .. } => Some(*chain),
+            _ => None,
+        }
+    }
+}
+
+/// Authority instruction that moves funds or opens accounts holding them.
+/// Immediate while the market has no timelock, and only with the exact
+/// arguments an executed `AdminAction` proposal approved once it does.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub enum AdminAction {
+    CoverLoss {
+        amount: u64,
+    },
+    InitLendingReserve {
+        borrow_mint: Pubkey,
+        ltv_bps: u16,
+        liquidation_threshold_bps: u16,
+        liquidation_bonus_bps: u16,
+        base_rate_bps: u16,
+        rate_slope_bps: u16,
+    },
+    CreateDistribution {
+        mint: Pubkey,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        num_nodes: u64,
+        expiry_ts: i64,
+    },
+    Clawback {
+        distribution_id: u64,
+        receiver_token_account: Pubkey,
+    },
+    WithdrawReserveLiquidity {
+        borrow_mint: Pubkey,
+        amount: u64,
+        receiver_token_account: Pubkey,
+    },
+}
+
+impl AdminAction {
+    pub const LEN: usize = 1 + // variant
+        32 + // mint
+        32 + // merkle_root
+        8 + // total_amount
+        8 + // num_nodes
+        8; // expiry_ts, `CreateDistribution` is the largest variant
+
+    /// What `ReceiptState` keeps of an approved action.
+    pub fn hash(&self) -> [u8; 32] {
+        hashv(&[&self.try_to_vec().unwrap()]).to_bytes()
+    }
+}
+
+/// A queued `MarketParamChange`, executable by anyone between `eta` and
+/// `eta + GRACE_PERIOD` unless the guardian or authority cancels it first.
+#[account]
+pub struct Proposal {
+    pub receipt_state: Pubkey,
+    /// Receives the rent back when the proposal is executed or cancelled.
+    pub proposer: Pubkey,
+    pub id: u64,
+    pub change: MarketParamChange,
+    pub eta: i64,
+    pub bump: u8,
+}
+
+impl Proposal {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // receipt_state
+        32 + // proposer
+        8 + // id
+        MarketParamChange::LEN + // change
+        8 + // eta
+        1; // bump
+
+    pub const PROPOSAL_SEED: &'static str = "proposal";
+    pub const GRACE_PERIOD: i64 = 14 * 24 * 60 * 60;
+    pub const MAX_DELAY: i64 = 30 * 24 * 60 * 60;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn len_fits_every_variant() {
+        let key = Pubkey::new_unique();
+        let changes = [
+            MarketParamChange::OracleConfig(OracleConfig {
+                oracle: key,
+                max_staleness: u64::MAX,
+                max_confidence_bps: u16::MAX,
+            }),
+            MarketParamChange::FlashLoanConfig { enabled: true, fee_bps: u16::MAX },
+            MarketParamChange::SwapProgram(key),
+            MarketParamChange::FeeConfig {
+                deposit_fee_bps: u16::MAX,
+                referral_fee_share_bps: u16::MAX,
+                fee_receiver: key,
+            },
+            MarketParamChange::Authority(key),
+            MarketParamChange::FlowLimits {
+                window_slots: u64::MAX,
+                max_inflow_bps: u16::MAX,
+                max_outflow_bps: u16::MAX,
+            },
+            MarketParamChange::Timelock { guardian: key, delay: i64::MAX },
+            MarketParamChange::BridgeConfig { core_bridge_program: key, escrow: true },
+            MarketParamChange::InsuranceFeeShare(u16::MAX),
+            MarketParamChange::ConfidentialTransferAuditor(Some([1; 32])),
+            MarketParamChange::ForeignEmitter { chain: u16::MAX, address: [1; 32] },
+            MarketParamChange::StakePool(key),
+            MarketParamChange::AdminAction(AdminAction::CoverLoss { amount: u64::MAX }),
+            MarketParamChange::AdminAction(AdminAction::InitLendingReserve {
+                borrow_mint: key,
+                ltv_bps: u16::MAX,
+                liquidation_threshold_bps: u16::MAX,
+                liquidation_bonus_bps: u16::MAX,
+                base_rate_bps: u16::MAX,
+                rate_slope_bps: u16::MAX,
+            }),
+            MarketParamChange::AdminAction(AdminAction::CreateDistribution {
+                mint: key,
+                merkle_root: [1; 32],
+                total_amount: u64::MAX,
+                num_nodes: u64::MAX,
+                expiry_ts: i64::MAX,
+            }),
+            MarketParamChange::AdminAction(AdminAction::Clawback {
+                distribution_id: u64::MAX,
+                receiver_token_account: key,
+            }),
+            MarketParamChange::AdminAction(AdminAction::WithdrawReserveLiquidity {
+                borrow_mint: key,
+                amount: u64::MAX,
+                receiver_token_account: key,
+            }),
+        ];
+        let sizes: Vec<usize> = changes
+            .iter()
+            .map(|change| change.try_to_vec().unwrap().len())
+            .collect();
+        assert!(sizes.iter().all(|size| *size <= MarketParamChange::LEN));
+        assert_eq!(sizes.into_iter().max(), Some(MarketParamChange::LEN));
+    }
+}
@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+use crate::{
+    errors::ReceiptErrorCode,
+    utils::{mul_div_floor, EXCHANGE_RATE_SCALE},
+};
+
+/// Cost basis of one owner's receipts in a market. `deposit` and `redeem`
+/// always pass the owner's position address and update it once opened.
+/// Transfers update it through receipt_hook on markets whose receipt mint
+/// has the hook, receipts moved by plain transfers on other markets are not
+/// tracked.
+#[account]
+pub struct UserPosition {
+    pub receipt_state: Pubkey,
+    pub owner: Pubkey,
+    /// Underlying paid into deposits, before fees.
+    pub total_deposited: u64,
+    /// Underlying received from redemptions.
+    pub total_redeemed: u64,
+    /// Receipts currently attributed to this position.
+    pub receipts: u64,
+    /// Underlying paid for `receipts`.
+    pub cost_basis: u64,
+    /// Weighted average underlying paid per receipt, scaled by `EXCHANGE_RATE_SCALE`.
+    pub entry_exchange_rate: u128,
+    /// Underlying received from redemptions minus the cost basis redeemed.
+    pub realized_yield: i64,
+    pub bump: u8,
+}
+
+impl UserPosition {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // receipt_state
+        32 + // owner
+        8 + // total_deposited
+        8 + // total_redeemed
+        8 + // receipts
+        8 + // cost_basis
+        16 + // entry_exchange_rate
+        8 + // realized_yield
+        1; // bump
+
+    pub const USER_POSITION_SEED: &'static str = "user_position";
+
+    /// Apply `update` to the position at `position_info` if its owner opened
+    /// one. The caller checks the address against the position seeds.
+    pub fn update_if_open(
+        position_info: &AccountInfo,
+        update: impl FnOnce(&mut Self) -> Result<()>,
+    ) -> Result<()> {
+        if position_info.data_is_empty() {
+            return Ok(());
+        }
+        require_keys_eq!(*position_info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let mut position = Self::try_deserialize(&mut &position_info.try_borrow_data()?[..])?;
+        update(&mut position)?;
+        position.try_serialize(&mut &mut position_info.try_borrow_mut_data()?[..])
+    }
+
+    pub fn record_deposit(&mut self, amount: u64, receipts: u64) -> Result<()> {
+        self.total_deposited = self
+            .total_deposited
+            .checked_add(amount)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        self.receipts = self
+            .receipts
+            .checked_add(receipts)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        self.cost_basis = self
+            .cost_basis
+            .checked_add(amount)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        self.update_entry_exchange_rate()
+    }
+
+    /// Burning more receipts than the position tracks only releases the tracked share.
+    pub fn record_redeem(&mut self, receipts: u64, underlying: u64) -> Result<()> {
+        self.total_redeemed = self
+            .total_redeemed
+            .checked_add(underlying)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        let tracked = receipts.min(self.receipts);
+        if tracked == 0 {
+            return Ok(());
+        }
+        let basis_released = mul_div_floor(self.cost_basis, tracked, self.receipts)?;
+        let tracked_underlying = mul_div_floor(underlying, tracked, receipts)?;
+        let realized = (tracked_underlying as i128) - (basis_released as i128);
+        self.realized_yield = i64::try_from(self.realized_yield as i128 + realized)
+            .map_err(|_| error!(ReceiptErrorCode::MathOverflow))?;
+        self.cost_basis -= basis_released;
+        self.receipts -= tracked;
+        self.update_entry_exchange_rate()
+    }
+
+    /// Releases the cost basis of up to `receipts` tracked receipts, returning
+    /// how many were tracked and their basis.
+    pub fn record_transfer_out(&mut self, receipts: u64) -> Result<(u64, u64)> {
+        let tracked = receipts.min(self.receipts);
+        if tracked == 0 {
+            return Ok((0, 0));
+        }
+        let basis_released = mul_div_floor(self.cost_basis, tracked, self.receipts)?;
+        self.cost_basis -= basis_released;
+        self.receipts -= tracked;
+        self.update_entry_exchange_rate()?;
+        Ok((tracked, basis_released))
+    }
+
+    pub fn record_transfer_in(&mut self, receipts: u64, cost_basis: u64) -> Result<()> {
+        self.receipts = self
+            .receipts
+            .checked_add(receipts)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        self.cost_basis = self
+            .cost_basis
+            .checked_add(cost_basis)
+            .ok_or(ReceiptErrorCode::MathOverflow)?;
+        self.update_entry_exchange_rate()
+    }
+
+    fn update_entry_exchange_rate(&mut self) -> Result<()> {
+        self.entry_exchange_rate = if self.receipts == 0 {
+            0
+        } else {
+            (self.cost_basis as u128)
+                .checked_mul(EXCHANGE_RATE_SCALE)
+                .ok_or(ReceiptErrorCode::MathOverflow)?
+                / self.receipts as u128
+        };
+        Ok(())
+    }
+}
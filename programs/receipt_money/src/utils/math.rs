@@ -0,0 +1,96 @@
+use crate::errors::ReceiptErrorCode;
+use anchor_lang::prelude::*;
+
+pub const BASIS_POINTS_DIVISOR: u64 = 10_000;
+/// Fixed point scale of exchange rates, underlying per receipt.
+pub const EXCHANGE_RATE_SCALE: u128 = 1_000_000_000_000;
+pub const SECONDS_PER_YEAR: u64 = 31_556_736;
+
+/// Virtual receipts and underlying added to both sides of every conversion.
+/// An empty market still prices 1:1, but the virtual position takes its share
+/// of anything donated to the vault, so inflating the rate ahead of the first
+/// real deposit costs the donor more than it takes from later depositors.
+pub const VIRTUAL_RECEIPTS: u64 = 1;
+pub const VIRTUAL_UNDERLYING: u64 = 1;
+
+/// Receipts minted for `amount` of underlying at the current exchange rate.
+pub fn underlying_to_receipts(
+    amount: u64,
+    total_underlying: u64,
+    receipt_supply: u64,
+) -> Result<u64> {
+    mul_div_floor_u128(
+        amount,
+        receipt_supply as u128 + VIRTUAL_RECEIPTS as u128,
+        total_underlying as u128 + VIRTUAL_UNDERLYING as u128,
+    )
+}
+
+/// Underlying owed for `receipts` at the current exchange rate.
+pub fn receipts_to_underlying(
+    receipts: u64,
+    total_underlying: u64,
+    receipt_supply: u64,
+) -> Result<u64> {
+    mul_div_floor_u128(
+        receipts,
+        total_underlying as u128 + VIRTUAL_UNDERLYING as u128,
+        receipt_supply as u128 + VIRTUAL_RECEIPTS as u128,
+    )
+}
+
+fn mul_div_floor_u128(value: u64, numerator: u128, denominator: u128) -> Result<u64> {
+    let result = (value as u128)
+        .checked_mul(numerator)
+        .ok_or(ReceiptErrorCode::MathOverflow)?
+        .checked_div(denominator)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    u64::try_from(result).map_err(|_| error!(ReceiptErrorCode::MathOverflow))
+}
+
+/// Calculate `value * numerator / denominator` rounding down.
+pub fn mul_div_floor(value: u64, numerator: u64, denominator: u64) -> Result<u64> {
+    mul_div_floor_u128(value, numerator as u128, denominator as u128)
+}
+
+/// Calculate `value * numerator / denominator` rounding up.
+pub fn mul_div_ceil(value: u64, numerator: u64, denominator: u64) -> Result<u64> {
+    if denominator == 0 {
+        return err!(ReceiptErrorCode::MathOverflow);
+    }
+    let result = (value as u128)
+        .checked_mul(numerator as u128)
+        .ok_or(ReceiptErrorCode::MathOverflow)?
+        .div_ceil(denominator as u128);
+    u64::try_from(result).map_err(|_| error!(ReceiptErrorCode::MathOverflow))
+}
+
+/// Fee of `fee_bps` on `amount`, rounded up in favour of the vault.
+pub fn fee_amount(amount: u64, fee_bps: u16) -> Result<u64> {
+    mul_div_ceil(amount, fee_bps as u64, BASIS_POINTS_DIVISOR)
+}
+
+/// Underlying per receipt scaled by `EXCHANGE_RATE_SCALE`, with the same
+/// virtual offset as the conversions deposits and redemptions use.
+/// An empty market is 1:1.
+pub fn exchange_rate(total_underlying: u64, receipt_supply: u64) -> Result<u128> {
+    Ok((total_underlying as u128 + VIRTUAL_UNDERLYING as u128)
+        .checked_mul(EXCHANGE_RATE_SCALE)
+        .ok_or(ReceiptErrorCode::MathOverflow)?
+        / (receipt_supply as u128 + VIRTUAL_RECEIPTS as u128))
+}
+
+/// Simple annual rate in basis points that grows `previous_rate` into
+/// `current_rate` over `elapsed` seconds, clamped to the `i16` range.
+pub fn annualized_rate_bps(previous_rate: u128, current_rate: u128, elapsed: u64) -> Result<i16> {
+    if previous_rate == 0 || elapsed == 0 {
+        return err!(ReceiptErrorCode::MathOverflow);
+    }
+    let change = current_rate as i128 - previous_rate as i128;
+    // Anything too large to multiply is far outside the i16 range anyway
+    let rate = match change.checked_mul(BASIS_POINTS_DIVISOR as i128 * SECONDS_PER_YEAR as i128) {
+        Some(scaled) => scaled / (previous_rate as i128 * elapsed as i128),
+        None => change.signum() * i128::MAX,
+    };
+    Ok(rate.clamp(i16::MIN as i128, i16::MAX as i128) as i16)
+}
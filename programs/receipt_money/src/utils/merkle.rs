@@ -0,0 +1,27 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+/// Domain separation so a leaf can never be passed off as an inner node.
+pub const MERKLE_LEAF_PREFIX: u8 = 0;
+pub const MERKLE_NODE_PREFIX: u8 = 1;
+
+/// Leaf of a distribution tree, paying `amount` to `claimant` at `index`.
+pub fn merkle_leaf(index: u64, claimant: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[
+        &[MERKLE_LEAF_PREFIX],
+        &index.to_le_bytes(),
+        claimant.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Parent of two nodes. Pairs are hashed in sorted order, so proofs carry no
+/// left/right flags.
+pub fn merkle_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[MERKLE_NODE_PREFIX], first, second]).to_bytes()
+}
+
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    proof.iter().fold(leaf, |node, sibling| merkle_node(&node, sibling)) == *root
+}
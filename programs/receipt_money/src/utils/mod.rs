@@ -1,3 +1,11 @@
+pub mod math;
+pub mod merkle;
+pub mod oracle;
+pub mod quote;
 pub mod token;
 
-pub use token::*;
\ No newline at end of file
+pub use math::*;
+pub use merkle::*;
+pub use oracle::*;
+pub use quote::*;
+pub use token::*;
@@ -0,0 +1,91 @@
+use crate::{errors::ReceiptErrorCode, state::OracleConfig, utils::BASIS_POINTS_DIVISOR};
+use anchor_lang::prelude::*;
+
+/// Magic number at the start of every Pyth v2 account.
+pub const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+/// Pyth account type of a price account.
+pub const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+/// Pyth aggregate status of a price that is currently trading.
+pub const PYTH_STATUS_TRADING: u32 = 1;
+/// Seconds a publish time may run ahead of the cluster clock.
+pub const MAX_CLOCK_SKEW: i64 = 10;
+
+// Byte offsets inside a Pyth v2 price account.
+const MAGIC_OFFSET: usize = 0;
+const ATYPE_OFFSET: usize = 8;
+const EXPO_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const AGG_PRICE_OFFSET: usize = 208;
+const AGG_CONF_OFFSET: usize = 216;
+const AGG_STATUS_OFFSET: usize = 224;
+/// Bytes needed to read everything up to and including the aggregate price.
+pub const PYTH_PRICE_MIN_LEN: usize = 240;
+
+/// An aggregate price read from an oracle account.
+#[derive(Clone, Copy, Debug)]
+pub struct OraclePrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+/// Read the aggregate price from a Pyth v2 price account.
+/// Only the Pyth v2 layout is understood, Switchboard feeds are not supported.
+pub fn read_pyth_price(oracle_info: &AccountInfo) -> Result<OraclePrice> {
+    let data = oracle_info.try_borrow_data()?;
+    if data.len() < PYTH_PRICE_MIN_LEN
+        || read_u32(&data, MAGIC_OFFSET) != PYTH_MAGIC
+        || read_u32(&data, ATYPE_OFFSET) != PYTH_PRICE_ACCOUNT_TYPE
+    {
+        return err!(ReceiptErrorCode::InvalidOracle);
+    }
+    if read_u32(&data, AGG_STATUS_OFFSET) != PYTH_STATUS_TRADING {
+        return err!(ReceiptErrorCode::InvalidOracle);
+    }
+    Ok(OraclePrice {
+        price: read_u64(&data, AGG_PRICE_OFFSET) as i64,
+        conf: read_u64(&data, AGG_CONF_OFFSET),
+        expo: read_u32(&data, EXPO_OFFSET) as i32,
+        publish_time: read_u64(&data, TIMESTAMP_OFFSET) as i64,
+    })
+}
+
+/// Read the configured oracle and reject stale or low confidence prices.
+pub fn get_checked_price(
+    config: &OracleConfig,
+    oracle_info: &AccountInfo,
+    now: i64,
+) -> Result<OraclePrice> {
+    if !config.is_configured() {
+        return err!(ReceiptErrorCode::OracleNotConfigured);
+    }
+    if *oracle_info.key != config.oracle {
+        return err!(ReceiptErrorCode::InvalidOracle);
+    }
+    let price = read_pyth_price(oracle_info)?;
+    if price.price <= 0 {
+        return err!(ReceiptErrorCode::InvalidOracle);
+    }
+    let age = now.saturating_sub(price.publish_time);
+    // Validator clocks drift, a publish time slightly ahead of ours is fresh
+    if age < -MAX_CLOCK_SKEW {
+        return err!(ReceiptErrorCode::OracleTimestampInFuture);
+    }
+    if age > 0 && age as u64 > config.max_staleness {
+        return err!(ReceiptErrorCode::StaleOracle);
+    }
+    let conf_bps = (price.conf as u128) * (BASIS_POINTS_DIVISOR as u128) / (price.price as u128);
+    if conf_bps > config.max_confidence_bps as u128 {
+        return err!(ReceiptErrorCode::OracleConfidenceTooWide);
+    }
+    Ok(price)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
@@ -0,0 +1,76 @@
+use crate::{
+    errors::ReceiptErrorCode,
+    state::ReceiptState,
+    utils::{fee_amount, get_transfer_fee, receipts_to_underlying, underlying_to_receipts},
+};
+use anchor_lang::prelude::*;
+
+/// Result of pricing a deposit.
+#[derive(Clone, Copy, Debug)]
+pub struct DepositQuote {
+    /// Protocol fee, including the referrer's share.
+    pub fee: u64,
+    /// Underlying sent to `token_mint_vault`.
+    pub to_vault: u64,
+    /// Receipts minted.
+    pub receipts: u64,
+}
+
+/// Result of pricing a redemption.
+#[derive(Clone, Copy, Debug)]
+pub struct RedeemQuote {
+    /// Underlying leaving `token_mint_vault`.
+    pub underlying: u64,
+    /// Underlying arriving in the user's account after Token-2022 transfer fees.
+    pub received: u64,
+}
+
+/// Price a deposit of `amount` underlying. The protocol fee is split off first
+/// and Token-2022 transfer fees are taken before the rest reaches the vault,
+/// so only the net amount buys receipts.
+pub fn quote_deposit(
+    receipt_state: &ReceiptState,
+    token_mint: &AccountInfo,
+    vault_amount: u64,
+    receipt_supply: u64,
+    amount: u64,
+) -> Result<DepositQuote> {
+    let fee = fee_amount(amount, receipt_state.deposit_fee_bps)?;
+    let to_vault = amount
+        .checked_sub(fee)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    let transfer_fee = get_transfer_fee(token_mint, to_vault)?;
+    let net_amount = to_vault
+        .checked_sub(transfer_fee)
+        .ok_or(ReceiptErrorCode::MathOverflow)?;
+    let receipts = underlying_to_receipts(
+        net_amount,
+        receipt_state.total_underlying(vault_amount)?,
+        receipt_supply,
+    )?;
+    Ok(DepositQuote {
+        fee,
+        to_vault,
+        receipts,
+    })
+}
+
+/// Underlying paid out for burning `receipts`.
+pub fn quote_redeem(
+    receipt_state: &ReceiptState,
+    token_mint: &AccountInfo,
+    vault_amount: u64,
+    receipt_supply: u64,
+    receipts: u64,
+) -> Result<RedeemQuote> {
+    let underlying = receipts_to_underlying(
+        receipts,
+        receipt_state.total_underlying(vault_amount)?,
+        receipt_supply,
+    )?;
+    let transfer_fee = get_transfer_fee(token_mint, underlying)?;
+    Ok(RedeemQuote {
+        underlying,
+        received: underlying.saturating_sub(transfer_fee),
+    })
+}
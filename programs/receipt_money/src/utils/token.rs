@@ -1,11 +1,16 @@
 use crate::errors::ReceiptErrorCode;
-use anchor_lang::{prelude::*, system_program};
+use anchor_lang::{
+    prelude::*,
+    solana_program::program::{invoke, invoke_signed},
+    system_program,
+};
 use anchor_spl::{
-    token::{Token, TokenAccount as SplTokenAccount},
+    token::{Mint as SplMint, Token, TokenAccount as SplTokenAccount},
     token_2022::{
         self,
         spl_token_2022::{
             self,
+            solana_zk_token_sdk::zk_token_elgamal::pod::ElGamalPubkey,
             extension::{
                 transfer_fee::{TransferFeeConfig, MAX_FEE_BASIS_POINTS},
                 ExtensionType, StateWithExtensions,
@@ -48,6 +53,7 @@ pub fn transfer_from_user_to_token_vault<'a>(
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn transfer_from_pool_vault_to_user<'a>(
     authority: AccountInfo<'a>,
     from_vault: AccountInfo<'a>,
@@ -77,6 +83,38 @@ pub fn transfer_from_pool_vault_to_user<'a>(
     )
 }
 
+/// `transfer_checked` for mints that may carry a Token-2022 transfer hook. The
+/// hook program, its extra account meta list and the accounts the list
+/// resolves to are looked up in `hook_accounts`, usually the remaining accounts.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked_with_hook<'a>(
+    authority: AccountInfo<'a>,
+    from: AccountInfo<'a>,
+    to: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    amount: u64,
+    mint_decimals: u8,
+    hook_accounts: &[AccountInfo<'a>],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    spl_token_2022::onchain::invoke_transfer_checked(
+        token_program.key,
+        from,
+        mint,
+        to,
+        authority,
+        hook_accounts,
+        amount,
+        mint_decimals,
+        signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
 /// Issue a spl_token `MintTo` instruction.
 pub fn token_mint_to<'a>(
     authority: AccountInfo<'a>,
@@ -122,6 +160,75 @@ pub fn token_burn<'a>(
     )
 }
 
+/// Close a token account or a Token-2022 mint, sending its lamports to `destination`.
+pub fn token_close_account<'a>(
+    authority: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    account: AccountInfo<'a>,
+    destination: AccountInfo<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    token_2022::close_account(CpiContext::new_with_signer(
+        token_program,
+        token_2022::CloseAccount {
+            account,
+            destination,
+            authority,
+        },
+        signer_seeds,
+    ))
+}
+
+/// Whether a Token-2022 mint carries the `MintCloseAuthority` extension.
+pub fn has_mint_close_authority(mint_info: &AccountInfo) -> Result<bool> {
+    if *mint_info.owner != token_2022::Token2022::id() {
+        return Ok(false);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    Ok(mint
+        .get_extension_types()?
+        .contains(&ExtensionType::MintCloseAuthority))
+}
+
+/// Initialize the `ConfidentialTransferMint` extension of an uninitialized
+/// mint. New accounts are auto approved, `auditor_elgamal_pubkey` can decrypt
+/// every confidential transfer.
+pub fn confidential_transfer_initialize_mint<'a>(
+    token_program: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    authority: &Pubkey,
+    auditor_elgamal_pubkey: Option<[u8; 32]>,
+) -> Result<()> {
+    let ix = spl_token_2022::extension::confidential_transfer::instruction::initialize_mint(
+        token_program.key,
+        mint.key,
+        Some(*authority),
+        true,
+        auditor_elgamal_pubkey.map(ElGamalPubkey),
+    )?;
+    invoke(&ix, std::slice::from_ref(mint)).map_err(Into::into)
+}
+
+/// Replace the auditor of a `ConfidentialTransferMint`, keeping auto approval.
+pub fn confidential_transfer_update_mint<'a>(
+    token_program: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    auditor_elgamal_pubkey: Option<[u8; 32]>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let ix = spl_token_2022::extension::confidential_transfer::instruction::update_mint(
+        token_program.key,
+        mint.key,
+        authority.key,
+        &[],
+        true,
+        auditor_elgamal_pubkey.map(ElGamalPubkey),
+    )?;
+    invoke_signed(&ix, &[mint.clone(), authority.clone()], signer_seeds).map_err(Into::into)
+}
+
 /// Calculate the fee for output amount
 pub fn get_transfer_inverse_fee(mint_info: &AccountInfo, post_fee_amount: u64) -> Result<u64> {
     if *mint_info.owner == Token::id() {
@@ -230,6 +337,31 @@ pub fn create_token_account<'a>(
     ))
 }
 
+/// Allocate a mint account owned by `token_program` with room for `extensions`,
+/// which are ignored for the classic token program.
+pub fn create_mint_account<'a>(
+    payer: &AccountInfo<'a>,
+    mint_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    extensions: &[ExtensionType],
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let space = if *token_program.key == token_2022::Token2022::id() {
+        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(extensions)?
+    } else {
+        SplMint::LEN
+    };
+    create_or_allocate_account(
+        token_program.key,
+        payer.to_account_info(),
+        system_program.to_account_info(),
+        mint_account.to_account_info(),
+        signer_seeds,
+        space,
+    )
+}
+
 pub fn create_or_allocate_account<'a>(
     program_id: &Pubkey,
     payer: AccountInfo<'a>,
@@ -285,6 +417,7 @@ pub fn create_or_allocate_account<'a>(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_metaplex_metadata<'info>(
     name: &str,
     symbol: &str,
@@ -311,7 +444,7 @@ pub fn create_metaplex_metadata<'info>(
                 rent: rent.to_account_info(),
                 system_program: system_program.to_account_info(),
             },
-            &[&seeds],
+            &[seeds],
         ),
         DataV2 {
             name: name.to_string(),
@@ -0,0 +1,278 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { assert } from "chai";
+import { createHash } from "crypto";
+import { MockCoreBridge } from "../target/types/mock_core_bridge";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import { ReceiptHook } from "../target/types/receipt_hook";
+import {
+  createMarket,
+  deposit,
+  expectError,
+  fundUser,
+  getHookConfigPDA,
+  Market,
+  transferHookAccounts,
+} from "./utils";
+
+const FOREIGN_CHAIN = 2;
+
+describe("bridge", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const coreBridge = anchor.workspace.MockCoreBridge as Program<MockCoreBridge>;
+  const hookProgram = anchor.workspace.ReceiptHook as Program<ReceiptHook>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  const foreignEmitter = Keypair.generate().publicKey.toBuffer();
+  let market: Market;
+  let receiptAccount: PublicKey;
+  let vaaSequence = 0;
+
+  const pda = (seeds: Buffer[], programId = program.programId) =>
+    PublicKey.findProgramAddressSync(seeds, programId)[0];
+  const u16 = (value: number) => new BN(value).toArrayLike(Buffer, "le", 2);
+  const u64 = (value: number) => new BN(value).toArrayLike(Buffer, "le", 8);
+
+  // Borsh layout of `BridgeTransfer`
+  const transferPayload = (amount: number, recipient: PublicKey) =>
+    Buffer.concat([market.tokenMint.toBuffer(), u64(amount), u16(1), recipient.toBuffer()]);
+
+  const receiptBalance = async () =>
+    Number((await getAccount(provider.connection, receiptAccount, undefined, TOKEN_2022_PROGRAM_ID)).amount);
+
+  /** Has the guardian attest a message from `emitter` and returns the posted VAA. */
+  const postVaa = async (payload: Buffer, emitter = foreignEmitter, sequence = vaaSequence++) => {
+    const hash = createHash("sha256")
+      .update(Buffer.concat([u16(FOREIGN_CHAIN), emitter, u64(sequence), payload]))
+      .digest();
+    await coreBridge.methods
+      .postVaa(FOREIGN_CHAIN, Array.from(emitter), new BN(sequence), payload)
+      .accounts({ payer: payer.publicKey, guardian: payer.publicKey })
+      .rpc();
+    return { postedVaa: pda([Buffer.from("PostedVAA"), hash], coreBridge.programId), sequence };
+  };
+
+  const foreignEmitterPDA = (receiptState: PublicKey) =>
+    pda([Buffer.from("foreign_emitter"), receiptState.toBuffer(), u16(FOREIGN_CHAIN)]);
+  const bridgeClaimPDA = (receiptState: PublicKey, sequence: number) =>
+    pda([Buffer.from("bridge_claim"), receiptState.toBuffer(), u16(FOREIGN_CHAIN), foreignEmitter, u64(sequence)]);
+
+  const bridgeIn = ({ postedVaa, sequence }: { postedVaa: PublicKey; sequence: number }) =>
+    program.methods
+      .bridgeIn(new BN(sequence))
+      .accountsPartial({
+        payer: payer.publicKey,
+        receiptState: market.receiptState,
+        postedVaa,
+        foreignEmitter: foreignEmitterPDA(market.receiptState),
+        bridgeClaim: bridgeClaimPDA(market.receiptState, sequence),
+        cryptoReceiptMint: market.cryptoReceiptMint,
+        recipientCryptoReceiptTokenAccount: receiptAccount,
+        cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+  before(async () => {
+    await coreBridge.methods.initialize(payer.publicKey).accounts({ payer: payer.publicKey }).rpc();
+    market = await createMarket(program, payer);
+    ({ userCryptoReceiptTokenAccount: receiptAccount } = await fundUser(
+      program,
+      payer,
+      market,
+      payer.publicKey,
+      1_000_000
+    ));
+    await deposit(program, market, payer, 1_000_000);
+    await program.methods
+      .setBridgeConfig(coreBridge.programId, false)
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+    await program.methods
+      .registerForeignEmitter(FOREIGN_CHAIN, Array.from(foreignEmitter))
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+  });
+
+  it("burns receipts and posts a transfer message", async () => {
+    const emitter = pda([Buffer.from("bridge_emitter"), market.receiptState.toBuffer()]);
+    const message = pda([Buffer.from("Message"), emitter.toBuffer(), u64(0)], coreBridge.programId);
+    const before = await receiptBalance();
+
+    await program.methods
+      .bridgeOut(new BN(100_000), FOREIGN_CHAIN, Array.from(payer.publicKey.toBuffer()), 7)
+      .accountsPartial({
+        user: payer.publicKey,
+        receiptState: market.receiptState,
+        foreignEmitter: foreignEmitterPDA(market.receiptState),
+        cryptoReceiptMint: market.cryptoReceiptMint,
+        userCryptoReceiptTokenAccount: receiptAccount,
+        emitter,
+        coreBridgeProgram: coreBridge.programId,
+        sequence: pda([Buffer.from("Sequence"), emitter.toBuffer()], coreBridge.programId),
+        message,
+        cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    assert.equal(await receiptBalance(), before - 100_000);
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.equal(state.bridgedOutSupply.toNumber(), 100_000);
+    const posted = await coreBridge.account.postedMessage.fetch(message);
+    assert.isTrue(posted.emitter.equals(emitter));
+    assert.equal(posted.nonce, 7);
+    assert.deepEqual(
+      Buffer.from(posted.payload),
+      Buffer.concat([market.tokenMint.toBuffer(), u64(100_000), u16(FOREIGN_CHAIN), payer.publicKey.toBuffer()])
+    );
+  });
+
+  it("mints receipts for a VAA from the registered emitter once", async () => {
+    const vaa = await postVaa(transferPayload(40_000, payer.publicKey));
+    const before = await receiptBalance();
+    await bridgeIn(vaa);
+    assert.equal(await receiptBalance(), before + 40_000);
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.equal(state.bridgedOutSupply.toNumber(), 60_000);
+    const claim = await program.account.bridgeClaim.fetch(bridgeClaimPDA(market.receiptState, vaa.sequence));
+    assert.equal(claim.sequence.toNumber(), vaa.sequence);
+
+    await expectError(bridgeIn(vaa), "already in use");
+  });
+
+  it("redeems a message once even when it is posted twice", async () => {
+    const first = await postVaa(transferPayload(5_000, payer.publicKey));
+    await bridgeIn(first);
+    // Same emitter and sequence, different body so the core bridge posts a new account
+    const second = await postVaa(transferPayload(6_000, payer.publicKey), foreignEmitter, first.sequence);
+    assert.isFalse(second.postedVaa.equals(first.postedVaa));
+    await expectError(bridgeIn(second), "already in use");
+  });
+
+  it("rejects a claim keyed on another sequence", async () => {
+    const { postedVaa } = await postVaa(transferPayload(1_000, payer.publicKey));
+    await expectError(bridgeIn({ postedVaa, sequence: vaaSequence + 100 }), "InvalidBridgeMessage");
+  });
+
+  it("only replaces a registered emitter through a proposal", async () => {
+    await expectError(
+      program.methods
+        .registerForeignEmitter(FOREIGN_CHAIN, Array.from(Keypair.generate().publicKey.toBuffer()))
+        .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+        .rpc(),
+      "ForeignEmitterRegistered"
+    );
+  });
+
+  it("rejects VAAs from unknown emitters", async () => {
+    const vaa = await postVaa(transferPayload(10_000, payer.publicKey), Keypair.generate().publicKey.toBuffer());
+    await expectError(bridgeIn(vaa), "UnknownEmitter");
+  });
+
+  it("never mints more than was bridged out", async () => {
+    const vaa = await postVaa(transferPayload(70_000, payer.publicKey));
+    await expectError(bridgeIn(vaa), "InvalidBridgeMessage");
+  });
+
+  describe("escrow on a transfer hook market", () => {
+    let hookMarket: Market;
+    let hookReceiptAccount: PublicKey;
+    let bridgeEscrow: PublicKey;
+
+    before(async () => {
+      hookMarket = await createMarket(program, payer, 9, TOKEN_2022_PROGRAM_ID, { transferHook: true });
+      ({ userCryptoReceiptTokenAccount: hookReceiptAccount } = await fundUser(
+        program,
+        payer,
+        hookMarket,
+        payer.publicKey,
+        1_000_000
+      ));
+      await deposit(program, hookMarket, payer, 1_000_000);
+      await program.methods
+        .setBridgeConfig(coreBridge.programId, true)
+        .accountsPartial({ authority: payer.publicKey, receiptState: hookMarket.receiptState })
+        .rpc();
+      await program.methods
+        .registerForeignEmitter(FOREIGN_CHAIN, Array.from(foreignEmitter))
+        .accountsPartial({ authority: payer.publicKey, receiptState: hookMarket.receiptState })
+        .rpc();
+      bridgeEscrow = pda([Buffer.from("bridge_escrow"), hookMarket.receiptState.toBuffer()]);
+
+      // Only the user is allowlisted, the escrow is market custody
+      const hookConfig = getHookConfigPDA(hookMarket.cryptoReceiptMint);
+      await hookProgram.methods
+        .setAllowlistEnabled(true)
+        .accountsPartial({ authority: payer.publicKey, hookConfig })
+        .rpc();
+      await hookProgram.methods
+        .addToAllowlist()
+        .accountsPartial({ authority: payer.publicKey, hookConfig, wallet: payer.publicKey })
+        .rpc();
+    });
+
+    it("escrows and releases receipts through the transfer hook", async () => {
+      const emitter = pda([Buffer.from("bridge_emitter"), hookMarket.receiptState.toBuffer()]);
+      // The first escrowed bridge out creates the escrow, so the hook accounts
+      // are resolved against the receipt vault, also owned by the vault authority
+      await program.methods
+        .bridgeOut(new BN(100_000), FOREIGN_CHAIN, Array.from(payer.publicKey.toBuffer()), 0)
+        .accountsPartial({
+          user: payer.publicKey,
+          receiptState: hookMarket.receiptState,
+          foreignEmitter: foreignEmitterPDA(hookMarket.receiptState),
+          cryptoReceiptMint: hookMarket.cryptoReceiptMint,
+          userCryptoReceiptTokenAccount: hookReceiptAccount,
+          emitter,
+          coreBridgeProgram: coreBridge.programId,
+          sequence: pda([Buffer.from("Sequence"), emitter.toBuffer()], coreBridge.programId),
+          message: pda([Buffer.from("Message"), emitter.toBuffer(), u64(0)], coreBridge.programId),
+          cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .remainingAccounts(
+          await transferHookAccounts(
+            provider.connection,
+            hookMarket.cryptoReceiptMint,
+            hookReceiptAccount,
+            hookMarket.cryptoReceiptMintVault,
+            payer.publicKey,
+            100_000
+          )
+        )
+        .rpc();
+      const escrowed = await getAccount(provider.connection, bridgeEscrow, undefined, TOKEN_2022_PROGRAM_ID);
+      assert.equal(Number(escrowed.amount), 100_000);
+
+      const vaa = await postVaa(
+        Buffer.concat([hookMarket.tokenMint.toBuffer(), u64(40_000), u16(1), payer.publicKey.toBuffer()])
+      );
+      await program.methods
+        .bridgeIn(new BN(vaa.sequence))
+        .accountsPartial({
+          payer: payer.publicKey,
+          receiptState: hookMarket.receiptState,
+          postedVaa: vaa.postedVaa,
+          foreignEmitter: foreignEmitterPDA(hookMarket.receiptState),
+          bridgeClaim: bridgeClaimPDA(hookMarket.receiptState, vaa.sequence),
+          cryptoReceiptMint: hookMarket.cryptoReceiptMint,
+          recipientCryptoReceiptTokenAccount: hookReceiptAccount,
+          cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .remainingAccounts(
+          await transferHookAccounts(
+            provider.connection,
+            hookMarket.cryptoReceiptMint,
+            bridgeEscrow,
+            hookReceiptAccount,
+            hookMarket.vaultAuthority,
+            40_000
+          )
+        )
+        .rpc();
+      const released = await getAccount(provider.connection, bridgeEscrow, undefined, TOKEN_2022_PROGRAM_ID);
+      assert.equal(Number(released.amount), 60_000);
+    });
+  });
+});
@@ -0,0 +1,240 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  transfer,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import {
+  createMarket,
+  deposit,
+  expectError,
+  fundUser,
+  getBridgeEscrowPDA,
+  getForeignEmitterPDA,
+  getInsuranceVaultPDA,
+  getLendingReservePDA,
+  getReserveCollateralPDA,
+  getReserveLiquidityPDA,
+  getRewardPoolPDA,
+  getRewardVaultPDA,
+  getStakeVaultPDA,
+  Market,
+  redeem,
+} from "./utils";
+
+describe("close market", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  const recipient = Keypair.generate().publicKey;
+  let market: Market;
+
+  const closeMarket = (
+    sweep: { insuranceVault?: PublicKey; feeReceiver?: PublicKey } = {},
+    rewardPools: PublicKey[] = []
+  ) =>
+    program.methods
+      .closeMarket()
+      .accountsPartial({
+        authority: payer.publicKey,
+        recipient,
+        receiptState: market.receiptState,
+        tokenMintVault: market.tokenMintVault,
+        cryptoReceiptMint: market.cryptoReceiptMint,
+        cryptoReceiptMintVault: market.cryptoReceiptMintVault,
+        stakeVault: getStakeVaultPDA(market.receiptState, program.programId),
+        bridgeEscrow: getBridgeEscrowPDA(market.receiptState, program.programId),
+        insuranceVault: sweep.insuranceVault ?? null,
+        feeReceiver: sweep.feeReceiver ?? null,
+        tokenMint: market.tokenMint,
+        stakePoolVault: null,
+        stakePoolTokenReceiver: null,
+        poolMint: null,
+        poolMintProgram: null,
+        tokenMintProgram: TOKEN_PROGRAM_ID,
+        cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        rewardPools.flatMap((rewardPool) => [
+          { pubkey: rewardPool, isSigner: false, isWritable: true },
+          { pubkey: getRewardVaultPDA(rewardPool, program.programId), isSigner: false, isWritable: true },
+          { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+        ])
+      )
+      .rpc();
+
+  const setFeeReceiver = async () => {
+    const feeReceiver = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, payer, market.tokenMint, Keypair.generate().publicKey)
+    ).address;
+    await program.methods
+      .setFeeConfig(0, 0, feeReceiver)
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+    return feeReceiver;
+  };
+
+  before(async () => {
+    market = await createMarket(program, payer);
+    await fundUser(program, payer, market, payer.publicKey, 1_000_000);
+    await deposit(program, market, payer, 1_000_000);
+  });
+
+  it("refuses while receipts are outstanding", async () => {
+    await expectError(closeMarket(), "MarketNotEmpty");
+  });
+
+  it("closes every market account and pays the rent to the recipient", async () => {
+    await redeem(program, market, payer, 1_000_000);
+    await closeMarket();
+
+    const connection = provider.connection;
+    for (const account of [
+      market.receiptState,
+      market.tokenMintVault,
+      market.cryptoReceiptMint,
+      market.cryptoReceiptMintVault,
+    ]) {
+      assert.isNull(await connection.getAccountInfo(account));
+    }
+    assert.isAbove(await connection.getBalance(recipient), 0);
+  });
+
+  it("closes an empty market without waiting out its timelock", async () => {
+    market = await createMarket(program, payer);
+    await program.methods
+      .setTimelock(payer.publicKey, new BN(60))
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+    await closeMarket();
+    assert.isNull(await provider.connection.getAccountInfo(market.receiptState));
+  });
+
+  it("waits for every lending reserve to close", async () => {
+    market = await createMarket(program, payer);
+    const reserve = getLendingReservePDA(market.receiptState, market.tokenMint, program.programId);
+    const reserveAccounts = {
+      authority: payer.publicKey,
+      receiptState: market.receiptState,
+      reserve,
+      liquidityVault: getReserveLiquidityPDA(reserve, program.programId),
+      collateralVault: getReserveCollateralPDA(reserve, program.programId),
+      borrowMintProgram: TOKEN_PROGRAM_ID,
+      cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+    };
+    await program.methods
+      .initLendingReserve(7_500, 8_500, 500, 200, 2_000)
+      .accountsPartial({
+        ...reserveAccounts,
+        borrowMint: market.tokenMint,
+        vaultAuthority: market.vaultAuthority,
+        cryptoReceiptMint: market.cryptoReceiptMint,
+      })
+      .rpc();
+    await expectError(closeMarket(), "MarketNotEmpty");
+
+    await program.methods
+      .closeLendingReserve()
+      .accountsPartial({ ...reserveAccounts, recipient })
+      .rpc();
+    await closeMarket();
+    assert.isNull(await provider.connection.getAccountInfo(market.receiptState));
+  });
+
+  it("sweeps the insurance fund to the fee receiver", async () => {
+    market = await createMarket(program, payer);
+    const { userMintTokenAccount } = await fundUser(program, payer, market, payer.publicKey, 100_000);
+    const feeReceiver = await setFeeReceiver();
+    const insuranceVault = getInsuranceVaultPDA(market.receiptState, program.programId);
+    await program.methods
+      .initInsuranceVault()
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        tokenMint: market.tokenMint,
+        insuranceVault,
+        vaultAuthority: market.vaultAuthority,
+        tokenMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    await program.methods
+      .fundInsurance(new BN(100_000))
+      .accountsPartial({
+        funder: payer.publicKey,
+        funderTokenAccount: userMintTokenAccount,
+        receiptState: market.receiptState,
+        tokenMint: market.tokenMint,
+        insuranceVault,
+        tokenMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    await expectError(closeMarket(), "InvalidInsuranceVault");
+
+    await closeMarket({ insuranceVault, feeReceiver });
+    assert.isNull(await provider.connection.getAccountInfo(insuranceVault));
+    assert.equal(Number((await getAccount(provider.connection, feeReceiver)).amount), 100_000);
+  });
+
+  it("sweeps underlying sent to an empty market to the fee receiver", async () => {
+    market = await createMarket(program, payer);
+    const { userMintTokenAccount } = await fundUser(program, payer, market, payer.publicKey, 1);
+    await transfer(provider.connection, payer, userMintTokenAccount, market.tokenMintVault, payer, 1);
+    await expectError(closeMarket(), "InvalidFeeReceiver");
+
+    const feeReceiver = await setFeeReceiver();
+    await closeMarket({ feeReceiver });
+    assert.isNull(await provider.connection.getAccountInfo(market.tokenMintVault));
+    assert.equal(Number((await getAccount(provider.connection, feeReceiver)).amount), 1);
+  });
+
+  it("closes reward pools, their vaults and the stake vault", async () => {
+    market = await createMarket(program, payer);
+    const rewardPool = getRewardPoolPDA(market.receiptState, market.tokenMint, program.programId);
+    const stakeVault = getStakeVaultPDA(market.receiptState, program.programId);
+    await program.methods
+      .addRewardPool()
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        rewardMint: market.tokenMint,
+        rewardPool,
+        rewardVault: getRewardVaultPDA(rewardPool, program.programId),
+        cryptoReceiptMint: market.cryptoReceiptMint,
+        stakeVault,
+        rewardMintProgram: TOKEN_PROGRAM_ID,
+        cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+    await expectError(closeMarket(), "InvalidRewardPool");
+
+    await closeMarket({}, [rewardPool]);
+    for (const account of [rewardPool, getRewardVaultPDA(rewardPool, program.programId), stakeVault]) {
+      assert.isNull(await provider.connection.getAccountInfo(account));
+    }
+  });
+
+  it("waits for every foreign emitter to close", async () => {
+    market = await createMarket(program, payer);
+    const foreignEmitter = getForeignEmitterPDA(market.receiptState, 2, program.programId);
+    await program.methods
+      .registerForeignEmitter(2, Array.from(Keypair.generate().publicKey.toBuffer()))
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState, foreignEmitter })
+      .rpc();
+    await expectError(closeMarket(), "MarketNotEmpty");
+
+    await program.methods
+      .closeForeignEmitter()
+      .accountsPartial({ authority: payer.publicKey, recipient, receiptState: market.receiptState, foreignEmitter })
+      .rpc();
+    await closeMarket();
+    assert.isNull(await provider.connection.getAccountInfo(foreignEmitter));
+    assert.isNull(await provider.connection.getAccountInfo(market.receiptState));
+  });
+});
@@ -0,0 +1,113 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  ExtensionType,
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  getExtensionTypes,
+  getMint,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import { createMarket, deposit, expectError, fundUser, Market, redeem } from "./utils";
+
+describe("confidential transfer receipts", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  const auditor = Array.from(Keypair.generate().publicKey.toBytes());
+  let market: Market;
+
+  before(async () => {
+    market = await createMarket(program, payer, 9, TOKEN_2022_PROGRAM_ID, {
+      confidentialTransferAuditor: auditor,
+    });
+    await fundUser(program, payer, market, payer.publicKey, 10_000_000);
+  });
+
+  it("initializes the receipt mint with the confidential transfer extension", async () => {
+    const mint = await getMint(
+      provider.connection,
+      market.cryptoReceiptMint,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.include(getExtensionTypes(mint.tlvData), ExtensionType.ConfidentialTransferMint);
+  });
+
+  it("deposits and redeems against the public balance", async () => {
+    await deposit(program, market, payer, 1_000_000);
+    await redeem(program, market, payer, 400_000);
+    await expectError(redeem(program, market, payer, 700_000), "InsufficientPublicBalance");
+  });
+
+  it("lets the authority rotate the auditor", async () => {
+    await program.methods
+      .setConfidentialTransferAuditor(null)
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        cryptoReceiptMint: market.cryptoReceiptMint,
+      })
+      .rpc();
+  });
+
+  it("rotates the auditor of a timelocked market through a proposal", async () => {
+    const auditorAccounts = {
+      receiptState: market.receiptState,
+      cryptoReceiptMint: market.cryptoReceiptMint,
+    };
+    await program.methods
+      .setTimelock(payer.publicKey, new BN(2))
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+    await expectError(
+      program.methods
+        .setConfidentialTransferAuditor(auditor)
+        .accountsPartial({ authority: payer.publicKey, ...auditorAccounts })
+        .rpc(),
+      "TimelockActive"
+    );
+    await expectError(
+      program.methods.applyConfidentialTransferAuditor().accountsPartial(auditorAccounts).rpc(),
+      "NoPendingAuditorUpdate"
+    );
+
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    const proposal = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        market.receiptState.toBuffer(),
+        state.proposalCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+    await program.methods
+      .proposeParamChange({ confidentialTransferAuditor: [auditor] })
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState, proposal })
+      .rpc();
+    await new Promise((resolve) => setTimeout(resolve, 3_000));
+    await program.methods
+      .executeProposal()
+      .accountsPartial({
+        receiptState: market.receiptState,
+        proposal,
+        proposer: payer.publicKey,
+        payer: null,
+        foreignEmitter: null,
+      })
+      .rpc();
+    await program.methods.applyConfidentialTransferAuditor().accountsPartial(auditorAccounts).rpc();
+
+    assert.isFalse((await program.account.receiptState.fetch(market.receiptState)).auditorUpdatePending);
+  });
+
+  it("rejects confidential classic spl token receipt mints", async () => {
+    await expectError(
+      createMarket(program, payer, 9, TOKEN_PROGRAM_ID, { confidentialTransferAuditor: auditor }),
+      "ConfidentialTransferNotSupported"
+    );
+  });
+});
@@ -0,0 +1,85 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, Transaction } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, getAccount, getAssociatedTokenAddressSync, transfer } from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import { createMarket, deposit, depositAccounts, fundUser, Market, redeem } from "./utils";
+
+describe("deposit", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  let market: Market;
+
+  const receiptBalance = async (owner: anchor.web3.PublicKey) => {
+    const account = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(market.cryptoReceiptMint, owner, false, TOKEN_2022_PROGRAM_ID),
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    return account.amount.toString();
+  };
+
+  before(async () => {
+    market = await createMarket(program, payer);
+    await fundUser(program, payer, market, payer.publicKey, 10_000_000);
+  });
+
+  it("mints receipts to the depositor", async () => {
+    await deposit(program, market, payer, 1_000_000);
+    assert.equal(await receiptBalance(payer.publicKey), "1000000");
+  });
+
+  it("creates the recipient's receipt account when missing", async () => {
+    const recipient = Keypair.generate().publicKey;
+    await deposit(program, market, payer, 2_000_000, recipient);
+    assert.equal(await receiptBalance(recipient), "2000000");
+  });
+
+  it("deposits for many recipients in one transaction", async () => {
+    const recipients = [Keypair.generate().publicKey, Keypair.generate().publicKey];
+    const tx = new Transaction();
+    for (const recipient of recipients) {
+      tx.add(
+        await program.methods
+          .deposit(new BN(500_000))
+          .accountsPartial(depositAccounts(market, payer.publicKey, recipient))
+          .instruction()
+      );
+    }
+    await provider.sendAndConfirm(tx);
+    for (const recipient of recipients) {
+      assert.equal(await receiptBalance(recipient), "500000");
+    }
+  });
+
+  it("makes inflating an empty market's exchange rate unprofitable", async () => {
+    const donated = await createMarket(program, payer);
+    const attacker = Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(attacker.publicKey, 1_000_000_000)
+    );
+    const { userMintTokenAccount } = await fundUser(program, payer, donated, attacker.publicKey, 1_000_001);
+    await fundUser(program, payer, donated, payer.publicKey, 1_000_000);
+
+    // One unit buys the only receipt, then the rest is donated to the vault
+    await deposit(program, donated, attacker, 1);
+    await transfer(provider.connection, attacker, userMintTokenAccount, donated.tokenMintVault, attacker, 1_000_000);
+
+    await deposit(program, donated, payer, 1_000_000);
+    const payerReceipts = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(donated.cryptoReceiptMint, payer.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.isAbove(Number(payerReceipts.amount), 0);
+
+    await redeem(program, donated, attacker, 1);
+    const recovered = await getAccount(provider.connection, userMintTokenAccount);
+    assert.isBelow(Number(recovered.amount), 1_000_001);
+  });
+});
@@ -0,0 +1,136 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, createMint, getAccount, getOrCreateAssociatedTokenAccount, mintTo } from "@solana/spl-token";
+import { assert } from "chai";
+import { createHash } from "crypto";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import { createMarket, expectError, getDistributionPDA, getDistributionVaultPDA, Market } from "./utils";
+
+// Mirrors utils/merkle.rs and tools/merkle_tree
+const sha256 = (...parts: Buffer[]) => createHash("sha256").update(Buffer.concat(parts)).digest();
+
+const leaf = (index: number, wallet: PublicKey, amount: number) =>
+  sha256(
+    Buffer.from([0]),
+    new BN(index).toArrayLike(Buffer, "le", 8),
+    wallet.toBuffer(),
+    new BN(amount).toArrayLike(Buffer, "le", 8)
+  );
+
+const node = (a: Buffer, b: Buffer) =>
+  Buffer.compare(a, b) <= 0 ? sha256(Buffer.from([1]), a, b) : sha256(Buffer.from([1]), b, a);
+
+function buildTree(leaves: Buffer[]): { root: Buffer; proofs: Buffer[][] } {
+  const levels = [leaves];
+  while (levels[levels.length - 1].length > 1) {
+    const level = levels[levels.length - 1];
+    const parents: Buffer[] = [];
+    for (let i = 0; i < level.length; i += 2) {
+      parents.push(i + 1 < level.length ? node(level[i], level[i + 1]) : level[i]);
+    }
+    levels.push(parents);
+  }
+  const proofs = leaves.map((_, leafIndex) => {
+    const proof: Buffer[] = [];
+    let index = leafIndex;
+    for (const level of levels.slice(0, -1)) {
+      const sibling = level[index ^ 1];
+      if (sibling) proof.push(sibling);
+      index = Math.floor(index / 2);
+    }
+    return proof;
+  });
+  return { root: levels[levels.length - 1][0], proofs };
+}
+
+describe("merkle distribution", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  const holders = [
+    { wallet: payer, amount: 1_000 },
+    { wallet: Keypair.generate(), amount: 2_500 },
+    { wallet: Keypair.generate(), amount: 400 },
+  ];
+  const tree = buildTree(holders.map((holder, index) => leaf(index, holder.wallet.publicKey, holder.amount)));
+  let market: Market;
+  let mint: PublicKey;
+  let payerTokenAccount: PublicKey;
+  let distribution: PublicKey;
+
+  const accounts = () => ({
+    receiptState: market.receiptState,
+    distribution,
+    vault: getDistributionVaultPDA(distribution, program.programId),
+    mint,
+    tokenProgram: TOKEN_PROGRAM_ID,
+  });
+
+  const claim = (holderIndex: number, amount = holders[holderIndex].amount) =>
+    program.methods
+      .claim(
+        new BN(holderIndex),
+        new BN(amount),
+        tree.proofs[holderIndex].map((sibling) => Array.from(sibling))
+      )
+      .accountsPartial({
+        ...accounts(),
+        claimant: holders[holderIndex].wallet.publicKey,
+        claimantTokenAccount: payerTokenAccount,
+      })
+      .signers([holders[holderIndex].wallet])
+      .rpc();
+
+  before(async () => {
+    market = await createMarket(program, payer);
+    mint = await createMint(provider.connection, payer, payer.publicKey, null, 6);
+    payerTokenAccount = (await getOrCreateAssociatedTokenAccount(provider.connection, payer, mint, payer.publicKey))
+      .address;
+    await mintTo(provider.connection, payer, mint, payerTokenAccount, payer, 10_000);
+    distribution = getDistributionPDA(market.receiptState, 0, program.programId);
+
+    await program.methods
+      .createDistribution(
+        Array.from(tree.root),
+        new BN(3_900),
+        new BN(holders.length),
+        new BN(Math.floor(Date.now() / 1000) + 5)
+      )
+      .accountsPartial({
+        ...accounts(),
+        authority: payer.publicKey,
+        authorityTokenAccount: payerTokenAccount,
+      })
+      .rpc();
+  });
+
+  it("pays a leaf once with a valid proof", async () => {
+    await expectError(claim(1, 3_000), "InvalidMerkleProof");
+    await claim(1);
+    await expectError(claim(1), "AlreadyClaimed");
+    const state = await program.account.distribution.fetch(distribution);
+    assert.equal(state.claimedAmount.toNumber(), 2_500);
+  });
+
+  it("claws back the unclaimed rest after expiry and closes the distribution", async () => {
+    const clawback = () =>
+      program.methods
+        .clawback()
+        .accountsPartial({ ...accounts(), authority: payer.publicKey, receiverTokenAccount: payerTokenAccount })
+        .rpc();
+    await expectError(clawback(), "DistributionNotExpired");
+
+    await new Promise((resolve) => setTimeout(resolve, 6_000));
+    const before = Number((await getAccount(provider.connection, payerTokenAccount)).amount);
+    await clawback();
+    const after = Number((await getAccount(provider.connection, payerTokenAccount)).amount);
+    assert.equal(after - before, 1_400);
+    assert.isNull(await provider.connection.getAccountInfo(distribution));
+    assert.isNull(await provider.connection.getAccountInfo(accounts().vault));
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.equal(state.openDistributionCount.toNumber(), 0);
+    await expectError(claim(0), "AccountNotInitialized");
+  });
+});
@@ -0,0 +1,155 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import {
+  createMarket,
+  deposit,
+  expectError,
+  fundUser,
+  getInsuranceVaultPDA,
+  Market,
+  redeem,
+} from "./utils";
+
+describe("emergency shutdown", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  const holder = Keypair.generate();
+  let market: Market;
+  let payerAccounts: { userMintTokenAccount: PublicKey; userCryptoReceiptTokenAccount: PublicKey };
+  let holderAccounts: { userMintTokenAccount: PublicKey; userCryptoReceiptTokenAccount: PublicKey };
+
+  const shutdown = (guardian: Keypair = payer) =>
+    program.methods
+      .emergencyShutdown()
+      .accountsPartial({
+        guardian: guardian.publicKey,
+        receiptState: market.receiptState,
+        tokenMintVault: market.tokenMintVault,
+        cryptoReceiptMint: market.cryptoReceiptMint,
+        stakePoolVault: null,
+      })
+      .signers([guardian])
+      .rpc();
+
+  const emergencyRedeem = (
+    user: Keypair,
+    accounts: { userMintTokenAccount: PublicKey; userCryptoReceiptTokenAccount: PublicKey },
+    receiptAmount: number
+  ) =>
+    program.methods
+      .emergencyRedeem(new BN(receiptAmount))
+      .accountsPartial({
+        user: user.publicKey,
+        ...accounts,
+        receiptState: market.receiptState,
+        tokenMint: market.tokenMint,
+        vaultAuthority: market.vaultAuthority,
+        tokenMintVault: market.tokenMintVault,
+        cryptoReceiptMint: market.cryptoReceiptMint,
+        tokenMintProgram: TOKEN_PROGRAM_ID,
+        cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+        stakePoolVault: null,
+        userPoolTokenAccount: null,
+        poolMint: null,
+        poolMintProgram: null,
+      })
+      .signers([user])
+      .rpc();
+
+  const balance = async (account: PublicKey) => Number((await getAccount(provider.connection, account)).amount);
+
+  before(async () => {
+    market = await createMarket(program, payer);
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(holder.publicKey, LAMPORTS_PER_SOL)
+    );
+    payerAccounts = await fundUser(program, payer, market, payer.publicKey, 2_000_000);
+    holderAccounts = await fundUser(program, payer, market, holder.publicKey, 1_000_000);
+    await deposit(program, market, payer, 1_000_000);
+    await deposit(program, market, holder, 1_000_000);
+
+    const insuranceVault = getInsuranceVaultPDA(market.receiptState, program.programId);
+    await program.methods
+      .initInsuranceVault()
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        tokenMint: market.tokenMint,
+        insuranceVault,
+        vaultAuthority: market.vaultAuthority,
+        tokenMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    await program.methods
+      .fundInsurance(new BN(200_000))
+      .accountsPartial({
+        funder: payer.publicKey,
+        funderTokenAccount: payerAccounts.userMintTokenAccount,
+        receiptState: market.receiptState,
+        tokenMint: market.tokenMint,
+        insuranceVault,
+        tokenMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  });
+
+  it("keeps emergency redemptions closed while the market runs", async () => {
+    await expectError(emergencyRedeem(holder, holderAccounts, 100_000), "MarketNotShutdown");
+  });
+
+  it("only lets the guardian or authority shut down", async () => {
+    await expectError(shutdown(holder), "Unauthorized");
+  });
+
+  it("snapshots the backing and freezes the market", async () => {
+    await shutdown();
+
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.isTrue(state.shutdown);
+    assert.equal(state.shutdownUnderlying.toNumber(), 2_000_000);
+    assert.equal(state.shutdownReceiptSupply.toNumber(), 2_000_000);
+    await expectError(deposit(program, market, payer, 100_000), "MarketShutdown");
+    await expectError(redeem(program, market, holder, 100_000), "MarketShutdown");
+    await expectError(shutdown(), "MarketShutdown");
+  });
+
+  it("redeems every holder pro-rata", async () => {
+    const before = await balance(holderAccounts.userMintTokenAccount);
+    await emergencyRedeem(holder, holderAccounts, 500_000);
+
+    assert.equal((await balance(holderAccounts.userMintTokenAccount)) - before, 500_000);
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.equal(state.shutdownUnderlying.toNumber(), 1_500_000);
+    assert.equal(state.shutdownReceiptSupply.toNumber(), 1_500_000);
+  });
+
+  it("shares losses covered after shutdown with the holders still to redeem", async () => {
+    await program.methods
+      .coverLoss(new BN(150_000))
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        tokenMint: market.tokenMint,
+        tokenMintVault: market.tokenMintVault,
+        insuranceVault: getInsuranceVaultPDA(market.receiptState, program.programId),
+        vaultAuthority: market.vaultAuthority,
+        tokenMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const payerBefore = await balance(payerAccounts.userMintTokenAccount);
+    await emergencyRedeem(payer, payerAccounts, 1_000_000);
+    assert.equal((await balance(payerAccounts.userMintTokenAccount)) - payerBefore, 1_100_000);
+
+    const holderBefore = await balance(holderAccounts.userMintTokenAccount);
+    await emergencyRedeem(holder, holderAccounts, 500_000);
+    assert.equal((await balance(holderAccounts.userMintTokenAccount)) - holderBefore, 550_000);
+    assert.equal(await balance(market.tokenMintVault), 0);
+  });
+});
@@ -0,0 +1,72 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { SYSVAR_INSTRUCTIONS_PUBKEY, Transaction } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import { createMarket, deposit, expectError, fundUser, Market } from "./utils";
+
+describe("flash loans", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  let market: Market;
+
+  const borrowIx = (amount: number) =>
+    program.methods
+      .flashBorrow(new BN(amount))
+      .accountsPartial({
+        borrower: payer.publicKey,
+        receiptState: market.receiptState,
+        tokenMint: market.tokenMint,
+        tokenMintVault: market.tokenMintVault,
+        borrowerTokenAccount: getAssociatedTokenAddressSync(market.tokenMint, payer.publicKey),
+        instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        tokenMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .instruction();
+
+  const repayIx = () =>
+    program.methods
+      .flashRepay()
+      .accountsPartial({
+        repayer: payer.publicKey,
+        receiptState: market.receiptState,
+        tokenMint: market.tokenMint,
+        tokenMintVault: market.tokenMintVault,
+        repayerTokenAccount: getAssociatedTokenAddressSync(market.tokenMint, payer.publicKey),
+        tokenMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .instruction();
+
+  before(async () => {
+    market = await createMarket(program, payer);
+    await fundUser(program, payer, market, payer.publicKey, 2_000_000_000);
+    await deposit(program, market, payer, 1_000_000_000);
+  });
+
+  it("rejects borrows while disabled", async () => {
+    const tx = new Transaction().add(await borrowIx(100_000_000), await repayIx());
+    await expectError(provider.sendAndConfirm(tx), "FlashLoanDisabled");
+  });
+
+  it("accrues the fee to the vault", async () => {
+    await program.methods
+      .setFlashLoanConfig(true, 30)
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+    const tx = new Transaction().add(await borrowIx(100_000_000), await repayIx());
+    await provider.sendAndConfirm(tx);
+
+    const vault = await getAccount(provider.connection, market.tokenMintVault);
+    assert.equal(vault.amount.toString(), "1000300000");
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.equal(state.flashLoanOutstanding.toNumber(), 0);
+  });
+
+  it("rejects a borrow without a matching repay", async () => {
+    const tx = new Transaction().add(await borrowIx(100_000_000));
+    await expectError(provider.sendAndConfirm(tx), "MissingFlashRepay");
+  });
+});
@@ -0,0 +1,74 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { TOKEN_2022_PROGRAM_ID, getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import { createMarket, deposit, expectError, fundUser, Market, redeem } from "./utils";
+
+describe("flow limits", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  let market: Market;
+
+  const resetFlowLimits = () =>
+    program.methods
+      .resetFlowLimits()
+      .accountsPartial({
+        guardian: payer.publicKey,
+        receiptState: market.receiptState,
+        tokenMintVault: market.tokenMintVault,
+      })
+      .rpc();
+
+  const receiptBalance = async () =>
+    Number(
+      (
+        await getAccount(
+          provider.connection,
+          getAssociatedTokenAddressSync(market.cryptoReceiptMint, payer.publicKey, false, TOKEN_2022_PROGRAM_ID),
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ).amount
+    );
+
+  before(async () => {
+    market = await createMarket(program, payer);
+    await fundUser(program, payer, market, payer.publicKey, 10_000_000);
+    await deposit(program, market, payer, 1_000_000);
+    await program.methods
+      .setFlowLimits(new BN(100_000), 5_000, 1_000)
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+    // Start a window at the current TVL
+    await resetFlowLimits();
+  });
+
+  it("pauses the market instead of letting outflow exceed the limit", async () => {
+    await redeem(program, market, payer, 60_000);
+    const before = await receiptBalance();
+
+    await redeem(program, market, payer, 60_000);
+    assert.equal(await receiptBalance(), before);
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.isTrue(state.paused);
+
+    await expectError(deposit(program, market, payer, 1_000), "MarketPaused");
+    await expectError(redeem(program, market, payer, 1_000), "MarketPaused");
+  });
+
+  it("resumes after the guardian resets the limits", async () => {
+    await resetFlowLimits();
+    const before = await receiptBalance();
+    await redeem(program, market, payer, 60_000);
+    assert.equal(await receiptBalance(), before - 60_000);
+  });
+
+  it("does not count deposits the user cannot pay for", async () => {
+    await expectError(deposit(program, market, payer, 20_000_000), "InsufficientBalance");
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.isFalse(state.paused);
+  });
+});
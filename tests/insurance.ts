@@ -0,0 +1,154 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, getAccount, getOrCreateAssociatedTokenAccount } from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import {
+  createMarket,
+  depositAccounts,
+  expectError,
+  fundUser,
+  getInsuranceVaultPDA,
+  Market,
+} from "./utils";
+
+describe("insurance fund", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  const treasury = Keypair.generate().publicKey;
+  let market: Market;
+  let feeReceiver: PublicKey;
+  let insuranceVault: PublicKey;
+
+  const depositWithFee = (amount: number, withInsuranceVault = true) =>
+    program.methods
+      .deposit(new BN(amount))
+      .accountsPartial({
+        ...depositAccounts(market, payer.publicKey),
+        feeReceiver,
+        insuranceVault: withInsuranceVault ? insuranceVault : null,
+      })
+      .rpc();
+
+  const coverLoss = (amount: number, authority: Keypair = payer) =>
+    program.methods
+      .coverLoss(new BN(amount))
+      .accountsPartial({
+        authority: authority.publicKey,
+        receiptState: market.receiptState,
+        tokenMint: market.tokenMint,
+        tokenMintVault: market.tokenMintVault,
+        insuranceVault,
+        vaultAuthority: market.vaultAuthority,
+        tokenMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc();
+
+  const balance = async (account: PublicKey) => Number((await getAccount(provider.connection, account)).amount);
+
+  before(async () => {
+    market = await createMarket(program, payer);
+    await fundUser(program, payer, market, payer.publicKey, 10_000_000);
+    feeReceiver = (await getOrCreateAssociatedTokenAccount(provider.connection, payer, market.tokenMint, treasury))
+      .address;
+    insuranceVault = getInsuranceVaultPDA(market.receiptState, program.programId);
+
+    await program.methods
+      .setFeeConfig(100, 0, feeReceiver)
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+  });
+
+  it("needs an insurance vault before taking a fee share", async () => {
+    await expectError(
+      program.methods
+        .setInsuranceFeeShare(2_500)
+        .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+        .rpc(),
+      "InvalidInsuranceVault"
+    );
+  });
+
+  it("creates the insurance vault", async () => {
+    await program.methods
+      .initInsuranceVault()
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        tokenMint: market.tokenMint,
+        insuranceVault,
+        vaultAuthority: market.vaultAuthority,
+        tokenMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    await expectError(
+      program.methods
+        .setInsuranceFeeShare(10_001)
+        .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+        .rpc(),
+      "InvalidBasisPoints"
+    );
+    await program.methods
+      .setInsuranceFeeShare(2_500)
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.isTrue(state.insuranceVault.equals(insuranceVault));
+    assert.equal(state.insuranceFeeShareBps, 2_500);
+  });
+
+  it("requires the insurance vault while a fee share is set", async () => {
+    await expectError(depositWithFee(1_000_000, false), "InvalidInsuranceVault");
+  });
+
+  it("pays the insurance cut of the deposit fee", async () => {
+    await depositWithFee(1_000_000);
+
+    assert.equal(await balance(insuranceVault), 2_500);
+    assert.equal(await balance(feeReceiver), 7_500);
+    assert.equal(await balance(market.tokenMintVault), 990_000);
+  });
+
+  it("accepts contributions from anyone", async () => {
+    const funder = Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(funder.publicKey, LAMPORTS_PER_SOL)
+    );
+    const { userMintTokenAccount } = await fundUser(program, payer, market, funder.publicKey, 100_000);
+    await program.methods
+      .fundInsurance(new BN(100_000))
+      .accountsPartial({
+        funder: funder.publicKey,
+        funderTokenAccount: userMintTokenAccount,
+        receiptState: market.receiptState,
+        tokenMint: market.tokenMint,
+        insuranceVault,
+        tokenMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([funder])
+      .rpc();
+
+    assert.equal(await balance(insuranceVault), 102_500);
+  });
+
+  it("only lets the authority cover losses", async () => {
+    const stranger = Keypair.generate();
+    await expectError(coverLoss(50_000, stranger), "ConstraintHasOne");
+    await expectError(coverLoss(102_501), "InsufficientLiquidity");
+  });
+
+  it("moves insurance funds into the vault to cover a loss", async () => {
+    const vaultBefore = await balance(market.tokenMintVault);
+    await coverLoss(50_000);
+
+    assert.equal((await balance(market.tokenMintVault)) - vaultBefore, 50_000);
+    assert.equal(await balance(insuranceVault), 52_500);
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.equal(state.insuranceLossesCovered.toNumber(), 50_000);
+  });
+});
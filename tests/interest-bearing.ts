@@ -0,0 +1,62 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  getInterestBearingMintConfigState,
+  getMint,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import { createMarket, deposit, expectError, fundUser, Market } from "./utils";
+
+describe("interest-bearing receipts", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  let market: Market;
+
+  before(async () => {
+    market = await createMarket(program, payer, 9, TOKEN_2022_PROGRAM_ID, { interestBearing: true });
+    await fundUser(program, payer, market, payer.publicKey, 10_000_000);
+    await deposit(program, market, payer, 1_000_000);
+  });
+
+  it("initializes the receipt mint with a zero rate held by the vault authority", async () => {
+    const mint = await getMint(
+      provider.connection,
+      market.cryptoReceiptMint,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const config = getInterestBearingMintConfigState(mint);
+    assert.ok(config);
+    assert.ok(config.rateAuthority.equals(market.vaultAuthority));
+    assert.equal(config.currentRate, 0);
+
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.equal(state.interestLastExchangeRate.toString(), "1000000000000");
+  });
+
+  it("rate limits keeper updates", async () => {
+    await expectError(
+      program.methods
+        .updateInterestRate()
+        .accountsPartial({
+          receiptState: market.receiptState,
+          tokenMintVault: market.tokenMintVault,
+          cryptoReceiptMint: market.cryptoReceiptMint,
+        })
+        .rpc(),
+      "InterestRateUpdateTooSoon"
+    );
+  });
+
+  it("rejects interest-bearing classic spl token receipt mints", async () => {
+    await expectError(
+      createMarket(program, payer, 9, TOKEN_PROGRAM_ID, { interestBearing: true }),
+      "InterestBearingNotSupported"
+    );
+  });
+});
@@ -0,0 +1,372 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import {
+  createMarket,
+  deposit,
+  expectError,
+  fundUser,
+  getLendingReservePDA,
+  getObligationPDA,
+  getReserveCollateralPDA,
+  getReserveLiquidityPDA,
+  Market,
+} from "./utils";
+
+describe("lending", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  let market: Market;
+  let mintAccount: PublicKey;
+  let receiptAccount: PublicKey;
+  let reserve: PublicKey;
+  let obligation: PublicKey;
+
+  const lendingMarket = () => ({
+    receiptState: market.receiptState,
+    reserve,
+    liquidityVault: getReserveLiquidityPDA(reserve, program.programId),
+    collateralVault: getReserveCollateralPDA(reserve, program.programId),
+    borrowMint: market.tokenMint,
+    tokenMintVault: market.tokenMintVault,
+    cryptoReceiptMint: market.cryptoReceiptMint,
+    oracle: null,
+    vaultAuthority: market.vaultAuthority,
+    borrowMintProgram: TOKEN_PROGRAM_ID,
+    cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+  });
+
+  const borrowAccounts = () => ({
+    market: lendingMarket(),
+    owner: payer.publicKey,
+    obligation,
+    ownerBorrowTokenAccount: mintAccount,
+  });
+
+  const health = () =>
+    program.methods.getObligationHealth().accountsPartial({ market: lendingMarket(), obligation }).view();
+
+  const closeReserve = () =>
+    program.methods
+      .closeLendingReserve()
+      .accountsPartial({
+        authority: payer.publicKey,
+        recipient: payer.publicKey,
+        receiptState: market.receiptState,
+        reserve,
+        liquidityVault: getReserveLiquidityPDA(reserve, program.programId),
+        collateralVault: getReserveCollateralPDA(reserve, program.programId),
+        borrowMintProgram: TOKEN_PROGRAM_ID,
+        cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+  before(async () => {
+    market = await createMarket(program, payer);
+    ({ userMintTokenAccount: mintAccount, userCryptoReceiptTokenAccount: receiptAccount } = await fundUser(
+      program,
+      payer,
+      market,
+      payer.publicKey,
+      3_000_000
+    ));
+    await deposit(program, market, payer, 1_000_000);
+
+    reserve = getLendingReservePDA(market.receiptState, market.tokenMint, program.programId);
+    obligation = getObligationPDA(reserve, payer.publicKey, program.programId);
+    await program.methods
+      .initLendingReserve(7_500, 8_500, 500, 200, 2_000)
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        borrowMint: market.tokenMint,
+        reserve,
+        liquidityVault: getReserveLiquidityPDA(reserve, program.programId),
+        collateralVault: getReserveCollateralPDA(reserve, program.programId),
+        vaultAuthority: market.vaultAuthority,
+        cryptoReceiptMint: market.cryptoReceiptMint,
+        oracle: null,
+        borrowMintProgram: TOKEN_PROGRAM_ID,
+        cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    await program.methods
+      .supplyReserveLiquidity(new BN(1_000_000))
+      .accountsPartial({ market: lendingMarket(), authority: payer.publicKey, authorityTokenAccount: mintAccount })
+      .rpc();
+  });
+
+  it("rejects a liquidation threshold below the LTV", async () => {
+    const other = await createMarket(program, payer);
+    const otherReserve = getLendingReservePDA(other.receiptState, other.tokenMint, program.programId);
+    await expectError(
+      program.methods
+        .initLendingReserve(8_000, 7_000, 500, 200, 2_000)
+        .accountsPartial({
+          authority: payer.publicKey,
+          receiptState: other.receiptState,
+          borrowMint: other.tokenMint,
+          reserve: otherReserve,
+          liquidityVault: getReserveLiquidityPDA(otherReserve, program.programId),
+          collateralVault: getReserveCollateralPDA(otherReserve, program.programId),
+          vaultAuthority: other.vaultAuthority,
+          cryptoReceiptMint: other.cryptoReceiptMint,
+          oracle: null,
+          borrowMintProgram: TOKEN_PROGRAM_ID,
+          cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc(),
+      "InvalidLendingConfig"
+    );
+  });
+
+  it("locks receipts as collateral", async () => {
+    await program.methods
+      .depositCollateral(new BN(400_000))
+      .accountsPartial({
+        market: lendingMarket(),
+        owner: payer.publicKey,
+        obligation,
+        ownerCryptoReceiptTokenAccount: receiptAccount,
+      })
+      .rpc();
+
+    const account = await program.account.obligation.fetch(obligation);
+    assert.equal(account.collateral.toNumber(), 400_000);
+    const vault = await getAccount(
+      provider.connection,
+      getReserveCollateralPDA(reserve, program.programId),
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(Number(vault.amount), 400_000);
+  });
+
+  it("borrows up to the LTV", async () => {
+    await expectError(
+      program.methods.borrow(new BN(400_000)).accountsPartial(borrowAccounts()).rpc(),
+      "BorrowLimitExceeded"
+    );
+    await program.methods.borrow(new BN(200_000)).accountsPartial(borrowAccounts()).rpc();
+
+    const view = await health();
+    assert.isAbove(view.collateralValue.toNumber(), view.debt.toNumber());
+    assert.isAtLeast(view.debt.toNumber(), 200_000);
+    assert.isAbove(view.healthFactorBps.toNumber(), 10_000);
+  });
+
+  it("queries health without write locking the market", async () => {
+    const ix = await program.methods
+      .getObligationHealth()
+      .accountsPartial({ market: lendingMarket(), obligation })
+      .instruction();
+    assert.isTrue(ix.keys.every((key) => !key.isWritable));
+  });
+
+  it("keeps collateral locked that backs the debt", async () => {
+    await expectError(
+      program.methods
+        .withdrawCollateral(new BN(200_000))
+        .accountsPartial({
+          market: lendingMarket(),
+          owner: payer.publicKey,
+          obligation,
+          ownerCryptoReceiptTokenAccount: receiptAccount,
+        })
+        .rpc(),
+      "BorrowLimitExceeded"
+    );
+  });
+
+  it("refuses to liquidate a healthy obligation", async () => {
+    await expectError(
+      program.methods
+        .liquidate(new BN(50_000))
+        .accountsPartial({
+          market: lendingMarket(),
+          liquidator: payer.publicKey,
+          obligation,
+          liquidatorBorrowTokenAccount: mintAccount,
+          liquidatorCryptoReceiptTokenAccount: receiptAccount,
+        })
+        .rpc(),
+      "ObligationHealthy"
+    );
+  });
+
+  it("refuses to close a reserve with debt", async () => {
+    await expectError(closeReserve(), "ReserveNotEmpty");
+  });
+
+  it("repays the debt in full and releases the collateral", async () => {
+    await program.methods
+      .repay(new BN(1_000_000))
+      .accountsPartial({
+        market: lendingMarket(),
+        payer: payer.publicKey,
+        obligation,
+        payerTokenAccount: mintAccount,
+      })
+      .rpc();
+    assert.equal((await program.account.obligation.fetch(obligation)).borrowShares.toNumber(), 0);
+
+    await program.methods
+      .withdrawCollateral(new BN(400_000))
+      .accountsPartial({
+        market: lendingMarket(),
+        owner: payer.publicKey,
+        obligation,
+        ownerCryptoReceiptTokenAccount: receiptAccount,
+      })
+      .rpc();
+    assert.equal((await program.account.lendingReserve.fetch(reserve)).totalCollateral.toNumber(), 0);
+  });
+
+  it("closes the reserve once its liquidity is withdrawn", async () => {
+    const liquidity = await getAccount(provider.connection, getReserveLiquidityPDA(reserve, program.programId));
+    await program.methods
+      .withdrawReserveLiquidity(new BN(liquidity.amount.toString()))
+      .accountsPartial({ market: lendingMarket(), authority: payer.publicKey, receiverTokenAccount: mintAccount })
+      .rpc();
+    await closeReserve();
+
+    assert.isNull(await provider.connection.getAccountInfo(reserve));
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.equal(state.openReserveCount, 0);
+  });
+});
+
+describe("lending liquidations", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  // Large amounts so a few seconds of interest move the debt by whole units
+  const COLLATERAL = 20_000_000_000;
+  let market: Market;
+  let mintAccount: PublicKey;
+  let receiptAccount: PublicKey;
+  let reserve: PublicKey;
+  let obligation: PublicKey;
+
+  const lendingMarket = () => ({
+    receiptState: market.receiptState,
+    reserve,
+    liquidityVault: getReserveLiquidityPDA(reserve, program.programId),
+    collateralVault: getReserveCollateralPDA(reserve, program.programId),
+    borrowMint: market.tokenMint,
+    tokenMintVault: market.tokenMintVault,
+    cryptoReceiptMint: market.cryptoReceiptMint,
+    oracle: null,
+    vaultAuthority: market.vaultAuthority,
+    borrowMintProgram: TOKEN_PROGRAM_ID,
+    cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+  });
+
+  const health = () =>
+    program.methods.getObligationHealth().accountsPartial({ market: lendingMarket(), obligation }).view();
+
+  const liquidate = (amount: number) =>
+    program.methods
+      .liquidate(new BN(amount))
+      .accountsPartial({
+        market: lendingMarket(),
+        liquidator: payer.publicKey,
+        obligation,
+        liquidatorBorrowTokenAccount: mintAccount,
+        liquidatorCryptoReceiptTokenAccount: receiptAccount,
+      })
+      .rpc();
+
+  const receiptBalance = async () =>
+    Number((await getAccount(provider.connection, receiptAccount, undefined, TOKEN_2022_PROGRAM_ID)).amount);
+
+  before(async () => {
+    market = await createMarket(program, payer);
+    ({ userMintTokenAccount: mintAccount, userCryptoReceiptTokenAccount: receiptAccount } = await fundUser(
+      program,
+      payer,
+      market,
+      payer.publicKey,
+      4 * COLLATERAL
+    ));
+    await deposit(program, market, payer, 2 * COLLATERAL);
+
+    // Liquidation threshold at the LTV, so a loan taken at the LTV turns
+    // liquidatable as soon as interest accrues. 500% a year at any utilization
+    reserve = getLendingReservePDA(market.receiptState, market.tokenMint, program.programId);
+    obligation = getObligationPDA(reserve, payer.publicKey, program.programId);
+    await program.methods
+      .initLendingReserve(8_000, 8_000, 500, 50_000, 0)
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        borrowMint: market.tokenMint,
+        reserve,
+        liquidityVault: getReserveLiquidityPDA(reserve, program.programId),
+        collateralVault: getReserveCollateralPDA(reserve, program.programId),
+        vaultAuthority: market.vaultAuthority,
+        cryptoReceiptMint: market.cryptoReceiptMint,
+        oracle: null,
+        borrowMintProgram: TOKEN_PROGRAM_ID,
+        cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+    await program.methods
+      .supplyReserveLiquidity(new BN(COLLATERAL))
+      .accountsPartial({ market: lendingMarket(), authority: payer.publicKey, authorityTokenAccount: mintAccount })
+      .rpc();
+    await program.methods
+      .depositCollateral(new BN(COLLATERAL))
+      .accountsPartial({
+        market: lendingMarket(),
+        owner: payer.publicKey,
+        obligation,
+        ownerCryptoReceiptTokenAccount: receiptAccount,
+      })
+      .rpc();
+    // Just under the LTV, leaving room for the rounding of the borrow shares
+    await program.methods
+      .borrow(new BN((COLLATERAL * 8) / 10 - 10))
+      .accountsPartial({ market: lendingMarket(), owner: payer.publicKey, obligation, ownerBorrowTokenAccount: mintAccount })
+      .rpc();
+  });
+
+  it("accrues interest on the debt over time", async () => {
+    const before = await health();
+    await new Promise((resolve) => setTimeout(resolve, 3_000));
+    const after = await health();
+    assert.isAbove(after.debt.toNumber(), before.debt.toNumber());
+    assert.equal(after.collateralValue.toNumber(), before.collateralValue.toNumber());
+  });
+
+  it("turns the obligation liquidatable once its health factor drops below one", async () => {
+    const view = await health();
+    assert.isBelow(view.healthFactorBps.toNumber(), 10_000);
+  });
+
+  it("limits a liquidation to the close factor", async () => {
+    const { debt } = await health();
+    await expectError(liquidate(Math.ceil(debt.toNumber() / 2) + 1_000_000), "LiquidationTooLarge");
+  });
+
+  it("seizes the collateral worth the repayment plus the bonus", async () => {
+    const repay = 1_000_000;
+    const before = await program.account.obligation.fetch(obligation);
+    const balanceBefore = await receiptBalance();
+    await liquidate(repay);
+
+    // One receipt is worth one underlying, nothing has accrued to the vault
+    const seized = (repay * 10_500) / 10_000;
+    assert.equal((await receiptBalance()) - balanceBefore, seized);
+    const after = await program.account.obligation.fetch(obligation);
+    assert.equal(before.collateral.toNumber() - after.collateral.toNumber(), seized);
+    assert.isBelow(after.borrowShares.toNumber(), before.borrowShares.toNumber());
+  });
+});
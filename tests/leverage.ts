@@ -0,0 +1,174 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, getAccount, getOrCreateAssociatedTokenAccount } from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import {
+  createMarket,
+  deposit,
+  expectError,
+  fundUser,
+  getLendingReservePDA,
+  getObligationPDA,
+  getReserveCollateralPDA,
+  getReserveLiquidityPDA,
+  Market,
+} from "./utils";
+
+describe("leverage", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  let market: Market;
+  let mintAccount: PublicKey;
+  let reserve: PublicKey;
+  let obligation: PublicKey;
+
+  const lendingMarket = () => ({
+    receiptState: market.receiptState,
+    reserve,
+    liquidityVault: getReserveLiquidityPDA(reserve, program.programId),
+    collateralVault: getReserveCollateralPDA(reserve, program.programId),
+    borrowMint: market.tokenMint,
+    tokenMintVault: market.tokenMintVault,
+    cryptoReceiptMint: market.cryptoReceiptMint,
+    oracle: null,
+    vaultAuthority: market.vaultAuthority,
+    borrowMintProgram: TOKEN_PROGRAM_ID,
+    cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+  });
+
+  const leverageDeposit = (amount: number, leverageBps: number, minCollateral = 0) =>
+    program.methods
+      .leverageDeposit(new BN(amount), new BN(leverageBps), new BN(minCollateral))
+      .accountsPartial({
+        market: lendingMarket(),
+        owner: payer.publicKey,
+        obligation,
+        ownerMintTokenAccount: mintAccount,
+        feeReceiver: null,
+        insuranceVault: null,
+      })
+      .rpc();
+
+  const deleverage = (receipts: number, minOut = 0) =>
+    program.methods
+      .deleverage(new BN(receipts), new BN(minOut))
+      .accountsPartial({
+        market: lendingMarket(),
+        owner: payer.publicKey,
+        obligation,
+        ownerMintTokenAccount: mintAccount,
+      })
+      .rpc();
+
+  const mintBalance = async () => Number((await getAccount(provider.connection, mintAccount)).amount);
+
+  before(async () => {
+    market = await createMarket(program, payer);
+    ({ userMintTokenAccount: mintAccount } = await fundUser(program, payer, market, payer.publicKey, 4_000_000));
+    await deposit(program, market, payer, 1_000_000);
+
+    reserve = getLendingReservePDA(market.receiptState, market.tokenMint, program.programId);
+    obligation = getObligationPDA(reserve, payer.publicKey, program.programId);
+    // 75% LTV caps leverage at 4x
+    await program.methods
+      .initLendingReserve(7_500, 8_500, 500, 200, 2_000)
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        borrowMint: market.tokenMint,
+        reserve,
+        liquidityVault: getReserveLiquidityPDA(reserve, program.programId),
+        collateralVault: getReserveCollateralPDA(reserve, program.programId),
+        vaultAuthority: market.vaultAuthority,
+        cryptoReceiptMint: market.cryptoReceiptMint,
+        oracle: null,
+        borrowMintProgram: TOKEN_PROGRAM_ID,
+        cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+    await program.methods
+      .supplyReserveLiquidity(new BN(2_000_000))
+      .accountsPartial({ market: lendingMarket(), authority: payer.publicKey, authorityTokenAccount: mintAccount })
+      .rpc();
+  });
+
+  it("rejects leverage above what the LTV allows", async () => {
+    await expectError(leverageDeposit(100_000, 40_001), "InvalidLeverage");
+    await expectError(leverageDeposit(100_000, 9_999), "InvalidLeverage");
+  });
+
+  it("enforces the minimum collateral", async () => {
+    await expectError(leverageDeposit(100_000, 30_000, 300_001), "SlippageExceeded");
+  });
+
+  it("opens a 3x position in one instruction", async () => {
+    const before = await mintBalance();
+    await leverageDeposit(100_000, 30_000, 299_000);
+
+    assert.equal(before - (await mintBalance()), 100_000);
+    const account = await program.account.obligation.fetch(obligation);
+    assert.approximately(account.collateral.toNumber(), 300_000, 1);
+    const view = await program.methods
+      .getObligationHealth()
+      .accountsPartial({ market: lendingMarket(), obligation })
+      .view();
+    assert.approximately(view.debt.toNumber(), 200_000, 1);
+    const collateralVault = await getAccount(
+      provider.connection,
+      getReserveCollateralPDA(reserve, program.programId),
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(Number(collateralVault.amount), account.collateral.toNumber());
+  });
+
+  it("enforces the minimum underlying out when unwinding", async () => {
+    await expectError(deleverage(300_000, 100_001), "SlippageExceeded");
+  });
+
+  it("unwinds the position, repaying the debt first", async () => {
+    const collateral = (await program.account.obligation.fetch(obligation)).collateral.toNumber();
+    const before = await mintBalance();
+    await deleverage(collateral, 99_000);
+
+    const account = await program.account.obligation.fetch(obligation);
+    assert.equal(account.collateral.toNumber(), 0);
+    assert.equal(account.borrowShares.toNumber(), 0);
+    assert.isAtLeast((await mintBalance()) - before, 99_000);
+  });
+
+  it("lowers the leverage cap by the deposit fee", async () => {
+    const feeReceiver = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, payer, market.tokenMint, Keypair.generate().publicKey)
+    ).address;
+    await program.methods
+      .setFeeConfig(100, 0, feeReceiver)
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+    const leverageWithFee = (leverageBps: number) =>
+      program.methods
+        .leverageDeposit(new BN(100_000), new BN(leverageBps), new BN(0))
+        .accountsPartial({
+          market: lendingMarket(),
+          owner: payer.publicKey,
+          obligation,
+          ownerMintTokenAccount: mintAccount,
+          feeReceiver,
+          insuranceVault: null,
+        })
+        .rpc();
+
+    // 1% of the position leaves 75% of 99% as LTV, capping leverage near 3.88x
+    await expectError(leverageWithFee(40_000), "InvalidLeverage");
+    await leverageWithFee(38_800);
+    const view = await program.methods
+      .getObligationHealth()
+      .accountsPartial({ market: lendingMarket(), obligation })
+      .view();
+    assert.isAtMost(view.debt.toNumber() * 10_000, view.collateralValue.toNumber() * 7_500);
+  });
+});
@@ -0,0 +1,31 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, getMint } from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import { createMarket, getMetaplexMetadataPDA, TOKEN_METADATA_PROGRAM_ID } from "./utils";
+
+// The Metaplex token metadata program is cloned into the local validator, see Anchor.toml.
+describe("receipt mint metadata", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+
+  it("embeds metadata in Token-2022 receipt mints", async () => {
+    const market = await createMarket(program, payer, 9, TOKEN_2022_PROGRAM_ID);
+    const mint = await provider.connection.getAccountInfo(market.cryptoReceiptMint);
+    assert.ok(mint.owner.equals(TOKEN_2022_PROGRAM_ID));
+    assert.isNull(await provider.connection.getAccountInfo(getMetaplexMetadataPDA(market.cryptoReceiptMint)));
+  });
+
+  it("creates Metaplex metadata for classic spl token receipt mints", async () => {
+    const market = await createMarket(program, payer, 6, TOKEN_PROGRAM_ID);
+    const mint = await getMint(provider.connection, market.cryptoReceiptMint, undefined, TOKEN_PROGRAM_ID);
+    assert.equal(mint.decimals, 6);
+    assert.ok(mint.mintAuthority.equals(market.vaultAuthority));
+
+    const metadata = await provider.connection.getAccountInfo(getMetaplexMetadataPDA(market.cryptoReceiptMint));
+    assert.ok(metadata.owner.equals(TOKEN_METADATA_PROGRAM_ID));
+  });
+});
@@ -0,0 +1,70 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import v0Authority from "./fixtures/v0-authority.json";
+import { createMarket, expectError } from "./utils";
+
+// A receipt state written with the pre-versioning layout, loaded into the
+// local validator from tests/fixtures/receipt_state_v0.json.
+const RECEIPT_STATE_V0 = new PublicKey("XA1DEAXAuDPa78FR3s1EiiyfTdKZthkJ6A3whaoYdPV");
+const V0_TOKEN_MINT = new PublicKey("4Wcu67GxPQ4REYQvWC83uhYkzri6LURQYL3sQpMVP72m");
+const V0_LEN = 173;
+
+describe("receipt state migration", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const authority = Keypair.fromSecretKey(Uint8Array.from(v0Authority));
+
+  const migrate = (receiptState: PublicKey, signer: Keypair) =>
+    program.methods
+      .migrateReceiptState()
+      .accountsPartial({ authority: signer.publicKey, receiptState })
+      .signers([signer])
+      .rpc();
+
+  before(async () => {
+    const sig = await provider.connection.requestAirdrop(authority.publicKey, LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(sig);
+  });
+
+  it("cannot deserialize the v0 layout before migrating", async () => {
+    const info = await provider.connection.getAccountInfo(RECEIPT_STATE_V0);
+    assert.equal(info.data.length, V0_LEN);
+    const decoded = await program.account.receiptState
+      .fetch(RECEIPT_STATE_V0)
+      .then(() => true)
+      .catch(() => false);
+    assert.isFalse(decoded);
+  });
+
+  it("rejects a signer other than the stored authority", async () => {
+    await expectError(migrate(RECEIPT_STATE_V0, provider.wallet.payer), "ConstraintHasOne");
+  });
+
+  it("reallocs a v0 account and fills defaults", async () => {
+    await migrate(RECEIPT_STATE_V0, authority);
+
+    const state = await program.account.receiptState.fetch(RECEIPT_STATE_V0);
+    assert.equal(state.version, 1);
+    assert.ok(state.authority.equals(authority.publicKey));
+    assert.ok(state.tokenMint.equals(V0_TOKEN_MINT));
+    assert.equal(state.bump, 254);
+    assert.equal(state.receiptMintVaultBump, 251);
+    assert.isFalse(state.flashLoanEnabled);
+    assert.ok(state.oracle.oracle.equals(PublicKey.default));
+  });
+
+  it("rejects migrating twice", async () => {
+    await expectError(migrate(RECEIPT_STATE_V0, authority), "AlreadyMigrated");
+  });
+
+  it("creates new markets at the current version", async () => {
+    const market = await createMarket(program, provider.wallet.payer);
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.equal(state.version, 1);
+    await expectError(migrate(market.receiptState, provider.wallet.payer), "AlreadyMigrated");
+  });
+});
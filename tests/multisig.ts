@@ -0,0 +1,122 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, TransactionInstruction } from "@solana/web3.js";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import { createMarket, expectError, Market } from "./utils";
+
+describe("multisig authority", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  const [alice, bob, carol] = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const feeReceiver = Keypair.generate().publicKey;
+  const createKey = Keypair.generate().publicKey;
+  const [multisig] = PublicKey.findProgramAddressSync(
+    [Buffer.from("multisig"), createKey.toBuffer()],
+    program.programId
+  );
+  const [multisigSigner] = PublicKey.findProgramAddressSync(
+    [Buffer.from("multisig_signer"), multisig.toBuffer()],
+    program.programId
+  );
+  let market: Market;
+
+  const transactionPDA = (index: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("multisig_transaction"), multisig.toBuffer(), new BN(index).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  const propose = async (index: number, proposer: Keypair, ix: TransactionInstruction) => {
+    await program.methods
+      .createMultisigTransaction(
+        ix.programId,
+        ix.keys.map(({ pubkey, isWritable }) => ({ pubkey, isSigner: false, isWritable })),
+        ix.data
+      )
+      .accountsPartial({ proposer: proposer.publicKey, multisig, transaction: transactionPDA(index) })
+      .signers([proposer])
+      .rpc();
+  };
+
+  const approve = (index: number, signer: Keypair) =>
+    program.methods
+      .approveMultisigTransaction()
+      .accountsPartial({ signer: signer.publicKey, multisig, transaction: transactionPDA(index) })
+      .signers([signer])
+      .rpc();
+
+  const execute = (index: number, proposer: Keypair, ix: TransactionInstruction) =>
+    program.methods
+      .executeMultisigTransaction()
+      .accountsPartial({ multisig, transaction: transactionPDA(index), proposer: proposer.publicKey })
+      .remainingAccounts([
+        ...ix.keys.map((key) => (key.pubkey.equals(multisigSigner) ? { ...key, isSigner: false } : key)),
+        { pubkey: ix.programId, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+
+  before(async () => {
+    for (const signer of [alice, bob, carol]) {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(signer.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+      );
+    }
+    await program.methods
+      .createMultisig([alice.publicKey, bob.publicKey, carol.publicKey], 2)
+      .accountsPartial({ payer: payer.publicKey, createKey, multisig })
+      .rpc();
+
+    market = await createMarket(program, payer);
+    await program.methods
+      .setAuthority(multisigSigner)
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+  });
+
+  it("executes admin changes once the threshold approves", async () => {
+    const ix = await program.methods
+      .setFeeConfig(50, 0, feeReceiver)
+      .accountsPartial({ authority: multisigSigner, receiptState: market.receiptState })
+      .instruction();
+    await propose(0, alice, ix);
+    await expectError(execute(0, alice, ix), "NotEnoughApprovals");
+
+    await approve(0, bob);
+    const proposerBalance = await provider.connection.getBalance(alice.publicKey);
+    await execute(0, alice, ix);
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.equal(state.depositFeeBps, 50);
+    assert.isNull(await provider.connection.getAccountInfo(transactionPDA(0)));
+    assert.isAbove(await provider.connection.getBalance(alice.publicKey), proposerBalance);
+    await expectError(execute(0, alice, ix), "AccountNotInitialized");
+  });
+
+  it("only lets signers propose and approve", async () => {
+    const stranger = Keypair.generate();
+    await expectError(approve(0, stranger), "AccountNotInitialized");
+    const ix = await program.methods
+      .setSwapProgram(feeReceiver)
+      .accountsPartial({ authority: multisigSigner, receiptState: market.receiptState })
+      .instruction();
+    await propose(1, carol, ix);
+    await expectError(approve(1, stranger), "NotMultisigSigner");
+  });
+
+  it("changes its own signer set and invalidates pending transactions", async () => {
+    const ix = await program.methods
+      .setMultisigConfig([alice.publicKey, bob.publicKey], 2)
+      .accountsPartial({ multisig, multisigSigner })
+      .instruction();
+    await propose(2, alice, ix);
+    await approve(2, carol);
+    await execute(2, alice, ix);
+
+    const account = await program.account.multisig.fetch(multisig);
+    assert.equal(account.signers.length, 2);
+    assert.equal(account.ownerSetSeqno, 1);
+    await expectError(approve(1, bob), "StaleMultisigTransaction");
+  });
+});
@@ -0,0 +1,79 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import { createMarket, deposit, expectError, fundUser, Market } from "./utils";
+
+// Pyth v2 price accounts loaded into the local validator from tests/fixtures.
+// All publish $150.00 (expo -8), the first two at unix time 1_700_000_000 and
+// the last at 4_000_000_000.
+const PYTH_PRICE = new PublicKey("3QAf4oYe8xV8X17jtF2RKvxc1CMo8PzruS38zRnwZcRc");
+const PYTH_PRICE_WIDE_CONF = new PublicKey("2ksTKiXuKFqPgLZdGHu4UkRWg6dcrdqJhwfTjH1mTW8T");
+const PYTH_PRICE_FUTURE = new PublicKey("4qkPfELDEL8NkDBAP2nvDdhHtJ9q8ZDjKJhzmKZ2NJwo");
+const TEN_YEARS = new BN(10 * 365 * 24 * 60 * 60);
+
+describe("oracle pricing", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const payer = (program.provider as anchor.AnchorProvider).wallet.payer;
+  let market: Market;
+
+  const setOracle = (oracle: PublicKey, maxStaleness: BN, maxConfidenceBps: number) =>
+    program.methods
+      .setOracleConfig({ oracle, maxStaleness, maxConfidenceBps })
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+
+  const getPrice = (oracle: PublicKey) =>
+    program.methods
+      .getReceiptPrice()
+      .accountsPartial({
+        receiptState: market.receiptState,
+        tokenMintVault: market.tokenMintVault,
+        cryptoReceiptMint: market.cryptoReceiptMint,
+        oracle,
+      })
+      .view();
+
+  before(async () => {
+    market = await createMarket(program, payer);
+  });
+
+  it("rejects pricing before an oracle is configured", async () => {
+    await expectError(getPrice(PYTH_PRICE), "OracleNotConfigured");
+  });
+
+  it("prices an empty market at the underlying price", async () => {
+    await setOracle(PYTH_PRICE, TEN_YEARS, 100);
+    const price = await getPrice(PYTH_PRICE);
+    assert.equal(price.price.toString(), "15000000000");
+    assert.equal(price.expo, -8);
+  });
+
+  it("prices receipts by the vault exchange rate", async () => {
+    await fundUser(program, payer, market, payer.publicKey, 1_000_000_000);
+    await deposit(program, market, payer, 1_000_000_000);
+    const price = await getPrice(PYTH_PRICE);
+    assert.equal(price.price.toString(), "15000000000");
+  });
+
+  it("rejects a different oracle account", async () => {
+    await expectError(getPrice(PYTH_PRICE_WIDE_CONF), "InvalidOracle");
+  });
+
+  it("rejects stale prices", async () => {
+    await setOracle(PYTH_PRICE, new BN(60), 100);
+    await expectError(getPrice(PYTH_PRICE), "StaleOracle");
+  });
+
+  it("rejects prices published ahead of the clock", async () => {
+    await setOracle(PYTH_PRICE_FUTURE, TEN_YEARS, 100);
+    await expectError(getPrice(PYTH_PRICE_FUTURE), "OracleTimestampInFuture");
+  });
+
+  it("rejects wide confidence intervals", async () => {
+    await setOracle(PYTH_PRICE_WIDE_CONF, TEN_YEARS, 100);
+    await expectError(getPrice(PYTH_PRICE_WIDE_CONF), "OracleConfidenceTooWide");
+  });
+});
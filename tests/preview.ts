@@ -0,0 +1,111 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { TOKEN_2022_PROGRAM_ID, getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import { createMarket, deposit, fundUser, Market, redeem } from "./utils";
+
+describe("preview quotes", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  let market: Market;
+
+  const previewAccounts = () => ({
+    receiptState: market.receiptState,
+    tokenMint: market.tokenMint,
+    tokenMintVault: market.tokenMintVault,
+    cryptoReceiptMint: market.cryptoReceiptMint,
+  });
+  const receiptAccount = () =>
+    getAssociatedTokenAddressSync(market.cryptoReceiptMint, payer.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  before(async () => {
+    market = await createMarket(program, payer);
+    await fundUser(program, payer, market, payer.publicKey, 2_000_000_000);
+  });
+
+  it("quotes the receipts a deposit mints", async () => {
+    const quoted = await program.methods
+      .previewDeposit(new BN(1_000_000_000))
+      .accountsPartial(previewAccounts())
+      .view();
+    await deposit(program, market, payer, 1_000_000_000);
+    const receipts = await getAccount(provider.connection, receiptAccount(), undefined, TOKEN_2022_PROGRAM_ID);
+    assert.equal(receipts.amount.toString(), quoted.toString());
+  });
+
+  it("limits max_redeem to the owner's balance", async () => {
+    const max = await program.methods
+      .maxRedeem()
+      .accountsPartial({ preview: previewAccounts(), ownerCryptoReceiptTokenAccount: receiptAccount() })
+      .view();
+    assert.equal(max.toString(), "1000000000");
+  });
+
+  it("quotes the underlying a redemption pays", async () => {
+    const quoted = await program.methods
+      .previewRedeem(new BN(400_000_000))
+      .accountsPartial(previewAccounts())
+      .view();
+    const userMintTokenAccount = getAssociatedTokenAddressSync(market.tokenMint, payer.publicKey);
+    const before = await getAccount(provider.connection, userMintTokenAccount);
+    await redeem(program, market, payer, 400_000_000);
+    const after = await getAccount(provider.connection, userMintTokenAccount);
+    assert.equal((after.amount - before.amount).toString(), quoted.toString());
+  });
+
+  it("caps max_deposit and max_redeem by the flow limits", async () => {
+    await program.methods
+      .setFlowLimits(new BN(100_000), 5_000, 1_000)
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+    await program.methods
+      .resetFlowLimits()
+      .accountsPartial({
+        guardian: payer.publicKey,
+        receiptState: market.receiptState,
+        tokenMintVault: market.tokenMintVault,
+      })
+      .rpc();
+    const vault = Number((await getAccount(provider.connection, market.tokenMintVault)).amount);
+
+    const maxDeposit = await program.methods.maxDeposit().accountsPartial(previewAccounts()).view();
+    assert.equal(maxDeposit.toNumber(), Math.floor(vault / 2));
+    await deposit(program, market, payer, 1_000_000);
+    const inflow = Number((await getAccount(provider.connection, market.tokenMintVault)).amount) - vault;
+    const remaining = await program.methods.maxDeposit().accountsPartial(previewAccounts()).view();
+    assert.equal(remaining.toNumber(), Math.floor(vault / 2) - inflow);
+
+    const balance = Number(
+      (await getAccount(provider.connection, receiptAccount(), undefined, TOKEN_2022_PROGRAM_ID)).amount
+    );
+    const maxRedeem = await program.methods
+      .maxRedeem()
+      .accountsPartial({ preview: previewAccounts(), ownerCryptoReceiptTokenAccount: receiptAccount() })
+      .view();
+    assert.isAbove(maxRedeem.toNumber(), 0);
+    assert.isBelow(maxRedeem.toNumber(), balance);
+  });
+
+  it("returns zero once the market shuts down", async () => {
+    await program.methods
+      .emergencyShutdown()
+      .accountsPartial({
+        guardian: payer.publicKey,
+        receiptState: market.receiptState,
+        tokenMintVault: market.tokenMintVault,
+        cryptoReceiptMint: market.cryptoReceiptMint,
+        stakePoolVault: null,
+      })
+      .rpc();
+    const maxDeposit = await program.methods.maxDeposit().accountsPartial(previewAccounts()).view();
+    const maxRedeem = await program.methods
+      .maxRedeem()
+      .accountsPartial({ preview: previewAccounts(), ownerCryptoReceiptTokenAccount: receiptAccount() })
+      .view();
+    assert.equal(maxDeposit.toString(), "0");
+    assert.equal(maxRedeem.toString(), "0");
+  });
+});
@@ -11,7 +11,7 @@ describe("receipt_money", () => {
   it("Is initialized!", async () => {
     // Add your test here.
     const tx = await program.methods
-      .initialize("Crypto Receipt Token", "CRT", "https://example.com/token")
+      .initialize("Crypto Receipt Token", "CRT", "https://example.com/token", false, null)
       .rpc();
     console.log("Your transaction signature", tx);
   });
@@ -0,0 +1,116 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { getAccount, getOrCreateAssociatedTokenAccount } from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import {
+  createMarket,
+  depositAccounts,
+  expectError,
+  fundUser,
+  getReferralStatsPDA,
+  Market,
+} from "./utils";
+
+describe("referrals", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  const referrer = Keypair.generate();
+  const treasury = Keypair.generate().publicKey;
+  let market: Market;
+  let feeReceiver: anchor.web3.PublicKey;
+  let referrerTokenAccount: anchor.web3.PublicKey;
+
+  const referredDeposit = (amount: number, withFeeReceiver = true, recipient = payer.publicKey) =>
+    program.methods
+      .deposit(new BN(amount))
+      .accountsPartial({
+        ...depositAccounts(market, payer.publicKey, recipient),
+        feeReceiver: withFeeReceiver ? feeReceiver : null,
+        referralStats: getReferralStatsPDA(market.receiptState, referrer.publicKey, program.programId),
+        referrerTokenAccount,
+      })
+      .rpc();
+
+  before(async () => {
+    market = await createMarket(program, payer);
+    await fundUser(program, payer, market, payer.publicKey, 10_000_000);
+    const connection = provider.connection;
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(referrer.publicKey, LAMPORTS_PER_SOL)
+    );
+    feeReceiver = (await getOrCreateAssociatedTokenAccount(connection, payer, market.tokenMint, treasury)).address;
+    referrerTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(connection, payer, market.tokenMint, referrer.publicKey)
+    ).address;
+
+    await program.methods
+      .registerReferrer()
+      .accountsPartial({ referrer: referrer.publicKey, receiptState: market.receiptState })
+      .signers([referrer])
+      .rpc();
+  });
+
+  it("records referred deposits without fees", async () => {
+    await referredDeposit(1_000_000, false);
+    const stats = await program.account.referralStats.fetch(
+      getReferralStatsPDA(market.receiptState, referrer.publicKey, program.programId)
+    );
+    assert.equal(stats.totalVolume.toNumber(), 1_000_000);
+    assert.equal(stats.depositCount.toNumber(), 1);
+  });
+
+  it("requires the fee receiver while fees are enabled", async () => {
+    await program.methods
+      .setFeeConfig(100, 5_000, feeReceiver)
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+    await expectError(referredDeposit(1_000_000, false), "InvalidFeeReceiver");
+  });
+
+  it("shares the deposit fee with the referrer", async () => {
+    await referredDeposit(1_000_000);
+
+    const connection = provider.connection;
+    assert.equal((await getAccount(connection, referrerTokenAccount)).amount.toString(), "5000");
+    assert.equal((await getAccount(connection, feeReceiver)).amount.toString(), "5000");
+    assert.equal((await getAccount(connection, market.tokenMintVault)).amount.toString(), "1990000");
+
+    const stats = await program.account.referralStats.fetch(
+      getReferralStatsPDA(market.receiptState, referrer.publicKey, program.programId)
+    );
+    assert.equal(stats.totalVolume.toNumber(), 2_000_000);
+    assert.equal(stats.depositCount.toNumber(), 2);
+    assert.equal(stats.totalFeesEarned.toNumber(), 5_000);
+  });
+
+  it("rejects deposits referring their own recipient", async () => {
+    await expectError(referredDeposit(1_000_000, true, referrer.publicKey), "InvalidReferrer");
+  });
+
+  it("rejects depositors referring themselves", async () => {
+    const selfReferral = getReferralStatsPDA(market.receiptState, payer.publicKey, program.programId);
+    await program.methods
+      .registerReferrer()
+      .accountsPartial({ referrer: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+    const payerTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, payer, market.tokenMint, payer.publicKey)
+    ).address;
+    await expectError(
+      program.methods
+        .deposit(new BN(1_000_000))
+        .accountsPartial({
+          ...depositAccounts(market, payer.publicKey),
+          feeReceiver,
+          referralStats: selfReferral,
+          referrerTokenAccount: payerTokenAccount,
+        })
+        .rpc(),
+      "InvalidReferrer"
+    );
+  });
+});
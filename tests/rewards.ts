@@ -0,0 +1,373 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import { ReceiptHook } from "../target/types/receipt_hook";
+import {
+  createMarket,
+  deposit,
+  depositAccounts,
+  expectError,
+  fundUser,
+  getBridgeEscrowPDA,
+  getHookConfigPDA,
+  getRewardPoolPDA,
+  getRewardVaultPDA,
+  getStakePositionPDA,
+  getStakeVaultPDA,
+  getUserPositionPDA,
+  Market,
+  redeem,
+  transferHookAccounts,
+} from "./utils";
+
+describe("rewards", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  let market: Market;
+  let receiptAccount: PublicKey;
+  let rewardMint: PublicKey;
+  let rewardPool: PublicKey;
+  let rewardAccount: PublicKey;
+
+  const poolAccounts = () => [{ pubkey: rewardPool, isSigner: false, isWritable: true }];
+
+  const stakeAccounts = () => ({
+    owner: payer.publicKey,
+    receiptState: market.receiptState,
+    stakePosition: getStakePositionPDA(market.receiptState, payer.publicKey, program.programId),
+    ownerCryptoReceiptTokenAccount: receiptAccount,
+    stakeVault: getStakeVaultPDA(market.receiptState, program.programId),
+    cryptoReceiptMint: market.cryptoReceiptMint,
+    cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+  });
+
+  const claim = () =>
+    program.methods
+      .claimRewards()
+      .accountsPartial({
+        owner: payer.publicKey,
+        receiptState: market.receiptState,
+        stakePosition: getStakePositionPDA(market.receiptState, payer.publicKey, program.programId),
+        rewardPool,
+        rewardVault: getRewardVaultPDA(rewardPool, program.programId),
+        rewardMint,
+        ownerRewardTokenAccount: rewardAccount,
+        rewardMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+  const sweep = () =>
+    program.methods
+      .sweepUnallocatedRewards()
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        rewardPool,
+        rewardVault: getRewardVaultPDA(rewardPool, program.programId),
+        rewardMint,
+        authorityRewardTokenAccount: rewardAccount,
+        rewardMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+  const closeMarket = (feeReceiver: PublicKey | null = null) =>
+    program.methods
+      .closeMarket()
+      .accountsPartial({
+        authority: payer.publicKey,
+        recipient: payer.publicKey,
+        receiptState: market.receiptState,
+        tokenMintVault: market.tokenMintVault,
+        cryptoReceiptMint: market.cryptoReceiptMint,
+        cryptoReceiptMintVault: market.cryptoReceiptMintVault,
+        stakeVault: getStakeVaultPDA(market.receiptState, program.programId),
+        bridgeEscrow: getBridgeEscrowPDA(market.receiptState, program.programId),
+        insuranceVault: null,
+        feeReceiver,
+        tokenMint: market.tokenMint,
+        stakePoolVault: null,
+        stakePoolTokenReceiver: null,
+        poolMint: null,
+        poolMintProgram: null,
+        tokenMintProgram: TOKEN_PROGRAM_ID,
+        cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: rewardPool, isSigner: false, isWritable: true },
+        { pubkey: getRewardVaultPDA(rewardPool, program.programId), isSigner: false, isWritable: true },
+        { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+
+  const rewardBalance = async () => Number((await getAccount(provider.connection, rewardAccount)).amount);
+
+  before(async () => {
+    market = await createMarket(program, payer);
+    ({ userCryptoReceiptTokenAccount: receiptAccount } = await fundUser(
+      program,
+      payer,
+      market,
+      payer.publicKey,
+      1_000_000
+    ));
+    await deposit(program, market, payer, 1_000_000);
+
+    rewardMint = await createMint(provider.connection, payer, payer.publicKey, null, 6);
+    rewardAccount = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, payer, rewardMint, payer.publicKey)
+    ).address;
+    await mintTo(provider.connection, payer, rewardMint, rewardAccount, payer, 10_000_000);
+    rewardPool = getRewardPoolPDA(market.receiptState, rewardMint, program.programId);
+
+    await program.methods
+      .addRewardPool()
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        rewardMint,
+        rewardPool,
+        rewardVault: getRewardVaultPDA(rewardPool, program.programId),
+        cryptoReceiptMint: market.cryptoReceiptMint,
+        stakeVault: getStakeVaultPDA(market.receiptState, program.programId),
+        rewardMintProgram: TOKEN_PROGRAM_ID,
+        cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+  });
+
+  it("rejects staking without every reward pool", async () => {
+    await expectError(
+      program.methods.stake(new BN(100_000)).accountsPartial(stakeAccounts()).rpc(),
+      "InvalidRewardPool"
+    );
+  });
+
+  it("emits funded rewards to stakers", async () => {
+    await program.methods
+      .stake(new BN(500_000))
+      .accountsPartial(stakeAccounts())
+      .remainingAccounts(poolAccounts())
+      .rpc();
+
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .fundRewards(new BN(1_000_000), new BN(now + 100))
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        rewardPool,
+        rewardVault: getRewardVaultPDA(rewardPool, program.programId),
+        rewardMint,
+        authorityRewardTokenAccount: rewardAccount,
+        rewardMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    const pool = await program.account.rewardPool.fetch(rewardPool);
+    assert.isAbove(pool.emissionPerSecond.toNumber(), 0);
+
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+    const before = await rewardBalance();
+    await claim();
+    const claimed = (await rewardBalance()) - before;
+    assert.isAbove(claimed, 0);
+    assert.isAtMost(claimed, 1_000_000);
+  });
+
+  it("returns staked receipts on unstake", async () => {
+    await expectError(
+      program.methods
+        .unstake(new BN(600_000))
+        .accountsPartial(stakeAccounts())
+        .remainingAccounts(poolAccounts())
+        .rpc(),
+      "InsufficientStake"
+    );
+    await program.methods
+      .unstake(new BN(500_000))
+      .accountsPartial(stakeAccounts())
+      .remainingAccounts(poolAccounts())
+      .rpc();
+    const position = await program.account.stakePosition.fetch(
+      getStakePositionPDA(market.receiptState, payer.publicKey, program.programId)
+    );
+    assert.equal(position.staked.toNumber(), 0);
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.equal(state.totalStaked.toNumber(), 0);
+  });
+
+  it("lets the authority sweep rewards emitted while nothing is staked", async () => {
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+    const before = await rewardBalance();
+    await sweep();
+    assert.isAbove((await rewardBalance()) - before, 0);
+    const pool = await program.account.rewardPool.fetch(rewardPool);
+    assert.equal(pool.unallocatedRewards.toNumber(), 0);
+  });
+
+  it("keeps the market open while rewards are left in a vault", async () => {
+    await redeem(program, market, payer, 1_000_000);
+    await expectError(closeMarket(), "MarketNotEmpty");
+  });
+
+  it("sweeps the whole vault once emissions end with nothing staked", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .fundRewards(new BN(0), new BN(now + 2))
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        rewardPool,
+        rewardVault: getRewardVaultPDA(rewardPool, program.programId),
+        rewardMint,
+        authorityRewardTokenAccount: rewardAccount,
+        rewardMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    await new Promise((resolve) => setTimeout(resolve, 3_000));
+    // Rewards settled when the position unstaked stay claimable
+    await claim();
+    await sweep();
+    const vault = await getAccount(provider.connection, getRewardVaultPDA(rewardPool, program.programId));
+    assert.equal(Number(vault.amount), 0);
+
+    const receipts = Number((await getAccount(provider.connection, receiptAccount, undefined, TOKEN_2022_PROGRAM_ID)).amount);
+    if (receipts > 0) {
+      await redeem(program, market, payer, receipts);
+    }
+    const feeReceiver = getAssociatedTokenAddressSync(market.tokenMint, payer.publicKey);
+    await program.methods
+      .setFeeConfig(0, 0, feeReceiver)
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+    await closeMarket(feeReceiver);
+    assert.isNull(await provider.connection.getAccountInfo(rewardPool));
+    assert.isNull(await provider.connection.getAccountInfo(market.receiptState));
+  });
+});
+
+describe("rewards on a transfer hook market", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const hookProgram = anchor.workspace.ReceiptHook as Program<ReceiptHook>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  let market: Market;
+  let receiptAccount: PublicKey;
+  let rewardPool: PublicKey;
+  let stakeVault: PublicKey;
+
+  const stakeAccounts = () => ({
+    owner: payer.publicKey,
+    receiptState: market.receiptState,
+    stakePosition: getStakePositionPDA(market.receiptState, payer.publicKey, program.programId),
+    ownerCryptoReceiptTokenAccount: receiptAccount,
+    stakeVault,
+    cryptoReceiptMint: market.cryptoReceiptMint,
+    cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+  });
+  const position = () => getUserPositionPDA(market.receiptState, payer.publicKey, program.programId);
+
+  before(async () => {
+    market = await createMarket(program, payer, 9, TOKEN_2022_PROGRAM_ID, { transferHook: true });
+    ({ userCryptoReceiptTokenAccount: receiptAccount } = await fundUser(
+      program,
+      payer,
+      market,
+      payer.publicKey,
+      1_000_000
+    ));
+    await program.methods
+      .openUserPosition()
+      .accountsPartial({ owner: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+    await program.methods
+      .deposit(new BN(1_000_000))
+      .accountsPartial({ ...depositAccounts(market, payer.publicKey), recipientPosition: position() })
+      .rpc();
+
+    const rewardMint = await createMint(provider.connection, payer, payer.publicKey, null, 6);
+    rewardPool = getRewardPoolPDA(market.receiptState, rewardMint, program.programId);
+    stakeVault = getStakeVaultPDA(market.receiptState, program.programId);
+    await program.methods
+      .addRewardPool()
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        rewardMint,
+        rewardPool,
+        rewardVault: getRewardVaultPDA(rewardPool, program.programId),
+        cryptoReceiptMint: market.cryptoReceiptMint,
+        stakeVault,
+        rewardMintProgram: TOKEN_PROGRAM_ID,
+        cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    // Only the staker is allowlisted, the market's own vaults never need to be
+    const hookConfig = getHookConfigPDA(market.cryptoReceiptMint);
+    await hookProgram.methods
+      .setAllowlistEnabled(true)
+      .accountsPartial({ authority: payer.publicKey, hookConfig })
+      .rpc();
+    await hookProgram.methods
+      .addToAllowlist()
+      .accountsPartial({ authority: payer.publicKey, hookConfig, wallet: payer.publicKey })
+      .rpc();
+  });
+
+  it("stakes and unstakes through the transfer hook", async () => {
+    const poolAccount = { pubkey: rewardPool, isSigner: false, isWritable: true };
+    await program.methods
+      .stake(new BN(400_000))
+      .accountsPartial(stakeAccounts())
+      .remainingAccounts([
+        poolAccount,
+        ...(await transferHookAccounts(
+          provider.connection,
+          market.cryptoReceiptMint,
+          receiptAccount,
+          stakeVault,
+          payer.publicKey,
+          400_000
+        )),
+      ])
+      .rpc();
+    const vault = await getAccount(provider.connection, stakeVault, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.equal(vault.amount.toString(), "400000");
+
+    await program.methods
+      .unstake(new BN(400_000))
+      .accountsPartial({ ...stakeAccounts(), vaultAuthority: market.vaultAuthority })
+      .remainingAccounts([
+        poolAccount,
+        ...(await transferHookAccounts(
+          provider.connection,
+          market.cryptoReceiptMint,
+          stakeVault,
+          receiptAccount,
+          market.vaultAuthority,
+          400_000
+        )),
+      ])
+      .rpc();
+
+    // Staking is custody, the cost basis never left the owner's position
+    const userPosition = await program.account.userPosition.fetch(position());
+    assert.equal(userPosition.receipts.toNumber(), 1_000_000);
+    const receipts = await getAccount(provider.connection, receiptAccount, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.equal(receipts.amount.toString(), "1000000");
+  });
+});
@@ -0,0 +1,215 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import {
+  NATIVE_MINT,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createSyncNativeInstruction,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import stakePoolMintAuthority from "./fixtures/stake-pool-mint-authority.json";
+import { createMarket, deposit, expectError, fundUser, getMarketPDAs, getStakePoolVaultPDA, Market } from "./utils";
+
+// An SPL stake pool worth 1.1 SOL per pool token and its pool mint, loaded
+// into the local validator from tests/fixtures. The pool's last update epoch
+// is far ahead so it counts as current for the whole run.
+const STAKE_POOL = new PublicKey("EAJAWZLRnpqnAjy5JidKFMbLabwgBeCTB39sVBescT6C");
+const POOL_MINT = new PublicKey("Cp32N86FYaHvfZESm164eaWZmmA2zJyz2sj1uALDcCDs");
+
+describe("stake pool deposits", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  const mintAuthority = Keypair.fromSecretKey(Uint8Array.from(stakePoolMintAuthority));
+  const market: Market = getMarketPDAs(NATIVE_MINT, program.programId);
+  let stakePoolVault: PublicKey;
+  let poolTokenAccount: PublicKey;
+  let receiptAccount: PublicKey;
+
+  const depositPoolTokens = (amount: number, stakePool = STAKE_POOL) =>
+    program.methods
+      .depositStakePoolTokens(new BN(amount))
+      .accountsPartial({
+        user: payer.publicKey,
+        userPoolTokenAccount: poolTokenAccount,
+        userCryptoReceiptTokenAccount: receiptAccount,
+        receiptState: market.receiptState,
+        stakePool,
+        poolMint: POOL_MINT,
+        stakePoolVault,
+        tokenMintVault: market.tokenMintVault,
+        cryptoReceiptMint: market.cryptoReceiptMint,
+        vaultAuthority: market.vaultAuthority,
+        poolMintProgram: TOKEN_PROGRAM_ID,
+        cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+  const receiptBalance = async () =>
+    Number((await getAccount(provider.connection, receiptAccount, undefined, TOKEN_2022_PROGRAM_ID)).amount);
+
+  before(async () => {
+    await program.methods
+      .initialize("Crypto Receipt SOL", "CR-SOL", "https://example.com/token", false, null)
+      .accountsPartial({
+        authority: payer.publicKey,
+        tokenMint: NATIVE_MINT,
+        tokenMintProgram: TOKEN_PROGRAM_ID,
+        cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+        metadata: null,
+        metadataProgram: null,
+        transferHookProgram: null,
+        extraAccountMetaList: null,
+        hookConfig: null,
+      })
+      .rpc();
+    ({ userCryptoReceiptTokenAccount: receiptAccount } = await fundUser(
+      program,
+      payer,
+      market,
+      payer.publicKey,
+      0
+    ));
+
+    stakePoolVault = getStakePoolVaultPDA(market.receiptState, POOL_MINT, program.programId);
+    poolTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, payer, POOL_MINT, payer.publicKey)
+    ).address;
+    await mintTo(provider.connection, payer, POOL_MINT, poolTokenAccount, mintAuthority, 10 * LAMPORTS_PER_SOL);
+  });
+
+  it("only takes stake pools in SOL markets", async () => {
+    const other = await createMarket(program, payer);
+    await expectError(
+      program.methods
+        .setStakePool()
+        .accountsPartial({
+          authority: payer.publicKey,
+          receiptState: other.receiptState,
+          stakePool: STAKE_POOL,
+          poolMint: POOL_MINT,
+          stakePoolVault: getStakePoolVaultPDA(other.receiptState, POOL_MINT, program.programId),
+          vaultAuthority: other.vaultAuthority,
+          poolMintProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc(),
+      "InvalidStakePool"
+    );
+  });
+
+  it("accepts the stake pool's token", async () => {
+    await program.methods
+      .setStakePool()
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        stakePool: STAKE_POOL,
+        poolMint: POOL_MINT,
+        stakePoolVault,
+        vaultAuthority: market.vaultAuthority,
+        poolMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.isTrue(state.stakePool.equals(STAKE_POOL));
+    assert.isTrue(state.stakePoolVault.equals(stakePoolVault));
+  });
+
+  it("mints receipts for the SOL the pool tokens are worth", async () => {
+    await depositPoolTokens(LAMPORTS_PER_SOL);
+
+    assert.equal(await receiptBalance(), 1.1 * LAMPORTS_PER_SOL);
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.equal(state.stakePoolValue.toNumber(), 1.1 * LAMPORTS_PER_SOL);
+    // Priced at the pool's update epoch, far ahead of the validator's
+    assert.isFalse(state.stakePoolValueEpoch.isZero());
+  });
+
+  it("prices SOL deposits against the pool token backing", async () => {
+    const wrappedSol = getAssociatedTokenAddressSync(NATIVE_MINT, payer.publicKey);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: payer.publicKey,
+          toPubkey: wrappedSol,
+          lamports: 1.1 * LAMPORTS_PER_SOL,
+        }),
+        createSyncNativeInstruction(wrappedSol)
+      )
+    );
+    const before = await receiptBalance();
+    await deposit(program, market, payer, 1.1 * LAMPORTS_PER_SOL);
+
+    assert.equal((await receiptBalance()) - before, 1.1 * LAMPORTS_PER_SOL);
+  });
+
+  it("refreshes the pool token value", async () => {
+    await program.methods
+      .refreshStakePoolValue()
+      .accountsPartial({ receiptState: market.receiptState, stakePool: STAKE_POOL, stakePoolVault })
+      .rpc();
+
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.equal(state.stakePoolValue.toNumber(), 1.1 * LAMPORTS_PER_SOL);
+  });
+
+  it("takes a single stake pool", async () => {
+    await expectError(
+      program.methods
+        .setStakePool()
+        .accountsPartial({
+          authority: payer.publicKey,
+          receiptState: market.receiptState,
+          stakePool: STAKE_POOL,
+          poolMint: POOL_MINT,
+          stakePoolVault,
+          vaultAuthority: market.vaultAuthority,
+          poolMintProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc(),
+      "InvalidStakePool"
+    );
+  });
+
+  it("rejects another stake pool", async () => {
+    await expectError(depositPoolTokens(LAMPORTS_PER_SOL, Keypair.generate().publicKey), "ConstraintHasOne");
+  });
+
+  it("redeems receipts for pool tokens and zeroes the value once the vault is empty", async () => {
+    const poolTokenBalance = async () => Number((await getAccount(provider.connection, poolTokenAccount)).amount);
+    const receiptsBefore = await receiptBalance();
+    const poolTokensBefore = await poolTokenBalance();
+
+    await program.methods
+      .redeemStakePoolTokens(new BN(1.1 * LAMPORTS_PER_SOL))
+      .accountsPartial({
+        user: payer.publicKey,
+        userCryptoReceiptTokenAccount: receiptAccount,
+        userPoolTokenAccount: poolTokenAccount,
+        receiptState: market.receiptState,
+        stakePool: STAKE_POOL,
+        poolMint: POOL_MINT,
+        stakePoolVault,
+        tokenMintVault: market.tokenMintVault,
+        cryptoReceiptMint: market.cryptoReceiptMint,
+        vaultAuthority: market.vaultAuthority,
+        poolMintProgram: TOKEN_PROGRAM_ID,
+        cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    assert.equal(receiptsBefore - (await receiptBalance()), 1.1 * LAMPORTS_PER_SOL);
+    assert.equal((await poolTokenBalance()) - poolTokensBefore, LAMPORTS_PER_SOL);
+    assert.equal(Number((await getAccount(provider.connection, stakePoolVault)).amount), 0);
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.equal(state.stakePoolValue.toNumber(), 0);
+  });
+});
@@ -0,0 +1,108 @@
+// The swap tests only run when the program is built with the swap CPI enabled,
+// which `cargo make test_program` does:
+//   anchor test -- --features jupiter-cpi
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import {
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import { MockSwap } from "../target/types/mock_swap";
+import { createMarket, depositAccounts, expectError, fundUser, Market } from "./utils";
+
+describe("swap and deposit", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const mockSwap = anchor.workspace.MockSwap as Program<MockSwap>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  const [poolAuthority] = PublicKey.findProgramAddressSync(
+    [Buffer.from("pool_authority")],
+    mockSwap.programId
+  );
+  let market: Market;
+  let inputMint: PublicKey;
+  let userInput: PublicKey;
+
+  const swapAndDeposit = async (amountIn: number, amountOut: number, minAmountOut: number) => {
+    const accounts = depositAccounts(market, payer.publicKey);
+    const poolSource = await getOrCreateAssociatedTokenAccount(
+      provider.connection, payer, inputMint, poolAuthority, true
+    );
+    const poolDestination = await getOrCreateAssociatedTokenAccount(
+      provider.connection, payer, market.tokenMint, poolAuthority, true
+    );
+    const swapIx = await mockSwap.methods
+      .swap(new BN(amountIn), new BN(amountOut))
+      .accountsPartial({
+        user: payer.publicKey,
+        userSource: userInput,
+        userDestination: accounts.userMintTokenAccount,
+        poolSource: poolSource.address,
+        poolDestination: poolDestination.address,
+        sourceMint: inputMint,
+        destinationMint: market.tokenMint,
+      })
+      .instruction();
+    return program.methods
+      .swapAndDeposit(new BN(amountIn), new BN(minAmountOut), swapIx.data)
+      .accountsPartial({
+        deposit: accounts,
+        userInputTokenAccount: userInput,
+        swapProgram: mockSwap.programId,
+      })
+      .remainingAccounts(swapIx.keys)
+      .rpc();
+  };
+
+  before(async () => {
+    market = await createMarket(program, payer);
+    await fundUser(program, payer, market, payer.publicKey, 0);
+    inputMint = await createMint(provider.connection, payer, payer.publicKey, null, 6);
+    userInput = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, payer, inputMint, payer.publicKey)
+    ).address;
+    await mintTo(provider.connection, payer, inputMint, userInput, payer, 1_000_000_000);
+    const poolDestination = await getOrCreateAssociatedTokenAccount(
+      provider.connection, payer, market.tokenMint, poolAuthority, true
+    );
+    await mintTo(provider.connection, payer, market.tokenMint, poolDestination.address, payer, 10_000_000_000);
+  });
+
+  it("rejects swap programs that are not configured", async () => {
+    await expectError(swapAndDeposit(1_000_000, 500_000_000, 1), "InvalidSwapProgram");
+  });
+
+  describe("with the swap program configured", () => {
+    before(async function () {
+      await program.methods
+        .setSwapProgram(mockSwap.programId)
+        .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+        .rpc();
+      // Without the feature the handler fails before looking at the amount
+      try {
+        await swapAndDeposit(0, 0, 0);
+      } catch (e) {
+        if (e instanceof anchor.AnchorError && e.error.errorCode.code === "SwapNotEnabled") {
+          this.skip();
+        }
+      }
+    });
+
+    it("swaps the input token and deposits the output", async () => {
+      await swapAndDeposit(1_000_000, 500_000_000, 490_000_000);
+
+      const vault = await getAccount(provider.connection, market.tokenMintVault);
+      assert.equal(vault.amount.toString(), "500000000");
+    });
+
+    it("enforces the minimum output", async () => {
+      await expectError(swapAndDeposit(1_000_000, 400_000_000, 490_000_000), "SlippageExceeded");
+    });
+  });
+});
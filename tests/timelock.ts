@@ -0,0 +1,205 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import { createMarket, expectError, fundUser, getInsuranceVaultPDA, Market } from "./utils";
+
+describe("timelock", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  const guardian = Keypair.generate();
+  const feeReceiver = Keypair.generate().publicKey;
+  let market: Market;
+
+  const proposalPDA = (id: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), market.receiptState.toBuffer(), new BN(id).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  const propose = (id: number, change: any) =>
+    program.methods
+      .proposeParamChange(change)
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        proposal: proposalPDA(id),
+      })
+      .rpc();
+
+  const execute = (id: number) =>
+    program.methods
+      .executeProposal()
+      .accountsPartial({
+        receiptState: market.receiptState,
+        proposal: proposalPDA(id),
+        proposer: payer.publicKey,
+        payer: null,
+        foreignEmitter: null,
+      })
+      .rpc();
+
+  before(async () => {
+    market = await createMarket(program, payer);
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(guardian.publicKey, LAMPORTS_PER_SOL)
+    );
+    await program.methods
+      .setTimelock(guardian.publicKey, new BN(2))
+      .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+  });
+
+  it("blocks direct admin changes once timelocked", async () => {
+    await expectError(
+      program.methods
+        .setFeeConfig(100, 0, feeReceiver)
+        .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+        .rpc(),
+      "TimelockActive"
+    );
+  });
+
+  it("executes a proposal after its eta", async () => {
+    await propose(0, {
+      feeConfig: { depositFeeBps: 100, referralFeeShareBps: 0, feeReceiver },
+    });
+    await expectError(execute(0), "ProposalNotReady");
+
+    await new Promise((resolve) => setTimeout(resolve, 3_000));
+    await execute(0);
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.equal(state.depositFeeBps, 100);
+    assert.ok(state.feeReceiver.equals(feeReceiver));
+    assert.isNull(await provider.connection.getAccountInfo(proposalPDA(0)));
+  });
+
+  it("lets the guardian cancel a proposal", async () => {
+    await propose(1, { authority: [guardian.publicKey] });
+    const stranger = Keypair.generate();
+    await expectError(
+      program.methods
+        .cancelProposal()
+        .accountsPartial({
+          canceller: stranger.publicKey,
+          receiptState: market.receiptState,
+          proposal: proposalPDA(1),
+          proposer: payer.publicKey,
+        })
+        .signers([stranger])
+        .rpc(),
+      "Unauthorized"
+    );
+    await program.methods
+      .cancelProposal()
+      .accountsPartial({
+        canceller: guardian.publicKey,
+        receiptState: market.receiptState,
+        proposal: proposalPDA(1),
+        proposer: payer.publicKey,
+      })
+      .signers([guardian])
+      .rpc();
+    assert.isNull(await provider.connection.getAccountInfo(proposalPDA(1)));
+  });
+
+  it("rejects invalid proposals up front", async () => {
+    await expectError(
+      propose(2, { flashLoanConfig: { enabled: true, feeBps: 10_001 } }),
+      "InvalidBasisPoints"
+    );
+  });
+
+  it("keeps admin instructions that only add to the market immediate", async () => {
+    const insuranceVault = getInsuranceVaultPDA(market.receiptState, program.programId);
+    await program.methods
+      .initInsuranceVault()
+      .accountsPartial({
+        authority: payer.publicKey,
+        receiptState: market.receiptState,
+        tokenMint: market.tokenMint,
+        insuranceVault,
+        vaultAuthority: market.vaultAuthority,
+        tokenMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.ok(state.insuranceVault.equals(insuranceVault));
+  });
+
+  it("registers foreign emitters through a proposal", async () => {
+    const chain = 2;
+    const address = Keypair.generate().publicKey.toBuffer();
+    const foreignEmitter = PublicKey.findProgramAddressSync(
+      [Buffer.from("foreign_emitter"), market.receiptState.toBuffer(), new BN(chain).toArrayLike(Buffer, "le", 2)],
+      program.programId
+    )[0];
+    await expectError(
+      program.methods
+        .registerForeignEmitter(chain, Array.from(address))
+        .accountsPartial({ authority: payer.publicKey, receiptState: market.receiptState })
+        .rpc(),
+      "TimelockActive"
+    );
+
+    await propose(2, { foreignEmitter: { chain, address: Array.from(address) } });
+    await new Promise((resolve) => setTimeout(resolve, 3_000));
+    await expectError(execute(2), "MissingForeignEmitter");
+    await program.methods
+      .executeProposal()
+      .accountsPartial({
+        receiptState: market.receiptState,
+        proposal: proposalPDA(2),
+        proposer: payer.publicKey,
+        payer: payer.publicKey,
+        foreignEmitter,
+      })
+      .rpc();
+    const emitter = await program.account.foreignEmitter.fetch(foreignEmitter);
+    assert.equal(emitter.chain, chain);
+    assert.deepEqual(Buffer.from(emitter.address), address);
+  });
+
+  it("moves funds only with the arguments a proposal approved", async () => {
+    const insuranceVault = getInsuranceVaultPDA(market.receiptState, program.programId);
+    const { userMintTokenAccount } = await fundUser(program, payer, market, payer.publicKey, 100_000);
+    await program.methods
+      .fundInsurance(new BN(100_000))
+      .accountsPartial({
+        funder: payer.publicKey,
+        funderTokenAccount: userMintTokenAccount,
+        receiptState: market.receiptState,
+        tokenMint: market.tokenMint,
+        insuranceVault,
+        tokenMintProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    const coverLoss = (amount: number) =>
+      program.methods
+        .coverLoss(new BN(amount))
+        .accountsPartial({
+          authority: payer.publicKey,
+          receiptState: market.receiptState,
+          tokenMint: market.tokenMint,
+          tokenMintVault: market.tokenMintVault,
+          insuranceVault,
+          vaultAuthority: market.vaultAuthority,
+          tokenMintProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    await expectError(coverLoss(50_000), "AdminActionNotApproved");
+
+    await propose(3, { adminAction: [{ coverLoss: { amount: new BN(50_000) } }] });
+    await new Promise((resolve) => setTimeout(resolve, 3_000));
+    await execute(3);
+    await expectError(coverLoss(40_000), "AdminActionNotApproved");
+    await coverLoss(50_000);
+    const state = await program.account.receiptState.fetch(market.receiptState);
+    assert.equal(state.insuranceLossesCovered.toNumber(), 50_000);
+    await expectError(coverLoss(50_000), "AdminActionNotApproved");
+  });
+});
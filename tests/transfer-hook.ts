@@ -0,0 +1,138 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, Transaction } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  createTransferCheckedWithTransferHookInstruction,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import { ReceiptHook } from "../target/types/receipt_hook";
+import {
+  createMarket,
+  depositAccounts,
+  expectError,
+  fundUser,
+  getAllowlistPDA,
+  getHookConfigPDA,
+  getUserPositionPDA,
+  Market,
+} from "./utils";
+
+describe("transfer hook", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const hookProgram = anchor.workspace.ReceiptHook as Program<ReceiptHook>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  const receiver = Keypair.generate();
+  let market: Market;
+
+  const position = (owner: PublicKey) =>
+    getUserPositionPDA(market.receiptState, owner, program.programId);
+
+  const transferReceipts = async (amount: number) => {
+    const ix = await createTransferCheckedWithTransferHookInstruction(
+      provider.connection,
+      getAssociatedTokenAddressSync(market.cryptoReceiptMint, payer.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      market.cryptoReceiptMint,
+      getAssociatedTokenAddressSync(market.cryptoReceiptMint, receiver.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      payer.publicKey,
+      BigInt(amount),
+      9,
+      [],
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    return provider.sendAndConfirm(new Transaction().add(ix));
+  };
+
+  before(async () => {
+    market = await createMarket(program, payer, 9, TOKEN_2022_PROGRAM_ID, { transferHook: true });
+    await fundUser(program, payer, market, payer.publicKey, 10_000_000);
+    await fundUser(program, payer, market, receiver.publicKey, 0);
+    for (const owner of [payer.publicKey, receiver.publicKey]) {
+      await program.methods
+        .openUserPosition()
+        .accountsPartial({ owner, receiptState: market.receiptState })
+        .rpc();
+    }
+    await program.methods
+      .deposit(new BN(1_000_000))
+      .accountsPartial({
+        ...depositAccounts(market, payer.publicKey),
+        recipientPosition: position(payer.publicKey),
+      })
+      .rpc();
+  });
+
+  it("moves cost basis with transferred receipts", async () => {
+    await transferReceipts(400_000);
+
+    const sender = await program.account.userPosition.fetch(position(payer.publicKey));
+    assert.equal(sender.receipts.toNumber(), 600_000);
+    assert.equal(sender.costBasis.toNumber(), 600_000);
+    const recipient = await program.account.userPosition.fetch(position(receiver.publicKey));
+    assert.equal(recipient.receipts.toNumber(), 400_000);
+    assert.equal(recipient.costBasis.toNumber(), 400_000);
+    assert.equal(recipient.entryExchangeRate.toString(), "1000000000000");
+  });
+
+  it("enforces the allowlist once enabled", async () => {
+    const hookConfig = getHookConfigPDA(market.cryptoReceiptMint);
+    await hookProgram.methods
+      .setAllowlistEnabled(true)
+      .accountsPartial({ authority: payer.publicKey, hookConfig })
+      .rpc();
+    await expectError(transferReceipts(1_000), "NotAllowlisted");
+
+    for (const wallet of [payer.publicKey, receiver.publicKey]) {
+      await hookProgram.methods
+        .addToAllowlist()
+        .accountsPartial({ authority: payer.publicKey, hookConfig, wallet })
+        .rpc();
+    }
+    await transferReceipts(1_000);
+
+    await hookProgram.methods
+      .removeFromAllowlist()
+      .accountsPartial({
+        authority: payer.publicKey,
+        hookConfig,
+        allowlistEntry: getAllowlistPDA(market.cryptoReceiptMint, receiver.publicKey),
+      })
+      .rpc();
+    await expectError(transferReceipts(1_000), "NotAllowlisted");
+  });
+
+  it("hands the allowlist to a new authority", async () => {
+    const hookConfig = getHookConfigPDA(market.cryptoReceiptMint);
+    const newAuthority = Keypair.generate();
+    await hookProgram.methods
+      .setHookAuthority(newAuthority.publicKey)
+      .accountsPartial({ authority: payer.publicKey, hookConfig })
+      .rpc();
+    await expectError(
+      hookProgram.methods
+        .setAllowlistEnabled(false)
+        .accountsPartial({ authority: payer.publicKey, hookConfig })
+        .rpc(),
+      "ConstraintHasOne"
+    );
+
+    await hookProgram.methods
+      .setAllowlistEnabled(false)
+      .accountsPartial({ authority: newAuthority.publicKey, hookConfig })
+      .signers([newAuthority])
+      .rpc();
+    assert.isFalse((await hookProgram.account.hookConfig.fetch(hookConfig)).allowlistEnabled);
+  });
+
+  it("rejects hooks on classic spl token receipt mints", async () => {
+    await expectError(
+      createMarket(program, payer, 9, anchor.utils.token.TOKEN_PROGRAM_ID, { transferHook: true }),
+      "InvalidTransferHook"
+    );
+  });
+});
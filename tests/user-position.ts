@@ -0,0 +1,92 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair } from "@solana/web3.js";
+import { getAssociatedTokenAddressSync, transfer } from "@solana/spl-token";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import {
+  createMarket,
+  deposit,
+  depositAccounts,
+  expectError,
+  fundUser,
+  getUserPositionPDA,
+  Market,
+  redeem,
+} from "./utils";
+
+describe("user positions", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = provider.wallet.payer;
+  let market: Market;
+  let position: anchor.web3.PublicKey;
+
+  before(async () => {
+    market = await createMarket(program, payer);
+    await fundUser(program, payer, market, payer.publicKey, 10_000_000);
+    position = getUserPositionPDA(market.receiptState, payer.publicKey, program.programId);
+    await program.methods
+      .openUserPosition()
+      .accountsPartial({ owner: payer.publicKey, receiptState: market.receiptState })
+      .rpc();
+  });
+
+  it("tracks cost basis across deposits and redemptions", async () => {
+    await program.methods
+      .deposit(new BN(1_000_000))
+      .accountsPartial({ ...depositAccounts(market, payer.publicKey), recipientPosition: position })
+      .rpc();
+
+    let state = await program.account.userPosition.fetch(position);
+    assert.equal(state.totalDeposited.toNumber(), 1_000_000);
+    assert.equal(state.receipts.toNumber(), 1_000_000);
+    assert.equal(state.costBasis.toNumber(), 1_000_000);
+    assert.equal(state.entryExchangeRate.toString(), "1000000000000");
+
+    // Donate to the vault so each receipt is worth two underlying.
+    await transfer(
+      provider.connection,
+      payer,
+      getAssociatedTokenAddressSync(market.tokenMint, payer.publicKey),
+      market.tokenMintVault,
+      payer,
+      1_000_000
+    );
+
+    await program.methods
+      .deposit(new BN(1_000_000))
+      .accountsPartial({ ...depositAccounts(market, payer.publicKey), recipientPosition: position })
+      .rpc();
+    state = await program.account.userPosition.fetch(position);
+    assert.equal(state.receipts.toNumber(), 1_500_000);
+    assert.equal(state.costBasis.toNumber(), 2_000_000);
+    assert.equal(state.entryExchangeRate.toString(), "1333333333333");
+
+    await redeem(program, market, payer, 750_000, position);
+    state = await program.account.userPosition.fetch(position);
+    assert.equal(state.totalRedeemed.toNumber(), 1_500_000);
+    assert.equal(state.receipts.toNumber(), 750_000);
+    assert.equal(state.costBasis.toNumber(), 1_000_000);
+    assert.equal(state.realizedYield.toNumber(), 500_000);
+  });
+
+  it("updates an open position whether or not the caller names it", async () => {
+    const before = await program.account.userPosition.fetch(position);
+    await deposit(program, market, payer, 1_000);
+    const after = await program.account.userPosition.fetch(position);
+    assert.equal(after.totalDeposited.toNumber() - before.totalDeposited.toNumber(), 1_000);
+  });
+
+  it("rejects another owner's position", async () => {
+    const recipient = Keypair.generate().publicKey;
+    await expectError(
+      program.methods
+        .deposit(new BN(1_000))
+        .accountsPartial({ ...depositAccounts(market, payer.publicKey, recipient), recipientPosition: position })
+        .rpc(),
+      "ConstraintSeeds"
+    );
+  });
+});
@@ -0,0 +1,440 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { AccountMeta, PublicKey, Keypair, TransactionInstruction } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  addExtraAccountMetasForExecute,
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { ReceiptMoney } from "../target/types/receipt_money";
+
+export interface Market {
+  tokenMint: PublicKey;
+  receiptState: PublicKey;
+  vaultAuthority: PublicKey;
+  tokenMintVault: PublicKey;
+  cryptoReceiptMint: PublicKey;
+  cryptoReceiptMintVault: PublicKey;
+}
+
+export const RECEIPT_MONEY_PROGRAM_ID = new PublicKey("RMcr2nvyrwCh89SvH47916S9TCvPkoGBPNR8E1d1LWa");
+
+export const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
+
+export function getMetaplexMetadataPDA(mint: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+}
+
+export const RECEIPT_HOOK_PROGRAM_ID = new PublicKey(
+  "AWNcxfuBcvrdsXRRALHoPihuHTXrYsVGKrvw3uA9Wrra"
+);
+
+export function getExtraAccountMetaListPDA(mint: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("extra-account-metas"), mint.toBuffer()],
+    RECEIPT_HOOK_PROGRAM_ID
+  )[0];
+}
+
+export function getHookConfigPDA(mint: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("hook_config"), mint.toBuffer()],
+    RECEIPT_HOOK_PROGRAM_ID
+  )[0];
+}
+
+export function getAllowlistPDA(mint: PublicKey, wallet: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("allowlist"), mint.toBuffer(), wallet.toBuffer()],
+    RECEIPT_HOOK_PROGRAM_ID
+  )[0];
+}
+
+export function getMarketPDAs(tokenMint: PublicKey, programId: PublicKey): Market {
+  const [receiptState] = PublicKey.findProgramAddressSync(
+    [Buffer.from("receipt_state"), tokenMint.toBuffer()],
+    programId
+  );
+  const [vaultAuthority] = PublicKey.findProgramAddressSync(
+    [Buffer.from("receipt_vault_authority"), receiptState.toBuffer()],
+    programId
+  );
+  const [tokenMintVault] = PublicKey.findProgramAddressSync(
+    [Buffer.from("receipt_mint_vault"), receiptState.toBuffer(), tokenMint.toBuffer()],
+    programId
+  );
+  const [cryptoReceiptMint] = PublicKey.findProgramAddressSync(
+    [Buffer.from("receipt_mint"), receiptState.toBuffer()],
+    programId
+  );
+  const [cryptoReceiptMintVault] = PublicKey.findProgramAddressSync(
+    [Buffer.from("receipt_mint_vault"), receiptState.toBuffer(), cryptoReceiptMint.toBuffer()],
+    programId
+  );
+  return {
+    tokenMint,
+    receiptState,
+    vaultAuthority,
+    tokenMintVault,
+    cryptoReceiptMint,
+    cryptoReceiptMintVault,
+  };
+}
+
+/** Optional Token-2022 features of the receipt mint. */
+export interface ReceiptMintOptions {
+  transferHook?: boolean;
+  interestBearing?: boolean;
+  confidentialTransferAuditor?: number[];
+}
+
+/**
+ * Creates a fresh SPL token mint and initializes a receipt market for it.
+ */
+export async function createMarket(
+  program: Program<ReceiptMoney>,
+  payer: Keypair,
+  decimals = 9,
+  cryptoReceiptMintProgram = TOKEN_2022_PROGRAM_ID,
+  { transferHook = false, interestBearing = false, confidentialTransferAuditor = null }: ReceiptMintOptions = {}
+): Promise<Market> {
+  const provider = program.provider as anchor.AnchorProvider;
+  const tokenMint = await createMint(
+    provider.connection,
+    payer,
+    payer.publicKey,
+    null,
+    decimals,
+    undefined,
+    undefined,
+    TOKEN_PROGRAM_ID
+  );
+  const market = getMarketPDAs(tokenMint, program.programId);
+  await program.methods
+    .initialize(
+      "Crypto Receipt Token",
+      "CRT",
+      "https://example.com/token",
+      interestBearing,
+      confidentialTransferAuditor
+    )
+    .accountsPartial({
+      authority: payer.publicKey,
+      tokenMint,
+      tokenMintProgram: TOKEN_PROGRAM_ID,
+      cryptoReceiptMintProgram,
+      metadata: cryptoReceiptMintProgram.equals(TOKEN_PROGRAM_ID)
+        ? getMetaplexMetadataPDA(market.cryptoReceiptMint)
+        : null,
+      metadataProgram: cryptoReceiptMintProgram.equals(TOKEN_PROGRAM_ID)
+        ? TOKEN_METADATA_PROGRAM_ID
+        : null,
+      transferHookProgram: transferHook ? RECEIPT_HOOK_PROGRAM_ID : null,
+      extraAccountMetaList: transferHook
+        ? getExtraAccountMetaListPDA(market.cryptoReceiptMint)
+        : null,
+      hookConfig: transferHook ? getHookConfigPDA(market.cryptoReceiptMint) : null,
+    })
+    .signers([payer])
+    .rpc();
+  return market;
+}
+
+/**
+ * Funds `owner` with underlying tokens and creates its receipt token account.
+ */
+export async function fundUser(
+  program: Program<ReceiptMoney>,
+  payer: Keypair,
+  market: Market,
+  owner: PublicKey,
+  amount: number
+): Promise<{ userMintTokenAccount: PublicKey; userCryptoReceiptTokenAccount: PublicKey }> {
+  const connection = program.provider.connection;
+  const userMintTokenAccount = (
+    await getOrCreateAssociatedTokenAccount(connection, payer, market.tokenMint, owner)
+  ).address;
+  if (amount > 0) {
+    await mintTo(connection, payer, market.tokenMint, userMintTokenAccount, payer, amount);
+  }
+  await getOrCreateAssociatedTokenAccount(
+    connection,
+    payer,
+    market.cryptoReceiptMint,
+    owner,
+    false,
+    undefined,
+    undefined,
+    TOKEN_2022_PROGRAM_ID
+  );
+  const userCryptoReceiptTokenAccount = getAssociatedTokenAddressSync(
+    market.cryptoReceiptMint,
+    owner,
+    false,
+    TOKEN_2022_PROGRAM_ID
+  );
+  return { userMintTokenAccount, userCryptoReceiptTokenAccount };
+}
+
+/**
+ * Accounts of the `deposit` instruction for `user` depositing into `market`
+ * on behalf of `recipient`.
+ */
+export function depositAccounts(market: Market, user: PublicKey, recipient: PublicKey = user) {
+  return {
+    user,
+    userMintTokenAccount: getAssociatedTokenAddressSync(market.tokenMint, user),
+    recipient,
+    recipientCryptoReceiptTokenAccount: getAssociatedTokenAddressSync(
+      market.cryptoReceiptMint,
+      recipient,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    ),
+    receiptState: market.receiptState,
+    tokenMint: market.tokenMint,
+    vaultAuthority: market.vaultAuthority,
+    tokenMintVault: market.tokenMintVault,
+    cryptoReceiptMint: market.cryptoReceiptMint,
+    cryptoReceiptMintVault: market.cryptoReceiptMintVault,
+    tokenMintProgram: TOKEN_PROGRAM_ID,
+    cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+    feeReceiver: null as PublicKey | null,
+    referralStats: null as PublicKey | null,
+    referrerTokenAccount: null as PublicKey | null,
+    recipientPosition: getUserPositionPDA(market.receiptState, recipient, RECEIPT_MONEY_PROGRAM_ID),
+    insuranceVault: null as PublicKey | null,
+    stakePool: null as PublicKey | null,
+    stakePoolVault: null as PublicKey | null,
+  };
+}
+
+export function getReferralStatsPDA(
+  receiptState: PublicKey,
+  referrer: PublicKey,
+  programId: PublicKey
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("referral_stats"), receiptState.toBuffer(), referrer.toBuffer()],
+    programId
+  )[0];
+}
+
+export function getUserPositionPDA(
+  receiptState: PublicKey,
+  owner: PublicKey,
+  programId: PublicKey
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("user_position"), receiptState.toBuffer(), owner.toBuffer()],
+    programId
+  )[0];
+}
+
+export function getRewardPoolPDA(
+  receiptState: PublicKey,
+  rewardMint: PublicKey,
+  programId: PublicKey
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("reward_pool"), receiptState.toBuffer(), rewardMint.toBuffer()],
+    programId
+  )[0];
+}
+
+export function getRewardVaultPDA(rewardPool: PublicKey, programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([Buffer.from("reward_vault"), rewardPool.toBuffer()], programId)[0];
+}
+
+export function getStakeVaultPDA(receiptState: PublicKey, programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([Buffer.from("stake_vault"), receiptState.toBuffer()], programId)[0];
+}
+
+export function getStakePositionPDA(
+  receiptState: PublicKey,
+  owner: PublicKey,
+  programId: PublicKey
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("stake_position"), receiptState.toBuffer(), owner.toBuffer()],
+    programId
+  )[0];
+}
+
+export function getDistributionPDA(receiptState: PublicKey, id: number, programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("distribution"), receiptState.toBuffer(), new BN(id).toArrayLike(Buffer, "le", 8)],
+    programId
+  )[0];
+}
+
+export function getDistributionVaultPDA(distribution: PublicKey, programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("distribution_vault"), distribution.toBuffer()],
+    programId
+  )[0];
+}
+
+export function getLendingReservePDA(
+  receiptState: PublicKey,
+  borrowMint: PublicKey,
+  programId: PublicKey
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("lending_reserve"), receiptState.toBuffer(), borrowMint.toBuffer()],
+    programId
+  )[0];
+}
+
+export function getReserveLiquidityPDA(reserve: PublicKey, programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([Buffer.from("reserve_liquidity"), reserve.toBuffer()], programId)[0];
+}
+
+export function getReserveCollateralPDA(reserve: PublicKey, programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([Buffer.from("reserve_collateral"), reserve.toBuffer()], programId)[0];
+}
+
+export function getObligationPDA(reserve: PublicKey, owner: PublicKey, programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("obligation"), reserve.toBuffer(), owner.toBuffer()],
+    programId
+  )[0];
+}
+
+export function getStakePoolVaultPDA(
+  receiptState: PublicKey,
+  poolMint: PublicKey,
+  programId: PublicKey
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("stake_pool_vault"), receiptState.toBuffer(), poolMint.toBuffer()],
+    programId
+  )[0];
+}
+
+export function getInsuranceVaultPDA(receiptState: PublicKey, programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([Buffer.from("insurance_vault"), receiptState.toBuffer()], programId)[0];
+}
+
+export function getBridgeEscrowPDA(receiptState: PublicKey, programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([Buffer.from("bridge_escrow"), receiptState.toBuffer()], programId)[0];
+}
+
+export function getForeignEmitterPDA(receiptState: PublicKey, chain: number, programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("foreign_emitter"), receiptState.toBuffer(), new BN(chain).toArrayLike(Buffer, "le", 2)],
+    programId
+  )[0];
+}
+
+/**
+ * Deposits `amount` of underlying for `user` into `market`.
+ */
+export async function deposit(
+  program: Program<ReceiptMoney>,
+  market: Market,
+  user: Keypair,
+  amount: number,
+  recipient: PublicKey = user.publicKey
+) {
+  return program.methods
+    .deposit(new BN(amount))
+    .accountsPartial(depositAccounts(market, user.publicKey, recipient))
+    .signers([user])
+    .rpc();
+}
+
+/**
+ * Redeems `receiptAmount` of `user`'s receipts from `market`.
+ */
+export async function redeem(
+  program: Program<ReceiptMoney>,
+  market: Market,
+  user: Keypair,
+  receiptAmount: number,
+  userPosition: PublicKey = getUserPositionPDA(market.receiptState, user.publicKey, program.programId)
+) {
+  return program.methods
+    .redeem(new BN(receiptAmount))
+    .accountsPartial({
+      user: user.publicKey,
+      userMintTokenAccount: getAssociatedTokenAddressSync(market.tokenMint, user.publicKey),
+      userCryptoReceiptTokenAccount: getAssociatedTokenAddressSync(
+        market.cryptoReceiptMint,
+        user.publicKey,
+        false,
+        TOKEN_2022_PROGRAM_ID
+      ),
+      receiptState: market.receiptState,
+      tokenMint: market.tokenMint,
+      vaultAuthority: market.vaultAuthority,
+      tokenMintVault: market.tokenMintVault,
+      cryptoReceiptMint: market.cryptoReceiptMint,
+      tokenMintProgram: TOKEN_PROGRAM_ID,
+      cryptoReceiptMintProgram: TOKEN_2022_PROGRAM_ID,
+      userPosition,
+      stakePool: null,
+      stakePoolVault: null,
+    })
+    .signers([user])
+    .rpc();
+}
+
+/**
+ * Remaining accounts receipt_money needs to move `amount` receipts of a
+ * transfer hook `mint` from `source` to `destination` on behalf of `owner`.
+ */
+export async function transferHookAccounts(
+  connection: anchor.web3.Connection,
+  mint: PublicKey,
+  source: PublicKey,
+  destination: PublicKey,
+  owner: PublicKey,
+  amount: number
+): Promise<AccountMeta[]> {
+  const transfer = new TransactionInstruction({
+    programId: TOKEN_2022_PROGRAM_ID,
+    keys: [source, mint, destination, owner].map((pubkey) => ({
+      pubkey,
+      isSigner: false,
+      isWritable: false,
+    })),
+  });
+  await addExtraAccountMetasForExecute(
+    connection,
+    transfer,
+    RECEIPT_HOOK_PROGRAM_ID,
+    source,
+    mint,
+    destination,
+    owner,
+    amount,
+    "confirmed"
+  );
+  return transfer.keys.slice(4);
+}
+
+export async function expectError(promise: Promise<unknown>, code: string) {
+  try {
+    await promise;
+  } catch (e) {
+    const message =
+      e instanceof anchor.AnchorError
+        ? e.error.errorCode.code
+        : `${e} ${(e as { logs?: string[] }).logs?.join("\n") ?? ""}`;
+    if (!message.includes(code)) {
+      throw new Error(`expected ${code}, got ${message}`);
+    }
+    return;
+  }
+  throw new Error(`expected ${code}, but the call succeeded`);
+}
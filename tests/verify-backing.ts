@@ -0,0 +1,39 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { ReceiptMoney } from "../target/types/receipt_money";
+import { createMarket, deposit, fundUser, Market } from "./utils";
+
+describe("verify backing", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.ReceiptMoney as Program<ReceiptMoney>;
+  const payer = (program.provider as anchor.AnchorProvider).wallet.payer;
+  let market: Market;
+
+  const verifyBacking = () =>
+    program.methods
+      .verifyBacking()
+      .accountsPartial({
+        receiptState: market.receiptState,
+        tokenMintVault: market.tokenMintVault,
+        cryptoReceiptMint: market.cryptoReceiptMint,
+      })
+      .view();
+
+  before(async () => {
+    market = await createMarket(program, payer);
+  });
+
+  it("reports an empty market as fully backed", async () => {
+    const report = await verifyBacking();
+    assert.equal(report.receiptSupply.toNumber(), 0);
+  });
+
+  it("reports a 1:1 ratio after deposits", async () => {
+    await fundUser(program, payer, market, payer.publicKey, 1_000_000_000);
+    await deposit(program, market, payer, 1_000_000_000);
+    const report = await verifyBacking();
+    assert.equal(report.totalUnderlying.toNumber(), 1_000_000_000);
+    assert.equal(report.collateralizationBps.toNumber(), 10_000);
+  });
+});
@@ -0,0 +1,272 @@
+//! Off-chain builder for `receipt_money` merkle distributions.
+//!
+//! Leaves and inner nodes are hashed with the program's own
+//! `merkle_leaf`/`merkle_node`, so every proof produced here verifies in `claim`.
+
+use std::{collections::HashMap, error::Error, str::FromStr};
+
+use anchor_lang::prelude::Pubkey;
+use receipt_money::{
+    state::Distribution,
+    utils::{merkle_leaf, merkle_node},
+};
+use serde::Serialize;
+
+/// One row of the holder snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Holder {
+    pub wallet: Pubkey,
+    pub amount: u64,
+}
+
+/// A holder's claim, ready to be passed to `claim`.
+#[derive(Debug, Serialize)]
+pub struct ClaimProof {
+    pub index: u64,
+    pub wallet: String,
+    pub amount: u64,
+    pub proof: Vec<[u8; 32]>,
+}
+
+/// Everything `create_distribution` and the claimants need.
+#[derive(Debug, Serialize)]
+pub struct DistributionTree {
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub num_nodes: u64,
+    pub claims: Vec<ClaimProof>,
+}
+
+/// Parse `wallet,amount` rows. Blank lines, `#` comments and a header row are
+/// skipped, and a wallet listed more than once has its amounts summed.
+pub fn parse_holders(csv: &str) -> Result<Vec<Holder>, Box<dyn Error>> {
+    let mut holders: Vec<Holder> = Vec::new();
+    // Position of each wallet in `holders`, which keeps the order of first appearance
+    let mut indices: HashMap<Pubkey, usize> = HashMap::new();
+    for (line_number, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (wallet, amount) = line
+            .split_once(',')
+            .ok_or_else(|| format!("line {}: expected `wallet,amount`", line_number + 1))?;
+        let (wallet, amount) = (wallet.trim(), amount.trim());
+        let Ok(wallet) = Pubkey::from_str(wallet) else {
+            if line_number == 0 {
+                continue;
+            }
+            return Err(format!("line {}: invalid wallet `{}`", line_number + 1, wallet).into());
+        };
+        let amount: u64 = amount
+            .parse()
+            .map_err(|_| format!("line {}: invalid amount `{}`", line_number + 1, amount))?;
+        match indices.get(&wallet) {
+            Some(&index) => {
+                let holder = &mut holders[index];
+                holder.amount = holder
+                    .amount
+                    .checked_add(amount)
+                    .ok_or_else(|| format!("line {}: amount overflow", line_number + 1))?;
+            }
+            None => {
+                indices.insert(wallet, holders.len());
+                holders.push(Holder { wallet, amount });
+            }
+        }
+    }
+    holders.retain(|holder| holder.amount > 0);
+    Ok(holders)
+}
+
+/// Build the tree over `holders`, leaf `i` paying `holders[i]`. A node without
+/// a sibling moves up a level unchanged.
+pub fn build_tree(holders: &[Holder]) -> Result<DistributionTree, Box<dyn Error>> {
+    if holders.is_empty() {
+        return Err("no holders to distribute to".into());
+    }
+    if holders.len() as u64 > Distribution::MAX_NODES {
+        return Err(format!(
+            "{} holders, a distribution takes at most {}",
+            holders.len(),
+            Distribution::MAX_NODES
+        )
+        .into());
+    }
+    let total_amount = holders
+        .iter()
+        .try_fold(0u64, |total, holder| total.checked_add(holder.amount))
+        .ok_or("total amount overflows u64")?;
+
+    let mut levels = vec![holders
+        .iter()
+        .enumerate()
+        .map(|(index, holder)| merkle_leaf(index as u64, &holder.wallet, holder.amount))
+        .collect::<Vec<_>>()];
+    while levels.last().is_some_and(|level| level.len() > 1) {
+        let level = levels.last().unwrap();
+        let parents = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => merkle_node(left, right),
+                [single] => *single,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(parents);
+    }
+
+    let claims = holders
+        .iter()
+        .enumerate()
+        .map(|(index, holder)| ClaimProof {
+            index: index as u64,
+            wallet: holder.wallet.to_string(),
+            amount: holder.amount,
+            proof: proof(&levels, index),
+        })
+        .collect();
+    Ok(DistributionTree {
+        merkle_root: levels.last().unwrap()[0],
+        total_amount,
+        num_nodes: holders.len() as u64,
+        claims,
+    })
+}
+
+fn proof(levels: &[Vec<[u8; 32]>], mut index: usize) -> Vec<[u8; 32]> {
+    let mut proof = Vec::new();
+    for level in &levels[..levels.len() - 1] {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        index /= 2;
+    }
+    proof
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use receipt_money::utils::verify_merkle_proof;
+
+    fn holders(count: usize) -> Vec<Holder> {
+        (0..count)
+            .map(|index| Holder {
+                wallet: Pubkey::new_unique(),
+                amount: 1_000 * (index as u64 + 1),
+            })
+            .collect()
+    }
+
+    fn assert_proofs_verify(holders: &[Holder], tree: &DistributionTree) {
+        for (claim, holder) in tree.claims.iter().zip(holders) {
+            let leaf = merkle_leaf(claim.index, &holder.wallet, holder.amount);
+            assert!(
+                verify_merkle_proof(&claim.proof, &tree.merkle_root, leaf),
+                "proof of leaf {} in a tree of {}",
+                claim.index,
+                holders.len()
+            );
+        }
+    }
+
+    #[test]
+    fn parse_holders_skips_header_comments_and_blank_lines() {
+        let wallet = Pubkey::new_unique();
+        let csv = format!("wallet,amount\n\n# snapshot at slot 1\n {wallet} , 42 \n");
+        assert_eq!(parse_holders(&csv).unwrap(), vec![Holder { wallet, amount: 42 }]);
+    }
+
+    #[test]
+    fn parse_holders_only_skips_a_header_on_the_first_line() {
+        let csv = format!("{},1\nwallet,amount\n", Pubkey::new_unique());
+        assert!(parse_holders(&csv).is_err());
+        assert!(parse_holders("not-a-row\n").is_err());
+        assert!(parse_holders(&format!("{},lots\n", Pubkey::new_unique())).is_err());
+    }
+
+    #[test]
+    fn parse_holders_merges_duplicates_and_drops_zero_amounts() {
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let csv = format!("{first},10\n{second},0\n{first},5\n");
+        assert_eq!(
+            parse_holders(&csv).unwrap(),
+            vec![Holder { wallet: first, amount: 15 }]
+        );
+        let third = Pubkey::new_unique();
+        let csv = format!("{second},1\n{third},2\n{second},3\n{first},4\n");
+        assert_eq!(
+            parse_holders(&csv).unwrap(),
+            vec![
+                Holder { wallet: second, amount: 4 },
+                Holder { wallet: third, amount: 2 },
+                Holder { wallet: first, amount: 4 },
+            ]
+        );
+        let overflow = format!("{first},{}\n{first},1\n", u64::MAX);
+        assert!(parse_holders(&overflow).is_err());
+    }
+
+    #[test]
+    fn build_tree_rejects_no_holders() {
+        assert!(build_tree(&[]).is_err());
+    }
+
+    #[test]
+    fn build_tree_rejects_more_holders_than_a_distribution_takes() {
+        let max = Distribution::MAX_NODES as usize;
+        assert!(build_tree(&holders(max + 1)).is_err());
+        assert_eq!(build_tree(&holders(max)).unwrap().num_nodes, Distribution::MAX_NODES);
+    }
+
+    #[test]
+    fn build_tree_of_one_holder_is_its_leaf() {
+        let holders = holders(1);
+        let tree = build_tree(&holders).unwrap();
+        assert_eq!(
+            tree.merkle_root,
+            merkle_leaf(0, &holders[0].wallet, holders[0].amount)
+        );
+        assert!(tree.claims[0].proof.is_empty());
+        assert_eq!(tree.total_amount, 1_000);
+        assert_eq!(tree.num_nodes, 1);
+    }
+
+    #[test]
+    fn build_tree_promotes_the_odd_node() {
+        let holders = holders(3);
+        let tree = build_tree(&holders).unwrap();
+        let leaves: Vec<_> = holders
+            .iter()
+            .enumerate()
+            .map(|(index, holder)| merkle_leaf(index as u64, &holder.wallet, holder.amount))
+            .collect();
+        let left = merkle_node(&leaves[0], &leaves[1]);
+        assert_eq!(tree.merkle_root, merkle_node(&left, &leaves[2]));
+        assert_eq!(tree.claims[2].proof, vec![left]);
+        assert_eq!(tree.claims[0].proof, vec![leaves[1], leaves[2]]);
+        assert_eq!(tree.total_amount, 6_000);
+    }
+
+    #[test]
+    fn every_proof_verifies_on_chain() {
+        for count in 1..=17 {
+            let holders = holders(count);
+            let tree = build_tree(&holders).unwrap();
+            assert_eq!(tree.claims.len(), count);
+            assert_proofs_verify(&holders, &tree);
+        }
+    }
+
+    #[test]
+    fn proofs_do_not_verify_other_claims() {
+        let holders = holders(5);
+        let tree = build_tree(&holders).unwrap();
+        let claim = &tree.claims[3];
+        let inflated = merkle_leaf(claim.index, &holders[3].wallet, holders[3].amount + 1);
+        assert!(!verify_merkle_proof(&claim.proof, &tree.merkle_root, inflated));
+        let moved = merkle_leaf(4, &holders[3].wallet, holders[3].amount);
+        assert!(!verify_merkle_proof(&claim.proof, &tree.merkle_root, moved));
+    }
+}
@@ -0,0 +1,28 @@
+use std::{env, fs, process};
+
+use merkle_tree::{build_tree, parse_holders};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        eprintln!("usage: {} <holders.csv> <tree.json>", args[0]);
+        process::exit(2);
+    }
+    if let Err(err) = run(&args[1], &args[2]) {
+        eprintln!("error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run(csv_path: &str, out_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let holders = parse_holders(&fs::read_to_string(csv_path)?)?;
+    let tree = build_tree(&holders)?;
+    fs::write(out_path, serde_json::to_string_pretty(&tree)?)?;
+    println!(
+        "{} holders, {} total, root {}",
+        tree.num_nodes,
+        tree.total_amount,
+        tree.merkle_root.iter().map(|byte| format!("{byte:02x}")).collect::<String>()
+    );
+    Ok(())
+}